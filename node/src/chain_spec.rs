@@ -152,5 +152,6 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		omniverse_swap: Default::default(),
 	}
 }
@@ -0,0 +1,20 @@
+//! Runtime API definition for the omniverse assets pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use pallet_assets::DelayedTx;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait OmniverseAssetsApi {
+		/// The omniverse balance of `pk` in the token `token_id`
+		fn token_balance(token_id: Vec<u8>, pk: [u8; 64]) -> u128;
+
+		/// The name, symbol and decimals of the token `token_id`
+		fn token_metadata(token_id: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>, u8)>;
+
+		/// The delayed transactions waiting to be executed in order: the index in the queue, the
+		/// transaction and the timestamp in seconds from which it is executable
+		fn pending_delayed() -> Vec<(u32, DelayedTx, u64)>;
+	}
+}
@@ -20,7 +20,10 @@
 use super::traits::OmniverseTokenFactoryHandler;
 use super::*;
 use codec::Decode;
-use frame_support::{traits::Get, BoundedVec};
+use frame_support::{
+	traits::{Get, UnixTime},
+	BoundedVec,
+};
 use pallet_omniverse_protocol::{
 	traits::OmniverseAccounts,
 	types::{Fungible, OmniverseTransactionData, VerifyError, VerifyResult, BURN, MINT, TRANSFER},
@@ -63,6 +66,38 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Account::<T, I>::get(id, who.borrow()).map(|a| a.balance)
 	}
 
+	/// Get the omniverse balance of `pk` in the token `token_id`.
+	pub fn token_balance(token_id: Vec<u8>, pk: [u8; 64]) -> u128 {
+		Tokens::<T, I>::get(token_id, pk)
+	}
+
+	/// Get the name, symbol and decimals of the token `token_id` if they are set.
+	pub fn token_metadata(token_id: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>, u8)> {
+		let id = TokenId2AssetId::<T, I>::get(token_id)?;
+		let metadata = Metadata::<T, I>::try_get(id).ok()?;
+		Some((metadata.name.into_inner(), metadata.symbol.into_inner(), metadata.decimals))
+	}
+
+	/// The delayed transactions waiting to be executed in order, with their index in the queue
+	/// and the timestamp in seconds from which they are executable. A transaction whose record is
+	/// not found is left out.
+	pub fn pending_delayed() -> Vec<(u32, DelayedTx, u64)> {
+		let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
+		(delayed_executing_index..delayed_index)
+			.filter_map(|index| {
+				let delayed_tx = DelayedTransactions::<T, I>::get(index)?;
+				let omni_tx = T::OmniverseProtocol::get_transaction_data(
+					delayed_tx.sender,
+					PALLET_NAME.to_vec(),
+					delayed_tx.token_id.clone(),
+					delayed_tx.nonce,
+				)?;
+				let cooldown_time = TokensInfo::<T, I>::get(&delayed_tx.token_id)?.cooldown_time;
+				Some((index, delayed_tx, omni_tx.timestamp + cooldown_time))
+			})
+			.collect()
+	}
+
 	/// Get the total supply of an asset `id`.
 	pub fn total_supply(id: T::AssetId) -> T::Balance {
 		Self::maybe_total_supply(id).unwrap_or_default()
@@ -875,6 +910,12 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		omniverse_token: OmniverseToken<T::AccountId>,
 		data: &OmniverseTransactionData,
 	) -> Result<FactoryResult, DispatchError> {
+		// Bound the work spent on the payload before it is verified and decoded
+		ensure!(
+			data.payload.len() <= T::MaxPayloadSize::get() as usize,
+			Error::<T, I>::PayloadTooLarge
+		);
+
 		// Check if the tx destination is correct
 		ensure!(
 			omniverse_token.is_member(&(data.chain_id, data.initiator_address.clone()))
@@ -885,12 +926,17 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		// Check if the sender is honest
 		ensure!(!T::OmniverseProtocol::is_malicious(data.from), Error::<T, I>::UserIsMalicious);
 
+		// Transactions of the other member chains are relayed here on purpose
+		let relayed = data.chain_id != T::OmniverseProtocol::get_chain_id()
+			&& omniverse_token.is_member(&(data.chain_id, data.initiator_address.clone()));
+
 		// Verify the signature
 		let ret = T::OmniverseProtocol::verify_transaction(
 			PALLET_NAME.as_ref(),
 			&omniverse_token.token_id,
 			data,
 			false,
+			relayed,
 		);
 		let ret = match ret {
 			Err(_) => T::OmniverseProtocol::verify_transaction(
@@ -898,6 +944,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				&omniverse_token.token_id,
 				data,
 				true,
+				relayed,
 			),
 			_ => ret,
 		};
@@ -920,13 +967,20 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				return Err(Error::<T, I>::ProtocolSignerNotCaller.into())
 			},
 			Err(VerifyError::NonceError) => return Err(Error::<T, I>::ProtocolNonceError.into()),
+			Err(VerifyError::WrongChain) => return Err(Error::<T, I>::ProtocolWrongChain.into()),
 			Ok(VerifyResult::Success) => {
 				// Verify balance
-				{
+				let (op, amount) = {
 					let id = TokenId2AssetId::<T, I>::get(&omniverse_token.token_id)
 						.ok_or(Error::<T, I>::Unknown)?;
 					let fungible = Fungible::decode(&mut data.payload.as_slice())
 						.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
+					// A frozen token can still be burned
+					ensure!(
+						fungible.op == BURN
+							|| !FrozenTokens::<T, I>::get(&omniverse_token.token_id),
+						Error::<T, I>::TokenFrozen
+					);
 					let amount = T::Balance::try_from(fungible.amount)
 						.unwrap_or(<T as Config<I>>::Balance::default());
 					let dest_pk: [u8; 64] = fungible
@@ -950,7 +1004,12 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 						let details = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
 						ensure!(source == details.issuer, Error::<T, I>::NoPermission);
 					} else if fungible.op == BURN {
-						let f = DebitFlags { keep_alive: false, best_effort: true };
+						ensure!(
+							Tokens::<T, I>::get(&omniverse_token.token_id, &dest_pk)
+								>= fungible.amount,
+							Error::<T, I>::BurnExceedsBalance
+						);
+						let f = DebitFlags { keep_alive: false, best_effort: false };
 						let actual = Self::prep_debit(id, &dest, amount, f)?;
 						let details = Asset::<T, I>::get(id).ok_or(Error::<T, I>::Unknown)?;
 						ensure!(source == details.issuer, Error::<T, I>::NoPermission);
@@ -960,11 +1019,22 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					} else {
 						return Err(Error::<T, I>::UnknownProtocolType.into());
 					}
-				}
+					(fungible.op, fungible.amount)
+				};
 				let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
+				ensure!(
+					delayed_index - delayed_executing_index < T::MaxDelayedQueue::get(),
+					Error::<T, I>::DelayedQueueFull
+				);
 				DelayedTransactions::<T, I>::insert(
 					delayed_index,
-					DelayedTx::new(data.from, omniverse_token.token_id.clone(), data.nonce),
+					DelayedTx::new(
+						data.from,
+						omniverse_token.token_id.clone(),
+						data.nonce,
+						op,
+						amount,
+					),
 				);
 				DelayedIndex::<T, I>::set((delayed_executing_index, delayed_index + 1));
 				Self::deposit_event(Event::TransactionSent {
@@ -978,10 +1048,69 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(FactoryResult::Success)
 	}
 
+	/// Execute the delayed transaction at the head of the queue if its cooling down time has
+	/// elapsed
+	pub(super) fn execute_next_delayed_transaction() -> DispatchResult {
+		let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
+		ensure!(delayed_executing_index < delayed_index, Error::<T, I>::NoDelayedTx);
+
+		let delayed_tx = DelayedTransactions::<T, I>::get(delayed_executing_index)
+			.ok_or(Error::<T, I>::DelayedTxNotExisted)?;
+		let omni_tx = T::OmniverseProtocol::get_transaction_data(
+			delayed_tx.sender,
+			PALLET_NAME.to_vec(),
+			delayed_tx.token_id.clone(),
+			delayed_tx.nonce,
+		)
+		.ok_or(Error::<T, I>::TxNotExisted)?;
+		let omniverse_token =
+			TokensInfo::<T, I>::get(&delayed_tx.token_id).ok_or(Error::<T, I>::Unknown)?;
+		let cur_st = T::Timestamp::now().as_secs();
+		ensure!(
+			cur_st >= omni_tx.timestamp + omniverse_token.cooldown_time,
+			Error::<T, I>::NotExecutable
+		);
+
+		DelayedIndex::<T, I>::set((delayed_executing_index + 1, delayed_index));
+
+		// A transaction failing to execute is skipped so that it does not block the queue, a
+		// record mismatching the queue is kept to be executed once it is fixed
+		let result = frame_support::storage::with_storage_layer(|| {
+			Self::execute_transaction(&delayed_tx, &omni_tx.tx_data)
+		});
+		match result {
+			Ok(()) => {},
+			Err(e) if e == DispatchError::from(Error::<T, I>::DelayedTxMismatch) => return Err(e),
+			Err(reason) => {
+				Self::deposit_event(Event::TransactionExecutionFailed {
+					pk: delayed_tx.sender,
+					nonce: delayed_tx.nonce,
+					token_id: delayed_tx.token_id,
+					reason,
+				});
+				return Ok(());
+			},
+		}
+		T::OmniverseProtocol::execute(
+			delayed_tx.sender,
+			PALLET_NAME.to_vec(),
+			delayed_tx.token_id.clone(),
+			delayed_tx.nonce,
+		);
+		Self::deposit_event(Event::TransactionExecuted {
+			pk: delayed_tx.sender,
+			nonce: delayed_tx.nonce,
+			token_id: delayed_tx.token_id,
+		});
+
+		Ok(())
+	}
+
 	pub(super) fn execute_transaction(
-		token_id: &Vec<u8>,
+		delayed_tx: &DelayedTx,
 		data: &OmniverseTransactionData,
 	) -> Result<(), DispatchError> {
+		let token_id = &delayed_tx.token_id;
 		let omniverse_token = TokensInfo::<T, I>::get(token_id).ok_or(Error::<T, I>::Unknown)?;
 
 		// Execute
@@ -989,6 +1118,11 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		// let transfer_data = TransferTokenOp::decode(&mut data.op_data.as_slice()).unwrap();
 		let fungible = Fungible::decode(&mut data.payload.as_slice())
 			.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
+		// The recorded transaction must be the one that was enqueued
+		ensure!(
+			fungible.op == delayed_tx.op && fungible.amount == delayed_tx.amount,
+			Error::<T, I>::DelayedTxMismatch
+		);
 		// let dest_pk: [u8; 64] = data
 		// 	.op_data
 		// 	.clone()
@@ -997,7 +1131,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		// Convert public key to account id
 		let origin = Self::to_account(&data.from)?;
 		let amount =
-			T::Balance::try_from(fungible.amount).unwrap_or(<T as Config<I>>::Balance::default());
+			T::Balance::try_from(delayed_tx.amount).unwrap_or(<T as Config<I>>::Balance::default());
 		let id = TokenId2AssetId::<T, I>::get(token_id).ok_or(Error::<T, I>::Unknown)?;
 		let dest_pk: [u8; 64] = fungible
 			.ex_data
@@ -1005,25 +1139,25 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			.map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
 		let dest = Self::to_account(&dest_pk)?;
 
-		if fungible.op == TRANSFER {
-			Self::omniverse_transfer(omniverse_token, data.from, dest_pk, fungible.amount)?;
+		if delayed_tx.op == TRANSFER {
+			Self::omniverse_transfer(omniverse_token, data.from, dest_pk, delayed_tx.amount)?;
 			let f = TransferFlags { keep_alive: false, best_effort: false, burn_dust: false };
 			Self::do_transfer(id, &origin, &dest, amount, None, f)?;
-		} else if fungible.op == MINT {
+		} else if delayed_tx.op == MINT {
 			// let mint_data = MintTokenOp::decode(&mut data.op_data.as_slice()).unwrap();
 			if data.from != omniverse_token.owner_pk {
 				return Err(Error::<T, I>::SignerNotOwner.into());
 			}
-			Self::omniverse_mint(omniverse_token, dest_pk, fungible.amount);
+			Self::omniverse_mint(omniverse_token, dest_pk, delayed_tx.amount)?;
 			Self::do_mint(id, &dest, amount, Some(origin))?;
-		} else if fungible.op == BURN {
+		} else if delayed_tx.op == BURN {
 			if data.from != omniverse_token.owner_pk {
 				return Err(Error::<T, I>::SignerNotOwner.into());
 			}
-			let f = DebitFlags { keep_alive: false, best_effort: true };
+			Self::omniverse_burn(omniverse_token, dest_pk, delayed_tx.amount)?;
+			let f = DebitFlags { keep_alive: false, best_effort: false };
 			// let _ = Self::do_burn(id, &who, amount, Some(origin), f)?;
 			let _ = Self::do_burn(id, &dest, amount, None, f)?;
-			Self::omniverse_burn(omniverse_token, dest_pk, fungible.amount);
 		}
 
 		Ok(())
@@ -1040,8 +1174,10 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			return Err(Error::<T, I>::BalanceLow.into());
 		} else {
 			Tokens::<T, I>::insert(&omniverse_token.token_id, &from, from_balance - amount);
-			let to_balance = Tokens::<T, I>::get(&omniverse_token.token_id, &to);
-			Tokens::<T, I>::insert(&omniverse_token.token_id, &to, to_balance + amount);
+			let to_balance = Tokens::<T, I>::get(&omniverse_token.token_id, &to)
+				.checked_add(amount)
+				.ok_or(ArithmeticError::Overflow)?;
+			Tokens::<T, I>::insert(&omniverse_token.token_id, &to, to_balance);
 		}
 		Ok(())
 	}
@@ -1050,18 +1186,23 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		omniverse_token: OmniverseToken<T::AccountId>,
 		to: [u8; 64],
 		amount: u128,
-	) {
-		let balance = Tokens::<T, I>::get(&omniverse_token.token_id, &to);
-		Tokens::<T, I>::insert(&omniverse_token.token_id, &to, balance + amount);
+	) -> Result<(), DispatchError> {
+		let balance = Tokens::<T, I>::get(&omniverse_token.token_id, &to)
+			.checked_add(amount)
+			.ok_or(ArithmeticError::Overflow)?;
+		Tokens::<T, I>::insert(&omniverse_token.token_id, &to, balance);
+		Ok(())
 	}
 
 	pub(super) fn omniverse_burn(
 		omniverse_token: OmniverseToken<T::AccountId>,
 		account: [u8; 64],
 		amount: u128,
-	) {
+	) -> Result<(), DispatchError> {
 		let balance = Tokens::<T, I>::get(&omniverse_token.token_id, &account);
+		ensure!(balance >= amount, Error::<T, I>::BurnExceedsBalance);
 		Tokens::<T, I>::insert(&omniverse_token.token_id, &account, balance - amount);
+		Ok(())
 	}
 
 	pub(super) fn to_account(public_key: &[u8; 64]) -> Result<T::AccountId, Error<T, I>> {
@@ -1072,7 +1213,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			.map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
 		let public_key_compressed = public_key.serialize();
 		let hash = BlakeTwo256::hash(&public_key_compressed);
-		Ok(T::AccountId::decode(&mut &hash[..]).unwrap())
+		T::AccountId::decode(&mut &hash[..]).map_err(|_| Error::<T, I>::AccountConversionFailed)
 	}
 }
 
@@ -1084,8 +1225,15 @@ impl<T: Config<I>, I: 'static> OmniverseTokenFactoryHandler for Pallet<T, I> {
 		// Check if the token exists.
 		let token = TokensInfo::<T, I>::get(token_id).ok_or(Error::<T, I>::Unknown)?;
 
-		Self::handle_transaction(token, data)?;
+		Self::handle_transaction(token, data)
+	}
 
-		Ok(FactoryResult::Success)
+	fn decimals(token_id: &Vec<u8>) -> Option<u8> {
+		let id = TokenId2AssetId::<T, I>::get(token_id)?;
+		Metadata::<T, I>::try_get(id).ok().map(|metadata| metadata.decimals)
+	}
+
+	fn balance_of(token_id: &Vec<u8>, pk: &[u8; 64]) -> u128 {
+		Tokens::<T, I>::get(token_id, pk)
 	}
 }
@@ -129,6 +129,7 @@ mod benchmarking;
 pub mod mock;
 #[cfg(test)]
 mod tests;
+pub mod migration;
 pub mod weights;
 
 mod extra_mutator;
@@ -156,6 +157,7 @@ use frame_support::{
 	pallet_prelude::DispatchResultWithPostInfo,
 	traits::{
 		tokens::{fungibles, DepositConsequence, WithdrawConsequence},
+		BalanceStatus::Reserved,
 		Currency, ReservableCurrency, StoredMap,
 	},
 };
@@ -178,8 +180,12 @@ pub mod pallet {
 	use pallet_omniverse_protocol::{traits::OmniverseAccounts, OmniverseTransactionData};
 	use traits::OmniverseTokenFactoryHandler;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T, I = ()>(_);
 
@@ -249,6 +255,22 @@ pub mod pallet {
 		#[pallet::constant]
 		type StringLimit: Get<u32>;
 
+		/// The maximum length of the payload of an omniverse transaction.
+		#[pallet::constant]
+		type MaxPayloadSize: Get<u32>;
+
+		/// The maximum number of delayed transactions waiting to be executed.
+		#[pallet::constant]
+		type MaxDelayedQueue: Get<u32>;
+
+		/// The maximum length of an omniverse token ID.
+		#[pallet::constant]
+		type MaxTokenIdLength: Get<u32>;
+
+		/// The maximum number of members of an omniverse token.
+		#[pallet::constant]
+		type MaxMembers: Get<u32>;
+
 		/// A hook to allow a per-asset, per-account minimum balance to be enforced. This must be
 		/// respected in all permissionless operations.
 		type Freezer: FrozenBalance<Self::AssetId, Self::AccountId, Self::Balance>;
@@ -357,6 +379,12 @@ pub mod pallet {
 	pub type TokenId2AssetId<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, Vec<u8>, T::AssetId>;
 
+	/// The omniverse tokens frozen by their owners, their transfers and mints are rejected
+	#[pallet::storage]
+	#[pallet::getter(fn is_token_frozen)]
+	pub type FrozenTokens<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, bool, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		/// Genesis assets: id, owner, is_sufficient, min_balance
@@ -556,10 +584,28 @@ pub mod pallet {
 			token_id: Vec<u8>,
 			members: Vec<(u32, Vec<u8>)>,
 		},
+		MembersRemoved {
+			token_id: Vec<u8>,
+			members: Vec<(u32, Vec<u8>)>,
+		},
 		CooldownTimeSet {
 			token_id: Vec<u8>,
 			cooldown_time: u64,
 		},
+		/// The ownership of an omniverse token was transferred, with its asset.
+		TokenOwnershipTransferred {
+			token_id: Vec<u8>,
+			owner: T::AccountId,
+			owner_pk: [u8; 64],
+		},
+		/// An omniverse token was frozen by its owner.
+		TokenFrozen {
+			token_id: Vec<u8>,
+		},
+		/// An omniverse token was thawed by its owner.
+		TokenThawed {
+			token_id: Vec<u8>,
+		},
 		TransactionSent {
 			pk: [u8; 64],
 			token_id: Vec<u8>,
@@ -572,6 +618,22 @@ pub mod pallet {
 			token_id: Vec<u8>,
 		},
 
+		/// An omniverse transaction failed to execute, it is reverted and skipped.
+		TransactionExecutionFailed {
+			pk: [u8; 64],
+			nonce: u128,
+			token_id: Vec<u8>,
+			reason: DispatchError,
+		},
+
+		/// A delayed transaction that can not be executed was skipped by the force origin.
+		DelayedTransactionSkipped {
+			index: u32,
+			pk: [u8; 64],
+			nonce: u128,
+			token_id: Vec<u8>,
+		},
+
 		TransactionDuplicated {
 			pk: [u8; 64],
 			nonce: u128,
@@ -622,17 +684,42 @@ pub mod pallet {
 		/// decode
 		DecodePayloadFailed,
 		SerializePublicKeyFailed,
+		/// The runtime account id can not be decoded from a public key
+		AccountConversionFailed,
 		WrongDestination,
 		UserIsMalicious,
 		SignerNotOwner,
 		ProtocolSignerNotCaller,
 		ProtocolSignatureError,
 		ProtocolNonceError,
+		/// The transaction was signed for another chain
+		ProtocolWrongChain,
 		NoDelayedTx,
 		TxNotExisted,
 		NotExecutable,
 		DelayedTxNotExisted,
 		UnknownProtocolType,
+		/// The omniverse token is frozen.
+		TokenFrozen,
+		/// The recorded transaction does not match the enqueued one
+		DelayedTxMismatch,
+		/// The omniverse balance is lower than the amount to burn
+		BurnExceedsBalance,
+		/// The payload of the transaction is longer than `MaxPayloadSize`
+		PayloadTooLarge,
+		/// There are `MaxDelayedQueue` delayed transactions waiting to be executed
+		DelayedQueueFull,
+		/// The token ID is longer than `MaxTokenIdLength`
+		TokenIdTooLong,
+		/// There are more than `MaxMembers` members of the token
+		TooManyMembers,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migration::migrate_to_v1::<T, I, Self>()
+		}
 	}
 
 	#[pallet::call]
@@ -1510,6 +1597,14 @@ pub mod pallet {
 
 			// Check if the token exists
 			ensure!(!TokensInfo::<T, I>::contains_key(&token_id), Error::<T, I>::InUse);
+			ensure!(
+				token_id.len() <= T::MaxTokenIdLength::get() as usize,
+				Error::<T, I>::TokenIdTooLong
+			);
+			ensure!(
+				members.as_ref().map_or(0, |m| m.len()) <= T::MaxMembers::get() as usize,
+				Error::<T, I>::TooManyMembers
+			);
 
 			// Convert public key to account id
 			let owner = Self::to_account(&owner_pk)?;
@@ -1522,7 +1617,7 @@ pub mod pallet {
 					owner_pk,
 					token_id.clone(),
 					members.clone(),
-					cooldown_time,
+					Some(cooldown_time.unwrap_or_else(T::OmniverseProtocol::get_cooling_down_time)),
 				),
 			);
 
@@ -1584,41 +1679,50 @@ pub mod pallet {
 		pub fn trigger_execution(origin: OriginFor<T>) -> DispatchResult {
 			ensure_signed(origin)?;
 
+			Self::execute_next_delayed_transaction()
+		}
+
+		/// Execute up to `max_count` delayed transactions in order, stopping at the first one
+		/// that is not executable yet. Fails only if the first one can not be executed.
+		#[pallet::weight(0)]
+		pub fn trigger_execution_batch(origin: OriginFor<T>, max_count: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			for i in 0..max_count.max(1) {
+				let result = frame_support::storage::with_storage_layer(|| {
+					Self::execute_next_delayed_transaction()
+				});
+				if let Err(e) = result {
+					if i == 0 {
+						return Err(e);
+					}
+					break;
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Skip the delayed transaction at the head of the queue without executing it, for a
+		/// transaction that keeps failing and blocks the ones after it.
+		///
+		/// Origin must be ForceOrigin.
+		#[pallet::weight(0)]
+		pub fn skip_delayed(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
 			let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
 			ensure!(delayed_executing_index < delayed_index, Error::<T, I>::NoDelayedTx);
-
 			let delayed_tx = DelayedTransactions::<T, I>::get(delayed_executing_index)
 				.ok_or(Error::<T, I>::DelayedTxNotExisted)?;
-			let omni_tx = T::OmniverseProtocol::get_transaction_data(
-				delayed_tx.sender,
-				PALLET_NAME.to_vec(),
-				delayed_tx.token_id.clone(),
-				delayed_tx.nonce,
-			)
-			.ok_or(Error::<T, I>::TxNotExisted)?;
-			let omniverse_token =
-				TokensInfo::<T, I>::get(&delayed_tx.token_id).ok_or(Error::<T, I>::Unknown)?;
-			let cur_st = T::Timestamp::now().as_secs();
-			ensure!(
-				cur_st >= omni_tx.timestamp + omniverse_token.cooldown_time,
-				Error::<T, I>::NotExecutable
-			);
-
 			DelayedIndex::<T, I>::set((delayed_executing_index + 1, delayed_index));
 
-			Self::execute_transaction(&delayed_tx.token_id, &omni_tx.tx_data)?;
-			T::OmniverseProtocol::execute(
-				delayed_tx.sender,
-				PALLET_NAME.to_vec(),
-				delayed_tx.token_id.clone(),
-				delayed_tx.nonce,
-			);
-			Self::deposit_event(Event::TransactionExecuted {
+			Self::deposit_event(Event::DelayedTransactionSkipped {
+				index: delayed_executing_index,
 				pk: delayed_tx.sender,
 				nonce: delayed_tx.nonce,
 				token_id: delayed_tx.token_id,
 			});
-
 			Ok(())
 		}
 
@@ -1634,6 +1738,7 @@ pub mod pallet {
 			let mut token = TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
 
 			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+			ensure!(members.len() <= T::MaxMembers::get() as usize, Error::<T, I>::TooManyMembers);
 
 			token.add_members(members.clone());
 
@@ -1648,6 +1753,35 @@ pub mod pallet {
 			Ok(())
 		}
 
+		#[pallet::weight(0)]
+		pub fn remove_members(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			members: Vec<(u32, Vec<u8>)>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let mut token = TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			token.remove_members(&members);
+
+			for member in members.iter() {
+				// The member may have been set for another token since
+				if TokenIdofMember::<T, I>::get(member).as_ref() == Some(&token_id) {
+					TokenIdofMember::<T, I>::remove(member);
+				}
+			}
+			// Update storage
+			TokensInfo::<T, I>::insert(&token_id, token);
+
+			Self::deposit_event(Event::MembersRemoved { token_id, members });
+
+			Ok(())
+		}
+
 		#[pallet::weight(0)]
 		pub fn set_cooldown_time(
 			origin: OriginFor<T>,
@@ -1670,5 +1804,104 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Transfer the ownership of the token to `new_owner_pk`, the asset of the token and its
+		/// deposits go along with it
+		#[pallet::weight(0)]
+		pub fn transfer_token_ownership(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			new_owner_pk: [u8; 64],
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let mut token = TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			let new_owner = Self::to_account(&new_owner_pk)?;
+			let id = TokenId2AssetId::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+			Asset::<T, I>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::Unknown)?;
+				let deposit = details.deposit.saturating_add(Metadata::<T, I>::get(id).deposit);
+				T::Currency::repatriate_reserved(&details.owner, &new_owner, deposit, Reserved)?;
+				details.owner = new_owner.clone();
+				details.issuer = new_owner.clone();
+				details.admin = new_owner.clone();
+				details.freezer = new_owner.clone();
+				Ok(())
+			})?;
+
+			token.owner = new_owner.clone();
+			token.owner_pk = new_owner_pk;
+			// Update storage
+			TokensInfo::<T, I>::insert(&token_id, token);
+
+			Self::deposit_event(Event::TokenOwnershipTransferred {
+				token_id,
+				owner: new_owner,
+				owner_pk: new_owner_pk,
+			});
+
+			Ok(())
+		}
+
+		/// Freeze the token, its transfers and mints are rejected until it is thawed
+		#[pallet::weight(0)]
+		pub fn freeze_token(origin: OriginFor<T>, token_id: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let token = TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			FrozenTokens::<T, I>::insert(&token_id, true);
+
+			Self::deposit_event(Event::TokenFrozen { token_id });
+
+			Ok(())
+		}
+
+		/// Thaw the token frozen by `freeze_token`
+		#[pallet::weight(0)]
+		pub fn thaw_token(origin: OriginFor<T>, token_id: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let token = TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			FrozenTokens::<T, I>::remove(&token_id);
+
+			Self::deposit_event(Event::TokenThawed { token_id });
+
+			Ok(())
+		}
+
+		/// Set the name, symbol and decimals of the token, they are stored as the metadata of its
+		/// asset and limited in length by `StringLimit`
+		///
+		/// Emits `MetadataSet`.
+		#[pallet::weight(T::WeightInfo::set_metadata(name.len() as u32, symbol.len() as u32))]
+		pub fn set_token_metadata(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let token = TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			let id = TokenId2AssetId::<T, I>::get(&token_id).ok_or(Error::<T, I>::Unknown)?;
+			Self::do_set_metadata(id, &sender, name, symbol, decimals)
+		}
 	}
 }
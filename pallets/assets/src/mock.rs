@@ -110,32 +110,40 @@ impl OmniverseAccounts for OmniverseProtocol {
 		_token_id: &[u8],
 		data: &OmniverseTransactionData,
 		_with_ethereum: bool,
+		_relayed: bool,
 	) -> Result<VerifyResult, VerifyError> {
 		if data.signature == [0; 65] {
 			return Err(VerifyError::SignatureError);
 		}
 
-		Ok(VerifyResult::Success)
+		Ok(VerifyOutcome::get())
 	}
 
 	fn get_transaction_count(_pk: [u8; 64], _pallet_name: Vec<u8>, _token_id: Vec<u8>) -> u128 {
 		0
 	}
 
-	fn is_malicious(_pk: [u8; 64]) -> bool {
-		false
+	fn is_malicious(pk: [u8; 64]) -> bool {
+		MaliciousPk::get() == Some(pk)
 	}
 
 	fn get_chain_id() -> u32 {
 		1
 	}
 
+	fn get_cooling_down_time() -> u64 {
+		CoolingDownTime::get()
+	}
+
 	fn get_transaction_data(
 		_pk: [u8; 64],
 		_pallet_name: Vec<u8>,
 		_token_id: Vec<u8>,
-		_nonce: u128,
+		nonce: u128,
 	) -> Option<OmniverseTx> {
+		if let Some(tx) = TransactionRecords::get().get(&nonce) {
+			return Some(tx.clone());
+		}
 		unsafe { TRANSACTION_DATA.clone() }
 	}
 	
@@ -159,6 +167,10 @@ impl Config for Test {
 	type MetadataDepositPerByte = ConstU64<1>;
 	type ApprovalDeposit = ConstU64<1>;
 	type StringLimit = ConstU32<50>;
+	type MaxPayloadSize = ConstU32<256>;
+	type MaxDelayedQueue = MaxDelayedQueue;
+	type MaxTokenIdLength = ConstU32<32>;
+	type MaxMembers = ConstU32<4>;
 	type Freezer = TestFreezer;
 	type WeightInfo = ();
 	type Extra = ();
@@ -175,6 +187,13 @@ pub enum Hook {
 parameter_types! {
 	static Frozen: HashMap<(u32, u64), u128> = Default::default();
 	static Hooks: Vec<Hook> = Default::default();
+	pub static CoolingDownTime: u64 = 0;
+	pub static MaxDelayedQueue: u32 = 100;
+	pub static MaliciousPk: Option<[u8; 64]> = None;
+	// The result of verifying a transaction with a valid signature
+	pub static VerifyOutcome: VerifyResult = VerifyResult::Success;
+	// key: nonce, the records found here take precedence over `TRANSACTION_DATA`
+	pub static TransactionRecords: HashMap<u128, OmniverseTx> = Default::default();
 }
 
 pub struct TestFreezer;
@@ -23,12 +23,13 @@ use crate::{mock::*, Error};
 use codec::{Decode, Encode};
 use frame_support::{
 	assert_err, assert_noop, assert_ok,
-	traits::{Currency, UnixTime},
+	traits::{Currency, GetStorageVersion, StorageVersion, UnixTime},
 };
 use pallet_balances::Error as BalancesError;
 use pallet_omniverse_protocol::OmniverseTx;
 use pallet_omniverse_protocol::{
-	traits::OmniverseAccounts, Fungible, OmniverseTransactionData, MINT, TRANSFER,
+	traits::OmniverseAccounts, Fungible, OmniverseTransactionData, VerifyResult, BURN, MINT,
+	TRANSFER,
 };
 use secp256k1::rand::rngs::OsRng;
 use secp256k1::{ecdsa::RecoverableSignature, Message, PublicKey, Secp256k1, SecretKey};
@@ -434,7 +435,7 @@ fn encode_transfer(
 	let payload = Fungible::new(TRANSFER, pk_to.into(), amount).encode();
 	let mut tx_data =
 		OmniverseTransactionData::new(nonce, CHAIN_ID, INITIATOR_ADDRESS, pk_from, payload);
-	let h = tx_data.get_raw_hash(false);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -454,7 +455,27 @@ fn encode_mint(
 	let pk_to: [u8; 64] = to.serialize_uncompressed()[1..].try_into().expect("");
 	let payload = Fungible::new(MINT, pk_to.into(), amount).encode();
 	let mut tx_data = OmniverseTransactionData::new(nonce, CHAIN_ID, TOKEN_ID, pk_from, payload);
-	let h = tx_data.get_raw_hash(false);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
+	let message = Message::from_slice(h.as_slice())
+		.expect("messages must be 32 bytes and are expected to be hashes");
+	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
+	let sig_recovery = get_sig_slice(&sig);
+	tx_data.set_signature(sig_recovery);
+	tx_data
+}
+
+fn encode_burn(
+	secp: &Secp256k1<secp256k1::All>,
+	from: (SecretKey, PublicKey),
+	to: PublicKey,
+	amount: u128,
+	nonce: u128,
+) -> OmniverseTransactionData {
+	let pk_from: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
+	let pk_to: [u8; 64] = to.serialize_uncompressed()[1..].try_into().expect("");
+	let payload = Fungible::new(BURN, pk_to.into(), amount).encode();
+	let mut tx_data = OmniverseTransactionData::new(nonce, CHAIN_ID, TOKEN_ID, pk_from, payload);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -514,6 +535,40 @@ fn it_fails_for_create_token_with_token_already_exist() {
 	});
 }
 
+#[test]
+fn it_fails_for_create_token_beyond_the_bounds() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		let members: Vec<(u32, Vec<u8>)> = (0..5).map(|i| (i, vec![i as u8])).collect();
+		assert_noop!(
+			Assets::create_token(RuntimeOrigin::signed(1), pk, vec![1; 33], None, None),
+			Error::<Test>::TokenIdTooLong
+		);
+		assert_noop!(
+			Assets::create_token(
+				RuntimeOrigin::signed(1),
+				pk,
+				vec![1],
+				Some(members.clone()),
+				None
+			),
+			Error::<Test>::TooManyMembers
+		);
+
+		assert_ok!(Assets::create_token(RuntimeOrigin::signed(1), pk, vec![1; 32], None, None));
+		assert_noop!(
+			Assets::set_members(RuntimeOrigin::signed(account), vec![1; 32], members),
+			Error::<Test>::TooManyMembers
+		);
+	});
+}
+
 #[test]
 fn it_fails_for_set_members_with_token_not_exist() {
 	new_test_ext().execute_with(|| {
@@ -524,6 +579,86 @@ fn it_fails_for_set_members_with_token_not_exist() {
 	});
 }
 
+#[test]
+fn it_works_for_remove_members() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(RuntimeOrigin::signed(1), pk, vec![1], None, None));
+
+		let members = vec![(1, vec![1]), (2, vec![2]), (3, vec![3])];
+		assert_ok!(Assets::set_members(RuntimeOrigin::signed(account), vec![1], members.clone()));
+		assert_noop!(
+			Assets::remove_members(RuntimeOrigin::signed(1), vec![1], vec![(2, vec![2])]),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Assets::remove_members(
+			RuntimeOrigin::signed(account),
+			vec![1],
+			vec![(2, vec![2])]
+		));
+
+		let token = Assets::tokens_info(vec![1]).unwrap();
+		assert_eq!(token.members, vec![(1, vec![1]), (3, vec![3])]);
+		assert_eq!(Assets::token_id_of_member((1, vec![1])), Some(vec![1]));
+		assert_eq!(Assets::token_id_of_member((2, vec![2])), None);
+		assert_eq!(Assets::token_id_of_member((3, vec![3])), Some(vec![1]));
+	});
+}
+
+#[test]
+fn it_works_for_transfer_token_ownership() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pairs
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (new_secret_key, new_public_key) = secp.generate_keypair(&mut OsRng);
+		let new_pk: [u8; 64] = new_public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let new_account = get_account_id_from_pk(new_public_key.serialize().as_slice());
+		fund_account(account);
+		fund_account(new_account);
+		assert_ok!(Assets::create_token(RuntimeOrigin::signed(1), pk, TOKEN_ID, None, None));
+		assert_eq!(Balances::reserved_balance(account), 1);
+
+		assert_noop!(
+			Assets::transfer_token_ownership(RuntimeOrigin::signed(new_account), TOKEN_ID, new_pk),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Assets::transfer_token_ownership(
+			RuntimeOrigin::signed(account),
+			TOKEN_ID,
+			new_pk
+		));
+		System::assert_last_event(RuntimeEvent::Assets(Event::TokenOwnershipTransferred {
+			token_id: TOKEN_ID,
+			owner: new_account,
+			owner_pk: new_pk,
+		}));
+		let token = Assets::tokens_info(TOKEN_ID).unwrap();
+		assert_eq!((token.owner, token.owner_pk), (new_account, new_pk));
+		assert_eq!(Balances::reserved_balance(account), 0);
+		assert_eq!(Balances::reserved_balance(new_account), 1);
+
+		// Only the new owner manages the token and mints it
+		assert_noop!(
+			Assets::set_members(RuntimeOrigin::signed(account), TOKEN_ID, vec![(1, vec![1])]),
+			Error::<Test>::NoPermission
+		);
+		let nonce =
+			OmniverseProtocol::get_transaction_count(new_pk, PALLET_NAME.to_vec(), Vec::new());
+		let data = encode_mint(&secp, (new_secret_key, new_public_key), new_public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data));
+	});
+}
+
 #[test]
 fn it_fails_for_set_members_with_not_owner() {
 	new_test_ext().execute_with(|| {
@@ -638,11 +773,11 @@ fn it_fails_for_factory_handler_with_signature_error() {
 }
 
 #[test]
-fn it_fails_for_factory_handler_mint_with_signer_not_owner() {
+fn it_fails_for_send_transaction_with_payload_too_large() {
 	new_test_ext().execute_with(|| {
 		let secp = Secp256k1::new();
 		// Generate key pair
-		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
 
 		// Get nonce
 		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
@@ -659,20 +794,49 @@ fn it_fails_for_factory_handler_mint_with_signer_not_owner() {
 			None
 		));
 
-		let (secret_key_to, public_key_to) = secp.generate_keypair(&mut OsRng);
-		let to = get_account_id_from_pk(public_key_to.serialize().as_slice());
-		fund_account(to);
+		let mut data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		data.payload = vec![0; 257];
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data),
+			Error::<Test>::PayloadTooLarge
+		);
+	});
+}
 
-		let data = encode_mint(&secp, (secret_key_to, public_key_to), public_key_to, 1, nonce);
-		assert_err!(
-			Assets::send_transaction_external(TOKEN_ID, &data),
-			Error::<Test>::SignerNotOwner
+#[test]
+fn it_fails_for_send_transaction_with_undecodable_payload() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// The amount is missing
+		let mut data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		data.payload = vec![0; 3];
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data),
+			Error::<Test>::DecodePayloadFailed
 		);
 	});
 }
 
 #[test]
-fn it_works_for_factory_handler_mint() {
+fn it_fails_for_send_transaction_with_unknown_opcode() {
 	new_test_ext().execute_with(|| {
 		let secp = Secp256k1::new();
 		// Generate key pair
@@ -693,29 +857,57 @@ fn it_works_for_factory_handler_mint() {
 			None
 		));
 
-		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
-		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
-		fund_account(account_to);
-		let data = encode_mint(&secp, (secret_key, public_key), public_key_to, 1, nonce);
-		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		// An opcode without a handler is rejected instead of being accepted as a no-op
+		let mut data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		data.payload = Fungible::new(u8::MAX, pk.into(), 100).encode();
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data),
+			Error::<Test>::UnknownProtocolType
+		);
+	});
+}
 
-		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-			data,
-			Timestamp::now().as_secs(),
-		)));
+#[test]
+fn it_works_for_send_transaction_to_member() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
 
-		// Delay
-		Timestamp::past(COOL_DOWN);
-		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
 
-		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
-		let token = Assets::tokens(TOKEN_ID, pk_to);
-		assert_eq!(token, 1);
+		// Create token with a member on another chain
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(vec![(2, vec![2])]),
+			None
+		));
+
+		// Neither the token nor one of its members
+		let mut data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		data.chain_id = 3;
+		data.initiator_address = vec![3];
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data.clone()),
+			Error::<Test>::WrongDestination
+		);
+
+		// Relayed from the member
+		data.chain_id = 2;
+		data.initiator_address = vec![2];
+		assert_ok!(Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data));
+		assert_eq!(Assets::delayed_index(), (0, 1));
 	});
 }
 
 #[test]
-fn it_fails_for_factory_handler_transfer_with_balance_overflow() {
+fn it_fails_for_send_transaction_from_malicious_user() {
 	new_test_ext().execute_with(|| {
 		let secp = Secp256k1::new();
 		// Generate key pair
@@ -728,7 +920,6 @@ fn it_fails_for_factory_handler_transfer_with_balance_overflow() {
 		// Create token
 		let account = get_account_id_from_pk(public_key.serialize().as_slice());
 		fund_account(account);
-
 		assert_ok!(Assets::create_token(
 			RuntimeOrigin::signed(1),
 			pk,
@@ -737,30 +928,66 @@ fn it_fails_for_factory_handler_transfer_with_balance_overflow() {
 			None
 		));
 
-		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
-		let to = get_account_id_from_pk(public_key_to.serialize().as_slice());
-		fund_account(to);
+		MaliciousPk::set(Some(pk));
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data),
+			Error::<Test>::UserIsMalicious
+		);
+	});
+}
 
-		// Mint token
-		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
-		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+#[test]
+fn it_works_for_send_transaction_external_returning_the_protocol_result() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
 
-		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-			mint_data,
-			Timestamp::now().as_secs(),
-		)));
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
 
-		// Delay
-		Timestamp::past(COOL_DOWN);
-		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
 
-		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 10, nonce);
-		assert_err!(Assets::send_transaction_external(TOKEN_ID, &data), Error::<Test>::BalanceLow);
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		VerifyOutcome::set(VerifyResult::Duplicated);
+		assert_eq!(
+			Assets::send_transaction_external(TOKEN_ID, &data),
+			Ok(FactoryResult::ProtocolDuplicated)
+		);
+		System::assert_last_event(RuntimeEvent::Assets(crate::Event::TransactionDuplicated {
+			pk,
+			nonce,
+			token_id: TOKEN_ID,
+		}));
+
+		VerifyOutcome::set(VerifyResult::Malicious);
+		assert_eq!(
+			Assets::send_transaction_external(TOKEN_ID, &data),
+			Ok(FactoryResult::ProtocolMalicious)
+		);
+
+		// Neither is delayed to be executed
+		assert_eq!(Assets::delayed_index(), (0, 0));
+
+		VerifyOutcome::set(VerifyResult::Success);
+		assert_eq!(Assets::send_transaction_external(TOKEN_ID, &data), Ok(FactoryResult::Success));
+		assert_eq!(Assets::delayed_index(), (0, 1));
 	});
 }
 
 #[test]
-fn it_works_for_factory_handler_transfer() {
+fn it_fails_for_send_transaction_with_delayed_queue_full() {
 	new_test_ext().execute_with(|| {
 		let secp = Secp256k1::new();
 		// Generate key pair
@@ -781,36 +1008,808 @@ fn it_works_for_factory_handler_transfer() {
 			None
 		));
 
-		// Mint token
+		// Fill the queue up to the cap
+		MaxDelayedQueue::set(2);
 		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
-		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+		assert_ok!(Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, mint_data.clone()));
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce + 1);
+		assert_ok!(Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data));
+		assert_eq!(Assets::delayed_index(), (0, 2));
+
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce + 2);
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data.clone()),
+			Error::<Test>::DelayedQueueFull
+		);
 
+		// Executing a transaction frees a slot
 		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
 			mint_data,
 			Timestamp::now().as_secs(),
 		)));
-
-		// Delay
 		Timestamp::past(COOL_DOWN);
 		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_ok!(Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data));
+		assert_eq!(Assets::delayed_index(), (1, 3));
+	});
+}
 
-		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
-		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
-		fund_account(account_to);
-		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 1, nonce);
-		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+#[test]
+fn it_works_for_pending_delayed() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
 
-		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-			data,
-			Timestamp::now().as_secs(),
-		)));
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			Some(COOL_DOWN)
+		));
+		assert_eq!(Assets::pending_delayed(), vec![]);
+
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		let data_next = encode_mint(&secp, (secret_key, public_key), public_key, 20, nonce + 1);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data_next));
+		TransactionRecords::mutate(|records| {
+			records.insert(nonce, OmniverseTx::new(data, 100));
+			records.insert(nonce + 1, OmniverseTx::new(data_next, 105));
+		});
+
+		// Each is executable once the cooling down time of the token has elapsed
+		assert_eq!(
+			Assets::pending_delayed(),
+			vec![
+				(0, DelayedTx::new(pk, TOKEN_ID, nonce, MINT, 10), 100 + COOL_DOWN),
+				(1, DelayedTx::new(pk, TOKEN_ID, nonce + 1, MINT, 20), 105 + COOL_DOWN),
+			]
+		);
 
-		// Delay
-		Timestamp::past(COOL_DOWN);
 		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(
+			Assets::pending_delayed(),
+			vec![(1, DelayedTx::new(pk, TOKEN_ID, nonce + 1, MINT, 20), 105 + COOL_DOWN)]
+		);
+	});
+}
 
-		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 9);
-		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
-		assert_eq!(Assets::tokens(TOKEN_ID, &pk_to), 1);
+#[test]
+fn it_fails_for_factory_handler_mint_with_signer_not_owner() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		let (secret_key_to, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(to);
+
+		let data = encode_mint(&secp, (secret_key_to, public_key_to), public_key_to, 1, nonce);
+		assert_err!(
+			Assets::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::SignerNotOwner
+		);
+	});
+}
+
+#[test]
+fn it_works_for_factory_handler_mint() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_mint(&secp, (secret_key, public_key), public_key_to, 1, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		let token = Assets::tokens(TOKEN_ID, pk_to);
+		assert_eq!(token, 1);
+	});
+}
+
+#[test]
+fn it_works_for_factory_handler_burn() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// Burn more than the balance
+		let data = encode_burn(&secp, (secret_key, public_key), public_key, 11, nonce);
+		assert_err!(
+			Assets::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::BurnExceedsBalance
+		);
+
+		// Burn token
+		let data = encode_burn(&secp, (secret_key, public_key), public_key, 4, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 6);
+		let asset_id = Assets::token_id_2_asset_id(TOKEN_ID).unwrap();
+		System::assert_has_event(RuntimeEvent::Assets(Event::Burned {
+			asset_id,
+			owner: account,
+			balance: 4,
+		}));
+	});
+}
+
+#[test]
+fn it_fails_for_factory_handler_transfer_with_balance_overflow() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(to);
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 10, nonce);
+		assert_err!(Assets::send_transaction_external(TOKEN_ID, &data), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn it_works_for_factory_handler_transfer() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 1, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 9);
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk_to), 1);
+	});
+}
+
+#[test]
+fn it_works_for_freeze_and_thaw_token() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// Only the owner freezes the token
+		assert_noop!(
+			Assets::freeze_token(RuntimeOrigin::signed(1), TOKEN_ID),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Assets::freeze_token(RuntimeOrigin::signed(account), TOKEN_ID));
+		System::assert_last_event(RuntimeEvent::Assets(Event::TokenFrozen { token_id: TOKEN_ID }));
+		assert!(Assets::is_token_frozen(TOKEN_ID));
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 1, nonce);
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data.clone()),
+			Error::<Test>::TokenFrozen
+		);
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_noop!(
+			Assets::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, mint_data),
+			Error::<Test>::TokenFrozen
+		);
+
+		assert_noop!(
+			Assets::thaw_token(RuntimeOrigin::signed(1), TOKEN_ID),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Assets::thaw_token(RuntimeOrigin::signed(account), TOKEN_ID));
+		System::assert_last_event(RuntimeEvent::Assets(Event::TokenThawed { token_id: TOKEN_ID }));
+		assert!(!Assets::is_token_frozen(TOKEN_ID));
+
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk_to), 1);
+	});
+}
+
+#[test]
+fn it_works_for_set_token_metadata() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_noop!(
+			Assets::set_token_metadata(
+				RuntimeOrigin::signed(account),
+				TOKEN_ID,
+				b"Token".to_vec(),
+				b"TKN".to_vec(),
+				12
+			),
+			Error::<Test>::Unknown
+		);
+		assert_ok!(Assets::create_token(RuntimeOrigin::signed(1), pk, TOKEN_ID, None, None));
+		assert_eq!(Assets::token_metadata(TOKEN_ID), None);
+
+		assert_noop!(
+			Assets::set_token_metadata(
+				RuntimeOrigin::signed(1),
+				TOKEN_ID,
+				b"Token".to_vec(),
+				b"TKN".to_vec(),
+				12
+			),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::set_token_metadata(
+				RuntimeOrigin::signed(account),
+				TOKEN_ID,
+				vec![0; 51],
+				b"TKN".to_vec(),
+				12
+			),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(Assets::set_token_metadata(
+			RuntimeOrigin::signed(account),
+			TOKEN_ID,
+			b"Token".to_vec(),
+			b"TKN".to_vec(),
+			12
+		));
+		assert_eq!(
+			Assets::token_metadata(TOKEN_ID),
+			Some((b"Token".to_vec(), b"TKN".to_vec(), 12))
+		);
+		assert_eq!(<Assets as OmniverseTokenFactoryHandler>::decimals(&TOKEN_ID), Some(12));
+	});
+}
+
+#[test]
+fn it_works_for_omniverse_balance_accounting() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+		let token = Assets::tokens_info(TOKEN_ID).unwrap();
+
+		assert_ok!(Assets::omniverse_mint(token.clone(), pk, 10));
+		assert_ok!(Assets::omniverse_transfer(token.clone(), pk, pk_to, 3));
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 7);
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk_to), 3);
+
+		// The balances are amounts, not a set of quantities
+		assert_ok!(Assets::omniverse_transfer(token.clone(), pk, pk_to, 3));
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 4);
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk_to), 6);
+
+		assert_noop!(
+			Assets::omniverse_transfer(token.clone(), pk, pk_to, 5),
+			Error::<Test>::BalanceLow
+		);
+		assert_noop!(
+			Assets::omniverse_burn(token.clone(), pk_to, 7),
+			Error::<Test>::BurnExceedsBalance
+		);
+		assert_noop!(Assets::omniverse_mint(token, pk_to, u128::MAX), ArithmeticError::Overflow);
+	});
+}
+
+#[test]
+fn it_works_for_skipping_a_failed_execution() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// Both transfers are covered by the balance when they are sent, not when executed
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 8, nonce + 1);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		let data_over =
+			encode_transfer(&secp, (secret_key, public_key), public_key_to, 8, nonce + 2);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data_over));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data_over,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		System::assert_last_event(RuntimeEvent::Assets(Event::TransactionExecutionFailed {
+			pk,
+			nonce: nonce + 2,
+			token_id: TOKEN_ID,
+			reason: Error::<Test>::BalanceLow.into(),
+		}));
+		assert_eq!(Assets::delayed_index(), (3, 3));
+
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 2);
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk_to), 8);
+	});
+}
+
+#[test]
+fn it_fails_for_trigger_execution_with_tampered_record() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &mint_data));
+		let delayed_tx = Assets::delayed_transctions(0).unwrap();
+		assert_eq!(delayed_tx.op, MINT);
+		assert_eq!(delayed_tx.amount, 10);
+
+		// Tamper the recorded transaction
+		let tampered_data = encode_mint(&secp, (secret_key, public_key), public_key, 20, nonce);
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			tampered_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_err!(
+			Assets::trigger_execution(RuntimeOrigin::signed(1)),
+			Error::<Test>::DelayedTxMismatch
+		);
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 10);
+	});
+}
+
+#[test]
+fn it_works_for_skip_delayed() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+		assert_noop!(Assets::skip_delayed(RuntimeOrigin::root()), Error::<Test>::NoDelayedTx);
+
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		let data_next = encode_mint(&secp, (secret_key, public_key), public_key, 20, nonce + 1);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data_next));
+
+		// The record of the head does not match it, it blocks the queue
+		let tampered_data = encode_mint(&secp, (secret_key, public_key), public_key, 30, nonce);
+		TransactionRecords::mutate(|records| {
+			records.insert(nonce, OmniverseTx::new(tampered_data, Timestamp::now().as_secs()));
+			records.insert(nonce + 1, OmniverseTx::new(data_next, Timestamp::now().as_secs()));
+		});
+		Timestamp::past(COOL_DOWN);
+		assert_err!(
+			Assets::trigger_execution(RuntimeOrigin::signed(1)),
+			Error::<Test>::DelayedTxMismatch
+		);
+
+		assert_noop!(Assets::skip_delayed(RuntimeOrigin::signed(1)), DispatchError::BadOrigin);
+		assert_ok!(Assets::skip_delayed(RuntimeOrigin::root()));
+		System::assert_last_event(RuntimeEvent::Assets(Event::DelayedTransactionSkipped {
+			index: 0,
+			pk,
+			nonce,
+			token_id: TOKEN_ID,
+		}));
+		assert_eq!(Assets::delayed_index(), (1, 2));
+
+		// The queue is unblocked
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(Assets::delayed_index(), (2, 2));
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 20);
+	});
+}
+
+#[test]
+fn it_works_for_trigger_execution_batch() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Enqueue three mints
+		for i in 0..3 {
+			let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce + i);
+			assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+			OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+				data,
+				Timestamp::now().as_secs(),
+			)));
+		}
+		assert_eq!(Assets::delayed_index(), (0, 3));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Assets::trigger_execution_batch(RuntimeOrigin::signed(1), 10));
+		assert_eq!(Assets::delayed_index(), (3, 3));
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 30);
+		let executed = System::events()
+			.into_iter()
+			.filter(|r| matches!(r.event, RuntimeEvent::Assets(Event::TransactionExecuted { .. })))
+			.count();
+		assert_eq!(executed, 3);
+
+		// A transaction still cooling down is not executed
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce + 3);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+		assert_err!(
+			Assets::trigger_execution_batch(RuntimeOrigin::signed(1), 10),
+			Error::<Test>::NotExecutable
+		);
+		assert_eq!(Assets::delayed_index(), (3, 4));
+	});
+}
+
+#[test]
+fn it_fails_for_trigger_execution_before_configured_cooling_down_time() {
+	new_test_ext().execute_with(|| {
+		CoolingDownTime::set(COOL_DOWN * 10);
+
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token without an explicit cooling down time
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+		assert_eq!(Assets::tokens_info(TOKEN_ID).unwrap().cooldown_time, COOL_DOWN * 10);
+
+		// Mint token
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, nonce);
+		assert_ok!(Assets::send_transaction_external(TOKEN_ID, &data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_err!(
+			Assets::trigger_execution(RuntimeOrigin::signed(1)),
+			Error::<Test>::NotExecutable
+		);
+
+		Timestamp::past(COOL_DOWN * 9);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(Assets::tokens(TOKEN_ID, &pk), 10);
+	});
+}
+
+#[test]
+fn it_works_for_migrating_delayed_transactions_to_v1() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		// A transaction queued before its operation was captured
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 10, 0);
+		TransactionRecords::mutate(|records| {
+			records.insert(0, OmniverseTx::new(data, 100));
+		});
+		let old = crate::migration::v0::DelayedTx { sender: pk, token_id: TOKEN_ID, nonce: 0 };
+		frame_support::storage::unhashed::put(
+			&DelayedTransactions::<Test>::hashed_key_for(0),
+			&old,
+		);
+		StorageVersion::new(0).put::<Assets>();
+
+		crate::migration::migrate_to_v1::<Test, (), Assets>();
+		assert_eq!(Assets::delayed_transctions(0), Some(DelayedTx::new(pk, TOKEN_ID, 0, MINT, 10)));
+		assert_eq!(Assets::on_chain_storage_version(), 1);
 	});
 }
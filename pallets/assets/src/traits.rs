@@ -7,4 +7,10 @@ pub trait OmniverseTokenFactoryHandler {
 		token_id: Vec<u8>,
 		data: &OmniverseTransactionData,
 	) -> Result<FactoryResult, DispatchError>;
+
+	/// The decimals of the token, `None` if its metadata is not set
+	fn decimals(token_id: &Vec<u8>) -> Option<u8>;
+
+	/// The balance of `pk` in the token
+	fn balance_of(token_id: &Vec<u8>, pk: &[u8; 64]) -> u128;
 }
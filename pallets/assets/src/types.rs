@@ -228,7 +228,7 @@ pub enum ConversionError {
 	AssetNotSufficient,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum FactoryResult {
 	Success,
 	ProtocolMalicious,
@@ -316,6 +316,10 @@ impl<AccountId> OmniverseToken<AccountId> {
 		self.members = members;
 	}
 
+	pub fn remove_members(&mut self, members: &[(u32, Vec<u8>)]) {
+		self.members.retain(|m| !members.contains(m));
+	}
+
 	pub fn set_cooldown_time(&mut self, cooldown_time: u64) {
 		self.cooldown_time = cooldown_time;
 	}
@@ -335,16 +339,20 @@ pub struct DelayedTx {
 	pub sender: [u8; 64],
 	pub token_id: Vec<u8>,
 	pub nonce: u128,
+	/// Operation type of the transaction, captured when it is enqueued
+	pub op: u8,
+	/// Amount of the transaction, captured when it is enqueued
+	pub amount: u128,
 }
 
 impl DelayedTx {
-	pub fn new(sender: [u8; 64], token_id: Vec<u8>, nonce: u128) -> Self {
-		Self { sender, token_id, nonce }
+	pub fn new(sender: [u8; 64], token_id: Vec<u8>, nonce: u128, op: u8, amount: u128) -> Self {
+		Self { sender, token_id, nonce, op, amount }
 	}
 }
 
 impl Default for DelayedTx {
 	fn default() -> Self {
-		Self { sender: [0; 64], token_id: Vec::<u8>::new(), nonce: 0 }
+		Self { sender: [0; 64], token_id: Vec::<u8>::new(), nonce: 0, op: 0, amount: 0 }
 	}
 }
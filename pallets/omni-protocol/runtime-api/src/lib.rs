@@ -0,0 +1,21 @@
+//! Runtime API definition for the omniverse protocol pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait OmniverseProtocolApi {
+		/// The nonce the next transaction of `pk` must be signed with
+		fn next_nonce(pk: [u8; 64], pallet_name: Vec<u8>, token_id: Vec<u8>) -> u128;
+
+		/// The first and the last nonce skipped if a transaction with `nonce` is submitted next,
+		/// `None` if there is no gap
+		fn missing_nonces(
+			pk: [u8; 64],
+			pallet_name: Vec<u8>,
+			token_id: Vec<u8>,
+			nonce: u128,
+		) -> Option<(u128, u128)>;
+	}
+}
@@ -1,10 +1,10 @@
 use super::traits::*;
 use super::*;
-use crate::{Fungible, OmniverseTransactionData};
+use crate::{Fungible, OmniverseTransactionData, ED25519, SECP256K1};
 use codec::Decode;
 use frame_support::traits::{Get, UnixTime};
 use scale_info::prelude::string::{String, ToString};
-use sp_core::Hasher;
+use sp_core::{ed25519, Hasher};
 use sp_io::crypto;
 use sp_runtime::traits::Keccak256;
 use sp_std::cmp::Ordering;
@@ -12,17 +12,20 @@ use sp_std::vec::Vec;
 
 const ETHEREUM_PREFIX: &str = "\x19Ethereum Signed Message:\n";
 
-pub fn get_transaction_hash(data: &OmniverseTransactionData, with_ethereum: bool) -> [u8; 32] {
+/// The token id is hashed along with the transaction so that a signature is bound to one token
+pub fn get_transaction_hash(
+	data: &OmniverseTransactionData,
+	token_id: &[u8],
+	with_ethereum: bool,
+) -> [u8; 32] {
 	let mut raw = Vec::<u8>::new();
 	raw.extend_from_slice(u128::to_be_bytes(data.nonce).as_slice());
 	raw.extend_from_slice(u32::to_be_bytes(data.chain_id).as_slice());
 	raw.extend(data.initiator_address.clone());
 	raw.extend_from_slice(&data.from.clone());
+	raw.extend_from_slice(token_id);
 
 	let mut bytes_data = Vec::<u8>::new();
-	let fungible = Fungible::decode(&mut data.payload.as_slice()).unwrap();
-	bytes_data.extend_from_slice(u8::to_be_bytes(fungible.op).as_slice());
-
 	// if data.op_type == TRANSFER {
 	// 	// let transfer_data = TransferTokenOp::decode(&mut data.op_data.as_slice()).unwrap();
 	// 	bytes_data.extend(data.op_data.clone());
@@ -32,8 +35,16 @@ pub fn get_transaction_hash(data: &OmniverseTransactionData, with_ethereum: bool
 	// 	bytes_data.extend_from_slice(&mut mint_data.to.clone());
 	// 	bytes_data.extend_from_slice(&mut u128::to_be_bytes(mint_data.amount).as_slice());
 	// }
-	bytes_data.extend(fungible.ex_data.clone());
-	bytes_data.extend_from_slice(u128::to_be_bytes(fungible.amount).as_slice());
+	// `Assets` and `NonFungible` payloads share the layout of `Fungible`, anything else is hashed
+	// as raw bytes
+	match Fungible::decode(&mut data.payload.as_slice()) {
+		Ok(fungible) => {
+			bytes_data.extend_from_slice(u8::to_be_bytes(fungible.op).as_slice());
+			bytes_data.extend(fungible.ex_data.clone());
+			bytes_data.extend_from_slice(u128::to_be_bytes(fungible.amount).as_slice());
+		},
+		Err(_) => bytes_data.extend(data.payload.clone()),
+	}
 	raw.append(bytes_data.as_mut());
 	if with_ethereum {
 		// let v: Vec<u8> = wrap_ethereum.into_bytes();
@@ -50,22 +61,59 @@ pub fn get_transaction_hash(data: &OmniverseTransactionData, with_ethereum: bool
 	h.0
 }
 
+/// The ed25519 public key is the first 32 bytes of `from` and the signature the first 64 bytes of
+/// `signature`, the remaining bytes must be zero
+fn verify_ed25519_signature(
+	data: &OmniverseTransactionData,
+	tx_hash_bytes: &[u8; 32],
+) -> Result<(), VerifyError> {
+	let (pk, pk_padding) = data.from.split_at(32);
+	if pk_padding.iter().any(|b| *b != 0) {
+		return Err(VerifyError::SignerNotCaller);
+	}
+	let (sig, sig_padding) = data.signature.split_at(64);
+	if sig_padding.iter().any(|b| *b != 0) {
+		return Err(VerifyError::SignatureError);
+	}
+
+	let pk = ed25519::Public::from_raw(pk.try_into().map_err(|_| VerifyError::SignerNotCaller)?);
+	let sig =
+		ed25519::Signature::from_raw(sig.try_into().map_err(|_| VerifyError::SignatureError)?);
+	if !crypto::ed25519_verify(&sig, tx_hash_bytes, &pk) {
+		return Err(VerifyError::SignatureError);
+	}
+
+	Ok(())
+}
+
 impl<T: Config> OmniverseAccounts for Pallet<T> {
 	fn verify_transaction(
 		pallet_name: &[u8],
 		token_id: &[u8],
 		data: &OmniverseTransactionData,
 		with_ethereum: bool,
+		relayed: bool,
 	) -> Result<VerifyResult, VerifyError> {
+		// A transaction signed for another chain must not be replayed here
+		if data.chain_id != T::ChainId::get() && !relayed {
+			return Err(VerifyError::WrongChain);
+		}
+
 		let nonce = TransactionCount::<T>::get((&data.from, pallet_name, token_id));
 
-		let tx_hash_bytes = super::functions::get_transaction_hash(data, with_ethereum);
+		let tx_hash_bytes = super::functions::get_transaction_hash(data, token_id, with_ethereum);
 
-		let recoverd_pk = crypto::secp256k1_ecdsa_recover(&data.signature, &tx_hash_bytes)
-			.map_err(|_| VerifyError::SignatureError)?;
+		match data.signature_scheme {
+			SECP256K1 => {
+				let recoverd_pk = crypto::secp256k1_ecdsa_recover(&data.signature, &tx_hash_bytes)
+					.map_err(|_| VerifyError::SignatureError)?;
 
-		if recoverd_pk != data.from {
-			return Err(VerifyError::SignerNotCaller);
+				if recoverd_pk != data.from {
+					return Err(VerifyError::SignerNotCaller);
+				}
+			},
+			ED25519 => verify_ed25519_signature(data, &tx_hash_bytes)?,
+			_ => return Err(VerifyError::SignatureError),
 		}
 
 		match nonce.cmp(&data.nonce) {
@@ -80,28 +128,54 @@ impl<T: Config> OmniverseAccounts for Pallet<T> {
 				// if data.chain_id == T::ChainId::get() {
 				// 	Self::deposit_event(Event::TransactionSent(data.from, token_id.clone(), nonce));
 				// }
+				Self::deposit_event(Event::TransactionVerified {
+					from: data.from,
+					pallet_name: pallet_name.to_vec(),
+					token_id: token_id.to_vec(),
+					nonce,
+				});
 				Ok(VerifyResult::Success)
 			},
 			Ordering::Greater => {
-				// Check conflicts
+				// Check conflicts, a pruned transaction can not be checked any more
 				let his_tx = TransactionRecorder::<T>::get((
 					data.from,
 					pallet_name.to_vec(),
 					token_id.to_vec(),
 					data.nonce,
 				))
-				.unwrap();
-				let his_tx_hash =
-					super::functions::get_transaction_hash(&his_tx.tx_data, with_ethereum);
+				.ok_or(VerifyError::NonceError)?;
+				let his_tx_hash = super::functions::get_transaction_hash(
+					&his_tx.tx_data,
+					token_id,
+					with_ethereum,
+				);
 				if his_tx_hash != tx_hash_bytes {
 					let omni_tx = OmniverseTx::new(data.clone(), T::Timestamp::now().as_secs());
 					let evil_tx = EvilTxData::new(omni_tx, nonce);
-					let mut er =
-						EvilRecorder::<T>::get(data.from).unwrap_or(Vec::<EvilTxData>::default());
-					er.push(evil_tx);
-					EvilRecorder::<T>::insert(data.from, er);
+					let mut er = EvilRecorder::<T>::get(data.from).unwrap_or_default();
+					// Only the first conflict of a nonce is punished
+					let first_conflict =
+						!er.iter().any(|evil| evil.tx_omni.tx_data.nonce == data.nonce);
+					// The records are evidence enough once they are full
+					if er.try_push(evil_tx).is_ok() {
+						EvilRecorder::<T>::insert(data.from, er);
+					}
+					if first_conflict {
+						T::SlashHandler::on_malicious(data.from, T::MaliciousPenalty::get());
+					}
+					Self::deposit_event(Event::MaliciousTransaction {
+						from: data.from,
+						nonce: data.nonce,
+					});
 					Ok(VerifyResult::Malicious)
 				} else {
+					Self::deposit_event(Event::TransactionDuplicated {
+						from: data.from,
+						pallet_name: pallet_name.to_vec(),
+						token_id: token_id.to_vec(),
+						nonce: data.nonce,
+					});
 					Ok(VerifyResult::Duplicated)
 				}
 			},
@@ -128,6 +202,10 @@ impl<T: Config> OmniverseAccounts for Pallet<T> {
 		T::ChainId::get()
 	}
 
+	fn get_cooling_down_time() -> u64 {
+		T::CoolingDownTime::get()
+	}
+
 	fn get_transaction_data(
 		pk: [u8; 64],
 		pallet_name: Vec<u8>,
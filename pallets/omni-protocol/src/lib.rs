@@ -15,9 +15,14 @@ pub mod functions;
 
 pub mod traits;
 
+pub mod migration;
+
 #[frame_support::pallet]
 pub mod pallet {
-	use super::types::{EvilTxData, OmniverseTx};
+	use super::{
+		traits::OnMaliciousTransaction,
+		types::{EvilTxData, OmniverseTx},
+	};
 	use frame_support::{pallet_prelude::*, traits::UnixTime};
 	use frame_system::pallet_prelude::*;
 	use sp_std::vec::Vec;
@@ -29,7 +34,26 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		#[pallet::constant]
 		type ChainId: Get<u32>;
+		/// Default seconds a delayed transaction has to wait before it can be executed
+		#[pallet::constant]
+		type CoolingDownTime: Get<u64>;
 		type Timestamp: UnixTime;
+		/// Seconds a recorded transaction is kept before it can be pruned, it is at least the
+		/// cooling down time so that duplicates are detected while transactions are delayed
+		#[pallet::constant]
+		type TransactionRetention: Get<u64>;
+		/// Called once for every nonce an account signed conflicting transactions with
+		type SlashHandler: OnMaliciousTransaction;
+		/// The penalty passed to the slash handler
+		#[pallet::constant]
+		type MaliciousPenalty: Get<u128>;
+		/// The origin which may clear the record of a malicious account and prune the recorded
+		/// transactions
+		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The most conflicting transactions recorded for an account, the later ones are still
+		/// rejected as malicious
+		#[pallet::constant]
+		type MaxEvilRecords: Get<u32>;
 	}
 
 	#[pallet::type_value]
@@ -37,8 +61,12 @@ pub mod pallet {
 		0
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -103,20 +131,105 @@ pub mod pallet {
 	#[pallet::getter(fn evil_recorder)]
 	// Learn more about declaring storage items:
 	// https://docs.substrate.io/v3/runtime/storage#declaring-storage-items
-	pub type EvilRecorder<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 64], Vec<EvilTxData>>;
+	pub type EvilRecorder<T: Config> =
+		StorageMap<_, Blake2_128Concat, [u8; 64], BoundedVec<EvilTxData, T::MaxEvilRecords>>;
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/v3/runtime/events-and-errors
 	#[pallet::event]
-	pub enum Event<T: Config> {}
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A transaction was verified and recorded.
+		TransactionVerified { from: [u8; 64], pallet_name: Vec<u8>, token_id: Vec<u8>, nonce: u128 },
+		/// A transaction was already recorded.
+		TransactionDuplicated {
+			from: [u8; 64],
+			pallet_name: Vec<u8>,
+			token_id: Vec<u8>,
+			nonce: u128,
+		},
+		/// A transaction conflicts with the recorded one with the same nonce.
+		MaliciousTransaction { from: [u8; 64], nonce: u128 },
+		/// Recorded transactions were pruned.
+		TransactionsPruned { from: [u8; 64], pallet_name: Vec<u8>, token_id: Vec<u8>, count: u32 },
+		/// The malicious record of an account was cleared.
+		EvilRecordCleared { from: [u8; 64] },
+	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
-	pub enum Error<T> {}
+	pub enum Error<T> {
+		/// The account has no malicious record
+		NoEvilRecord,
+	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migration::migrate_to_v1::<T>()
+		}
+	}
 
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Remove the recorded transactions of `pk` with a nonce from `up_to_nonce - max` up to
+		/// `up_to_nonce` which are executed and older than the retention window, the others are
+		/// kept as they may still be executed or confirmed
+		#[pallet::weight(
+			10_000 + T::DbWeight::get().reads_writes(*max as u64, *max as u64).ref_time()
+		)]
+		pub fn prune_transactions(
+			origin: OriginFor<T>,
+			pk: [u8; 64],
+			pallet_name: Vec<u8>,
+			token_id: Vec<u8>,
+			up_to_nonce: u128,
+			max: u32,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let retention = T::TransactionRetention::get().max(T::CoolingDownTime::get());
+			let now = T::Timestamp::now().as_secs();
+			let expired: Vec<u128> = (up_to_nonce.saturating_sub(max.into())..up_to_nonce)
+				.filter(|nonce| {
+					TransactionRecorder::<T>::get((
+						pk,
+						pallet_name.clone(),
+						token_id.clone(),
+						nonce,
+					))
+					.map_or(false, |omni_tx| {
+						omni_tx.executed && omni_tx.timestamp.saturating_add(retention) <= now
+					})
+				})
+				.collect();
+			for nonce in expired.iter() {
+				TransactionRecorder::<T>::remove((
+					pk,
+					pallet_name.clone(),
+					token_id.clone(),
+					nonce,
+				));
+			}
+
+			Self::deposit_event(Event::TransactionsPruned {
+				from: pk,
+				pallet_name,
+				token_id,
+				count: expired.len() as u32,
+			});
+			Ok(())
+		}
+
+		/// Clear the malicious record of `pk` after the dispute was resolved off-chain
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		pub fn clear_evil_record(origin: OriginFor<T>, pk: [u8; 64]) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(EvilRecorder::<T>::contains_key(pk), Error::<T>::NoEvilRecord);
+
+			EvilRecorder::<T>::remove(pk);
+			Self::deposit_event(Event::EvilRecordCleared { from: pk });
+			Ok(())
+		}
+	}
 }
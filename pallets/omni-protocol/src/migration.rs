@@ -0,0 +1,108 @@
+//! Storage migrations of the pallet.
+use super::*;
+use frame_support::{
+	traits::{Get, GetStorageVersion, StorageVersion},
+	BoundedVec,
+};
+use sp_std::vec::Vec;
+
+/// The types as they were encoded before the signature scheme was recorded.
+pub mod v0 {
+	use crate::types::{self, SECP256K1};
+	use codec::{Decode, Encode};
+	use sp_std::vec::Vec;
+
+	#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+	pub struct OmniverseTransactionData {
+		pub nonce: u128,
+		pub chain_id: u32,
+		pub initiator_address: Vec<u8>,
+		pub from: [u8; 64],
+		pub payload: Vec<u8>,
+		pub signature: [u8; 65],
+	}
+
+	impl OmniverseTransactionData {
+		/// The transaction signed with secp256k1, the only scheme supported before
+		pub fn upgrade(self) -> types::OmniverseTransactionData {
+			types::OmniverseTransactionData {
+				nonce: self.nonce,
+				chain_id: self.chain_id,
+				initiator_address: self.initiator_address,
+				from: self.from,
+				payload: self.payload,
+				signature: self.signature,
+				signature_scheme: SECP256K1,
+			}
+		}
+	}
+
+	#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+	pub struct OmniverseTx {
+		pub tx_data: OmniverseTransactionData,
+		pub timestamp: u64,
+		pub executed: bool,
+	}
+
+	impl OmniverseTx {
+		pub fn upgrade(self) -> types::OmniverseTx {
+			types::OmniverseTx {
+				tx_data: self.tx_data.upgrade(),
+				timestamp: self.timestamp,
+				executed: self.executed,
+			}
+		}
+	}
+
+	#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+	pub struct EvilTxData {
+		pub tx_omni: OmniverseTx,
+		pub his_nonce: u128,
+	}
+
+	impl EvilTxData {
+		pub fn upgrade(self) -> types::EvilTxData {
+			types::EvilTxData { tx_omni: self.tx_omni.upgrade(), his_nonce: self.his_nonce }
+		}
+	}
+}
+
+/// Migrate the pallet storage to v1, the recorded transactions are translated to carry the
+/// signature scheme, which is secp256k1 for all of them.
+pub fn migrate_to_v1<T: Config>() -> frame_support::weights::Weight {
+	let on_chain_storage_version = Pallet::<T>::on_chain_storage_version();
+	log::info!(
+		target: "runtime::omniverse-protocol",
+		"Running migration storage v1 for omniverse protocol with storage version {:?}",
+		on_chain_storage_version,
+	);
+
+	if on_chain_storage_version < 1 {
+		let mut count = 0u64;
+		TransactionRecorder::<T>::translate::<v0::OmniverseTx, _>(|_, tx| {
+			count += 1;
+			Some(tx.upgrade())
+		});
+		EvilRecorder::<T>::translate::<Vec<v0::EvilTxData>, _>(|_, records| {
+			count += 1;
+			let mut records: Vec<EvilTxData> = records.into_iter().map(|r| r.upgrade()).collect();
+			records.truncate(T::MaxEvilRecords::get() as usize);
+			BoundedVec::try_from(records).ok()
+		});
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!(
+			target: "runtime::omniverse-protocol",
+			"Running migration storage v1 for omniverse protocol with storage version {:?} done",
+			on_chain_storage_version,
+		);
+		// calculate and return migration weights
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	} else {
+		log::warn!(
+			target: "runtime::omniverse-protocol",
+			"Attempted to apply migration to v1 but failed because storage version is {:?}",
+			on_chain_storage_version,
+		);
+		T::DbWeight::get().reads(1)
+	}
+}
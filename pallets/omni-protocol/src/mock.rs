@@ -1,4 +1,5 @@
 use crate as pallet_omniverse_protocol;
+use crate::traits::OnMaliciousTransaction;
 use frame_support::parameter_types;
 use frame_support::traits::UnixTime;
 use frame_system as system;
@@ -7,7 +8,10 @@ use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
 };
-use std::time::SystemTime;
+use std::{
+	cell::RefCell,
+	time::{Duration, SystemTime},
+};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -58,24 +62,62 @@ impl system::Config for Test {
 
 parameter_types! {
 	pub ChainId: u32 = 1;
+	pub CoolingDownTime: u64 = 10;
+	pub TransactionRetention: u64 = 100;
+	pub MaliciousPenalty: u128 = 1_000;
+	pub MaxEvilRecords: u32 = 4;
 }
 
 impl pallet_omniverse_protocol::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type ChainId = ChainId;
+	type CoolingDownTime = CoolingDownTime;
 	type Timestamp = Timestamp;
+	type TransactionRetention = TransactionRetention;
+	type SlashHandler = SlashHandler;
+	type MaliciousPenalty = MaliciousPenalty;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type MaxEvilRecords = MaxEvilRecords;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut ext: sp_io::TestExternalities =
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
 
+pub static mut TIME_PAST: u64 = 0;
+
 pub struct Timestamp();
 
+impl Timestamp {
+	pub fn past(t: u64) {
+		unsafe {
+			TIME_PAST = TIME_PAST + t;
+		}
+	}
+}
+
 impl UnixTime for Timestamp {
 	fn now() -> core::time::Duration {
-		let now = SystemTime::now();
-		now.duration_since(SystemTime::UNIX_EPOCH).unwrap()
+		unsafe {
+			let now = SystemTime::now() + Duration::from_secs(TIME_PAST);
+			now.duration_since(SystemTime::UNIX_EPOCH).unwrap()
+		}
+	}
+}
+
+thread_local! {
+	pub static SLASHED: RefCell<Vec<([u8; 64], u128)>> = RefCell::new(Vec::new());
+}
+
+/// Records the slashed accounts instead of punishing them
+pub struct SlashHandler;
+
+impl OnMaliciousTransaction for SlashHandler {
+	fn on_malicious(pk: [u8; 64], penalty: u128) {
+		SLASHED.with(|slashed| slashed.borrow_mut().push((pk, penalty)));
 	}
 }
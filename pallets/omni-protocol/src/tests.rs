@@ -1,12 +1,15 @@
 use crate::{
-	mock::*, traits::OmniverseAccounts, Fungible, OmniverseTransactionData, VerifyError,
-	VerifyResult, MINT, TRANSFER,
+	mock::*, traits::OmniverseAccounts, Assets, Fungible, NonFungible, OmniverseTransactionData,
+	VerifyError, VerifyResult, ED25519, MINT, SECP256K1, TRANSFER,
 };
 use codec::Encode;
-use frame_support::assert_err;
+use frame_support::{
+	assert_err, assert_ok,
+	traits::{Get, GetStorageVersion, StorageVersion},
+};
 use secp256k1::rand::rngs::OsRng;
 use secp256k1::{ecdsa::RecoverableSignature, Message, PublicKey, Secp256k1, SecretKey};
-use sp_core::Hasher;
+use sp_core::{ed25519, Hasher, Pair};
 use sp_runtime::traits::Keccak256;
 
 const CHAIN_ID: u32 = 1;
@@ -44,7 +47,7 @@ fn encode_transaction_with_data(
 	let pk: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
 	let mut tx_data =
 		OmniverseTransactionData::new(nonce, CHAIN_ID, INITIATOR_ADDRESS, pk, payload);
-	let h = tx_data.get_raw_hash(with_ethereum);
+	let h = tx_data.get_raw_hash(&Vec::new(), with_ethereum);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -53,6 +56,24 @@ fn encode_transaction_with_data(
 	tx_data
 }
 
+fn encode_ed25519_transaction(
+	pair: &ed25519::Pair,
+	nonce: u128,
+	amount: u128,
+) -> OmniverseTransactionData {
+	let mut pk = [0u8; 64];
+	pk[..32].copy_from_slice(&pair.public().0);
+	let payload = Fungible::new(TRANSFER, pk.into(), amount).encode();
+	let mut tx_data =
+		OmniverseTransactionData::new(nonce, CHAIN_ID, INITIATOR_ADDRESS, pk, payload);
+	tx_data.set_signature_scheme(ED25519);
+	let h = tx_data.get_raw_hash(&Vec::new(), false);
+	let mut signature = [0u8; 65];
+	signature[..64].copy_from_slice(&pair.sign(&h).0);
+	tx_data.set_signature(signature);
+	tx_data
+}
+
 #[test]
 fn it_fails_for_signature_error() {
 	new_test_ext().execute_with(|| {
@@ -72,7 +93,7 @@ fn it_fails_for_signature_error() {
 		data.set_signature([0; 65]);
 
 		assert_err!(
-			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false),
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
 			VerifyError::SignatureError
 		);
 	});
@@ -94,9 +115,77 @@ fn it_fails_for_signer_not_caller_error() {
 		let data = encode_transaction(&secp, (new_secret_key, public_key), nonce, amount, false);
 
 		assert_err!(
-			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false),
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			VerifyError::SignerNotCaller
+		);
+	});
+}
+
+#[test]
+fn it_fails_for_signature_of_another_token() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let token_a = vec![1u8];
+		let token_b = vec![2u8];
+
+		// Sign the transaction for token A
+		let payload = Fungible::new(TRANSFER, pk.into(), 1).encode();
+		let mut data = OmniverseTransactionData::new(0, CHAIN_ID, INITIATOR_ADDRESS, pk, payload);
+		let message = Message::from_slice(data.get_raw_hash(&token_a, false).as_slice())
+			.expect("messages must be 32 bytes and are expected to be hashes");
+		data.set_signature(get_sig_slice(&secp.sign_ecdsa_recoverable(&message, &secret_key)));
+
+		// The signature does not recover the sender for token B
+		assert_err!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &token_b, &data, false, false),
 			VerifyError::SignerNotCaller
 		);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &token_a, &data, false, false),
+			Ok(VerifyResult::Success)
+		);
+	});
+}
+
+#[test]
+fn it_fails_for_wrong_chain_error() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let encode = |chain_id: u32, nonce: u128| {
+			let payload = Fungible::new(TRANSFER, pk.into(), 1).encode();
+			let mut data =
+				OmniverseTransactionData::new(nonce, chain_id, INITIATOR_ADDRESS, pk, payload);
+			let message = Message::from_slice(data.get_raw_hash(&Vec::new(), false).as_slice())
+				.expect("messages must be 32 bytes and are expected to be hashes");
+			data.set_signature(get_sig_slice(&secp.sign_ecdsa_recoverable(&message, &secret_key)));
+			data
+		};
+
+		// A transaction of the local chain
+		let data = encode(ChainId::get(), 0);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			Ok(VerifyResult::Success)
+		);
+
+		// A transaction signed for another chain is rejected
+		let data = encode(ChainId::get() + 1, 1);
+		assert_err!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			VerifyError::WrongChain
+		);
+
+		// Unless it is relayed on purpose
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, true),
+			Ok(VerifyResult::Success)
+		);
 	});
 }
 
@@ -115,7 +204,7 @@ fn it_fails_for_nonce_error() {
 		let data = encode_transaction(&secp, (secret_key, public_key), nonce, amount, false);
 
 		assert_err!(
-			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false),
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
 			VerifyError::NonceError
 		);
 	});
@@ -136,7 +225,8 @@ fn it_works_for_verify_transaction() {
 		// Encode transaction
 		let data = encode_transaction(&secp, (secret_key, public_key), nonce, amount, false);
 
-		let ret = OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false);
+		let ret =
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false);
 		assert!(ret.is_ok());
 		assert_eq!(ret.unwrap(), VerifyResult::Success);
 	});
@@ -157,7 +247,8 @@ fn it_works_for_malicious_transaction() {
 		// Encode transaction
 		let data = encode_transaction(&secp, (secret_key, public_key), nonce, amount, false);
 
-		let ret = OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false);
+		let ret =
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false);
 		assert!(ret.is_ok());
 		assert_eq!(ret.unwrap(), VerifyResult::Success);
 		// Encode a malicious transaction
@@ -167,10 +258,18 @@ fn it_works_for_malicious_transaction() {
 		let data_new =
 			encode_transaction_with_data(&secp, (secret_key, public_key), nonce, payload, false);
 
-		let ret =
-			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data_new, false);
+		let ret = OmniverseProtocol::verify_transaction(
+			&PALLET_NAME,
+			&Vec::new(),
+			&data_new,
+			false,
+			false,
+		);
 		assert!(ret.is_ok());
 		assert_eq!(ret.unwrap(), VerifyResult::Malicious);
+		System::assert_last_event(RuntimeEvent::OmniverseProtocol(
+			crate::Event::MaliciousTransaction { from: pk, nonce },
+		));
 	});
 }
 
@@ -189,13 +288,135 @@ fn it_works_for_duplicated_transaction() {
 		// Encode transaction
 		let data = encode_transaction(&secp, (secret_key, public_key), nonce, amount, false);
 
-		let ret = OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false);
+		let ret =
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false);
 		assert!(ret.is_ok());
 		assert_eq!(ret.unwrap(), VerifyResult::Success);
+		System::assert_last_event(RuntimeEvent::OmniverseProtocol(
+			crate::Event::TransactionVerified {
+				from: pk,
+				pallet_name: PALLET_NAME,
+				token_id: Vec::new(),
+				nonce,
+			},
+		));
 
-		let ret = OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false);
+		let ret =
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false);
 		assert!(ret.is_ok());
 		assert_eq!(ret.unwrap(), VerifyResult::Duplicated);
+		System::assert_last_event(RuntimeEvent::OmniverseProtocol(
+			crate::Event::TransactionDuplicated {
+				from: pk,
+				pallet_name: PALLET_NAME,
+				token_id: Vec::new(),
+				nonce,
+			},
+		));
+	});
+}
+
+#[test]
+fn it_works_for_prune_transactions() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		// Record several transactions
+		let mut txs = Vec::new();
+		for nonce in 0..3 {
+			let data = encode_transaction(&secp, (secret_key, public_key), nonce, 1, false);
+			assert_eq!(
+				OmniverseProtocol::verify_transaction(
+					&PALLET_NAME,
+					&Vec::new(),
+					&data,
+					false,
+					false
+				),
+				Ok(VerifyResult::Success)
+			);
+			txs.push(data);
+		}
+
+		// Only executed transactions are pruned, nonce 1 is still waiting to be executed
+		for nonce in [0, 2] {
+			OmniverseProtocol::execute(pk, PALLET_NAME, Vec::new(), nonce);
+		}
+		assert_err!(
+			OmniverseProtocol::prune_transactions(
+				RuntimeOrigin::signed(1),
+				pk,
+				PALLET_NAME,
+				Vec::new(),
+				3,
+				3
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		// The transactions are within the retention window
+		assert_ok!(OmniverseProtocol::prune_transactions(
+			RuntimeOrigin::root(),
+			pk,
+			PALLET_NAME,
+			Vec::new(),
+			3,
+			3
+		));
+		assert!(OmniverseProtocol::transaction_recorder((pk, PALLET_NAME, Vec::<u8>::new(), 0))
+			.is_some());
+
+		// At most `max` nonces below `up_to_nonce` are pruned
+		Timestamp::past(TransactionRetention::get());
+		assert_ok!(OmniverseProtocol::prune_transactions(
+			RuntimeOrigin::root(),
+			pk,
+			PALLET_NAME,
+			Vec::new(),
+			3,
+			2
+		));
+		System::assert_last_event(RuntimeEvent::OmniverseProtocol(
+			crate::Event::TransactionsPruned {
+				from: pk,
+				pallet_name: PALLET_NAME,
+				token_id: Vec::new(),
+				count: 1,
+			},
+		));
+		assert_ok!(OmniverseProtocol::prune_transactions(
+			RuntimeOrigin::root(),
+			pk,
+			PALLET_NAME,
+			Vec::new(),
+			1,
+			1
+		));
+		for nonce in [0, 2] {
+			assert!(OmniverseProtocol::transaction_recorder((
+				pk,
+				PALLET_NAME,
+				Vec::<u8>::new(),
+				nonce
+			))
+			.is_none());
+		}
+		assert!(OmniverseProtocol::transaction_recorder((pk, PALLET_NAME, Vec::<u8>::new(), 1))
+			.is_some());
+		assert_eq!(OmniverseProtocol::get_transaction_count(pk, PALLET_NAME, Vec::new()), 3);
+
+		// Duplicates of the kept transactions are still detected
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &txs[1], false, false),
+			Ok(VerifyResult::Duplicated)
+		);
+		assert_err!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &txs[0], false, false),
+			VerifyError::NonceError
+		);
 	});
 }
 
@@ -232,3 +453,354 @@ fn it_works_for_ethereum_signature() {
 		assert_eq!(signature, expect);
 	});
 }
+
+#[test]
+fn it_works_for_verify_ed25519_transaction() {
+	new_test_ext().execute_with(|| {
+		let (pair, _) = ed25519::Pair::generate();
+		let data = encode_ed25519_transaction(&pair, 0, 1);
+
+		let ret =
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false);
+		assert_eq!(ret, Ok(VerifyResult::Success));
+		assert_eq!(OmniverseProtocol::get_transaction_count(data.from, PALLET_NAME, Vec::new()), 1);
+	});
+}
+
+#[test]
+fn it_fails_for_ed25519_signature_error() {
+	new_test_ext().execute_with(|| {
+		let (pair, _) = ed25519::Pair::generate();
+		let mut data = encode_ed25519_transaction(&pair, 0, 1);
+
+		// Tamper the signature
+		let mut signature = data.signature;
+		signature[0] ^= 1;
+		data.set_signature(signature);
+
+		assert_err!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			VerifyError::SignatureError
+		);
+
+		// A signature from another key
+		let (other, _) = ed25519::Pair::generate();
+		let mut signature = [0u8; 65];
+		signature[..64].copy_from_slice(&other.sign(&data.get_raw_hash(&Vec::new(), false)).0);
+		data.set_signature(signature);
+
+		assert_err!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			VerifyError::SignatureError
+		);
+	});
+}
+
+#[test]
+fn it_works_for_verify_non_fungible_payloads() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let assets_payload = Assets::new(TRANSFER, pk.into(), 1).encode();
+		let non_fungible_payload =
+			NonFungible { op: MINT, ex_data: pk.into(), token_id: 1 }.encode();
+		let raw_payload = vec![1u8];
+		for (nonce, payload) in
+			[assets_payload, non_fungible_payload, raw_payload].into_iter().enumerate()
+		{
+			let data = encode_transaction_with_data(
+				&secp,
+				(secret_key, public_key),
+				nonce as u128,
+				payload,
+				false,
+			);
+			let ret = OmniverseProtocol::verify_transaction(
+				&PALLET_NAME,
+				&Vec::new(),
+				&data,
+				false,
+				false,
+			);
+			assert_eq!(ret, Ok(VerifyResult::Success));
+		}
+	});
+}
+
+#[test]
+fn it_works_for_verify_transactions() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let txs: Vec<OmniverseTransactionData> = (0..3)
+			.map(|nonce| encode_transaction(&secp, (secret_key, public_key), nonce, 1, false))
+			.collect();
+
+		// A nonce gap and a duplicated transaction do not stop the rest of the batch
+		let batch = vec![txs[0].clone(), txs[2].clone(), txs[0].clone(), txs[1].clone()];
+		assert_eq!(
+			OmniverseProtocol::verify_transactions(&PALLET_NAME, &Vec::new(), batch, false, false),
+			vec![
+				Ok(VerifyResult::Success),
+				Err(VerifyError::NonceError),
+				Ok(VerifyResult::Duplicated),
+				Ok(VerifyResult::Success),
+			]
+		);
+		assert_eq!(OmniverseProtocol::get_transaction_count(pk, PALLET_NAME, Vec::new()), 2);
+	});
+}
+
+#[test]
+fn it_works_for_slashing_malicious_transactions() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		for nonce in 0..2 {
+			let data = encode_transaction(&secp, (secret_key, public_key), nonce, 1, false);
+			assert_eq!(
+				OmniverseProtocol::verify_transaction(
+					&PALLET_NAME,
+					&Vec::new(),
+					&data,
+					false,
+					false
+				),
+				Ok(VerifyResult::Success)
+			);
+		}
+
+		// Conflict twice with nonce 0 and once with nonce 1
+		for (nonce, amount) in [(0, 2), (0, 3), (1, 2)] {
+			let data = encode_transaction(&secp, (secret_key, public_key), nonce, amount, false);
+			assert_eq!(
+				OmniverseProtocol::verify_transaction(
+					&PALLET_NAME,
+					&Vec::new(),
+					&data,
+					false,
+					false
+				),
+				Ok(VerifyResult::Malicious)
+			);
+		}
+
+		// The account is slashed once per conflicting nonce
+		assert_eq!(OmniverseProtocol::evil_recorder(pk).map(|er| er.len()), Some(3));
+		assert_eq!(
+			SLASHED.with(|slashed| slashed.borrow().clone()),
+			vec![(pk, MaliciousPenalty::get()), (pk, MaliciousPenalty::get())]
+		);
+	});
+}
+
+#[test]
+fn it_works_for_evil_records_beyond_the_limit() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let data = encode_transaction(&secp, (secret_key, public_key), 0, 1, false);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			Ok(VerifyResult::Success)
+		);
+
+		// Conflicts past the limit are still malicious, but no longer recorded
+		for amount in 2..=MaxEvilRecords::get() as u128 + 2 {
+			let data = encode_transaction(&secp, (secret_key, public_key), 0, amount, false);
+			assert_eq!(
+				OmniverseProtocol::verify_transaction(
+					&PALLET_NAME,
+					&Vec::new(),
+					&data,
+					false,
+					false
+				),
+				Ok(VerifyResult::Malicious)
+			);
+		}
+
+		assert_eq!(
+			OmniverseProtocol::evil_recorder(pk).map(|er| er.len()),
+			Some(MaxEvilRecords::get() as usize)
+		);
+		assert!(OmniverseProtocol::is_malicious(pk));
+
+		// A conflict of another nonce once the records are full is malicious all the same
+		let data = encode_transaction(&secp, (secret_key, public_key), 1, 1, false);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			Ok(VerifyResult::Success)
+		);
+		let data = encode_transaction(&secp, (secret_key, public_key), 1, 2, false);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			Ok(VerifyResult::Malicious)
+		);
+		assert_eq!(
+			OmniverseProtocol::evil_recorder(pk).map(|er| er.len()),
+			Some(MaxEvilRecords::get() as usize)
+		);
+		assert!(OmniverseProtocol::is_malicious(pk));
+	});
+}
+
+#[test]
+fn it_works_for_clear_evil_record() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		assert_err!(
+			OmniverseProtocol::clear_evil_record(RuntimeOrigin::root(), pk),
+			crate::Error::<Test>::NoEvilRecord
+		);
+
+		// Flag the account with conflicting transactions
+		for (amount, result) in [(1, VerifyResult::Success), (2, VerifyResult::Malicious)] {
+			let data = encode_transaction(&secp, (secret_key, public_key), 0, amount, false);
+			assert_eq!(
+				OmniverseProtocol::verify_transaction(
+					&PALLET_NAME,
+					&Vec::new(),
+					&data,
+					false,
+					false
+				),
+				Ok(result)
+			);
+		}
+		assert!(OmniverseProtocol::is_malicious(pk));
+
+		assert_err!(
+			OmniverseProtocol::clear_evil_record(RuntimeOrigin::signed(1), pk),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(OmniverseProtocol::clear_evil_record(RuntimeOrigin::root(), pk));
+		System::assert_last_event(RuntimeEvent::OmniverseProtocol(
+			crate::Event::EvilRecordCleared { from: pk },
+		));
+		assert!(!OmniverseProtocol::is_malicious(pk));
+
+		// The next transaction verifies normally
+		let data = encode_transaction(&secp, (secret_key, public_key), 1, 1, false);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			Ok(VerifyResult::Success)
+		);
+	});
+}
+
+#[test]
+fn it_works_for_missing_nonces() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		assert_eq!(OmniverseProtocol::next_nonce(pk, PALLET_NAME, Vec::new()), 0);
+		assert_eq!(OmniverseProtocol::missing_nonces(pk, PALLET_NAME, Vec::new(), 0), None);
+		assert_eq!(OmniverseProtocol::missing_nonces(pk, PALLET_NAME, Vec::new(), 3), Some((0, 2)));
+
+		let data = encode_transaction(&secp, (secret_key, public_key), 0, 1, false);
+		assert_eq!(
+			OmniverseProtocol::verify_transaction(&PALLET_NAME, &Vec::new(), &data, false, false),
+			Ok(VerifyResult::Success)
+		);
+		assert_eq!(OmniverseProtocol::next_nonce(pk, PALLET_NAME, Vec::new()), 1);
+		// A submitted nonce is not a gap
+		assert_eq!(OmniverseProtocol::missing_nonces(pk, PALLET_NAME, Vec::new(), 0), None);
+		assert_eq!(OmniverseProtocol::missing_nonces(pk, PALLET_NAME, Vec::new(), 1), None);
+		assert_eq!(OmniverseProtocol::missing_nonces(pk, PALLET_NAME, Vec::new(), 2), Some((1, 1)));
+	});
+}
+
+#[test]
+fn it_works_for_migrating_to_v1() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		// A transaction recorded before the signature scheme was
+		let data = encode_transaction(&secp, (secret_key, public_key), 0, 1, false);
+		let old = crate::migration::v0::OmniverseTx {
+			tx_data: crate::migration::v0::OmniverseTransactionData {
+				nonce: data.nonce,
+				chain_id: data.chain_id,
+				initiator_address: data.initiator_address.clone(),
+				from: data.from,
+				payload: data.payload.clone(),
+				signature: data.signature,
+			},
+			timestamp: 1,
+			executed: true,
+		};
+		let key = crate::TransactionRecorder::<Test>::hashed_key_for((
+			pk,
+			PALLET_NAME,
+			Vec::<u8>::new(),
+			0u128,
+		));
+		frame_support::storage::unhashed::put(&key, &old);
+		StorageVersion::new(0).put::<OmniverseProtocol>();
+
+		crate::migration::migrate_to_v1::<Test>();
+		let tx = OmniverseProtocol::transaction_recorder((pk, PALLET_NAME, Vec::<u8>::new(), 0))
+			.unwrap();
+		assert_eq!(tx.tx_data, data);
+		assert_eq!(tx.tx_data.signature_scheme, SECP256K1);
+		assert!(tx.executed);
+		assert_eq!(OmniverseProtocol::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn it_works_for_migrating_evil_records_beyond_the_limit_to_v1() {
+	new_test_ext().execute_with(|| {
+		let pk = [1u8; 64];
+		let old: Vec<_> = (0..MaxEvilRecords::get() as u128 + 2)
+			.map(|nonce| crate::migration::v0::EvilTxData {
+				tx_omni: crate::migration::v0::OmniverseTx {
+					tx_data: crate::migration::v0::OmniverseTransactionData {
+						nonce,
+						chain_id: 1,
+						initiator_address: Vec::new(),
+						from: pk,
+						payload: vec![2],
+						signature: [3; 65],
+					},
+					timestamp: 1,
+					executed: false,
+				},
+				his_nonce: nonce,
+			})
+			.collect();
+		let key = crate::EvilRecorder::<Test>::hashed_key_for(pk);
+		frame_support::storage::unhashed::put(&key, &old);
+		StorageVersion::new(0).put::<OmniverseProtocol>();
+
+		// The records past the limit are dropped, the account is still malicious
+		crate::migration::migrate_to_v1::<Test>();
+		let records = OmniverseProtocol::evil_recorder(pk).unwrap();
+		assert_eq!(records.len(), MaxEvilRecords::get() as usize);
+		assert_eq!(records[0], old[0].clone().upgrade());
+		assert!(OmniverseProtocol::is_malicious(pk));
+	});
+}
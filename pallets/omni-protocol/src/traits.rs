@@ -2,15 +2,49 @@ use crate::{OmniverseTransactionData, OmniverseTx, VerifyError, VerifyResult};
 use sp_std::vec::Vec;
 
 pub trait OmniverseAccounts {
+	/// Transactions of other chains are rejected unless they are `relayed` on purpose
 	fn verify_transaction(
 		pallet_name: &[u8],
 		token_id: &[u8],
 		data: &OmniverseTransactionData,
 		with_ethereum: bool,
+		relayed: bool,
 	) -> Result<VerifyResult, VerifyError>;
+	/// Verify a batch of transactions one by one, a failed one does not stop the others
+	fn verify_transactions(
+		pallet_name: &[u8],
+		token_id: &[u8],
+		data: Vec<OmniverseTransactionData>,
+		with_ethereum: bool,
+		relayed: bool,
+	) -> Vec<Result<VerifyResult, VerifyError>> {
+		data.iter()
+			.map(|tx| Self::verify_transaction(pallet_name, token_id, tx, with_ethereum, relayed))
+			.collect()
+	}
 	fn get_transaction_count(pk: [u8; 64], pallet_name: Vec<u8>, token_id: Vec<u8>) -> u128;
+	/// The nonce the next transaction of `pk` must be signed with
+	fn next_nonce(pk: [u8; 64], pallet_name: Vec<u8>, token_id: Vec<u8>) -> u128 {
+		Self::get_transaction_count(pk, pallet_name, token_id)
+	}
+	/// The first and the last nonce skipped if a transaction with `nonce` is submitted next, `None`
+	/// if there is no gap
+	fn missing_nonces(
+		pk: [u8; 64],
+		pallet_name: Vec<u8>,
+		token_id: Vec<u8>,
+		nonce: u128,
+	) -> Option<(u128, u128)> {
+		let next_nonce = Self::next_nonce(pk, pallet_name, token_id);
+		if nonce > next_nonce {
+			Some((next_nonce, nonce - 1))
+		} else {
+			None
+		}
+	}
 	fn is_malicious(pk: [u8; 64]) -> bool;
 	fn get_chain_id() -> u32;
+	fn get_cooling_down_time() -> u64;
 	fn get_transaction_data(
 		pk: [u8; 64],
 		pallet_name: Vec<u8>,
@@ -24,3 +58,12 @@ pub trait OmniverseAccounts {
 		nonce: u128,
 	);
 }
+
+/// Handles the penalty of an account which signed conflicting transactions
+pub trait OnMaliciousTransaction {
+	fn on_malicious(pk: [u8; 64], penalty: u128);
+}
+
+impl OnMaliciousTransaction for () {
+	fn on_malicious(_pk: [u8; 64], _penalty: u128) {}
+}
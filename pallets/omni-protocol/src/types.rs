@@ -6,6 +6,11 @@ use sp_std::vec::Vec;
 pub const TRANSFER: u8 = 0_u8;
 pub const MINT: u8 = 1_u8;
 pub const BURN: u8 = 2_u8;
+pub const BATCH_MINT: u8 = 3_u8;
+pub const SET_METADATA: u8 = 4_u8;
+
+pub const SECP256K1: u8 = 0_u8;
+pub const ED25519: u8 = 1_u8;
 
 // #[derive(Decode, Encode, Debug)]
 // pub struct TokenOpcode {
@@ -52,6 +57,12 @@ pub struct NonFungible {
 	pub token_id: u128,
 }
 
+impl NonFungible {
+	pub fn new(op: u8, ex_data: Vec<u8>, token_id: u128) -> Self {
+		Self { op, ex_data, token_id }
+	}
+}
+
 #[derive(Decode, Encode, Debug)]
 pub struct MintTokenOp {
 	pub to: [u8; 64],
@@ -64,6 +75,31 @@ impl MintTokenOp {
 	}
 }
 
+/// The `ex_data` of a non-fungible `BATCH_MINT`, the items are all minted to `to`
+#[derive(Decode, Encode, Debug)]
+pub struct BatchMintOp {
+	pub to: [u8; 64],
+	pub item_ids: Vec<u128>,
+}
+
+impl BatchMintOp {
+	pub fn new(to: [u8; 64], item_ids: Vec<u128>) -> Self {
+		Self { to, item_ids }
+	}
+}
+
+#[derive(Decode, Encode, Debug)]
+pub struct TransferWithPriceOp {
+	pub to: [u8; 64],
+	pub price: u128,
+}
+
+impl TransferWithPriceOp {
+	pub fn new(to: [u8; 64], price: u128) -> Self {
+		Self { to, price }
+	}
+}
+
 #[derive(Decode, Encode, Debug)]
 pub struct TransferTokenOp {
 	pub to: [u8; 64],
@@ -88,6 +124,7 @@ pub enum VerifyError {
 	SignatureError,
 	NonceError,
 	SignerNotCaller,
+	WrongChain,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
@@ -98,6 +135,7 @@ pub struct OmniverseTransactionData {
 	pub from: [u8; 64],
 	pub payload: Vec<u8>,
 	pub signature: [u8; 65],
+	pub signature_scheme: u8,
 }
 
 impl OmniverseTransactionData {
@@ -108,16 +146,28 @@ impl OmniverseTransactionData {
 		from: [u8; 64],
 		payload: Vec<u8>,
 	) -> Self {
-		Self { nonce, chain_id, initiator_address, from, payload, signature: [0; 65] }
+		Self {
+			nonce,
+			chain_id,
+			initiator_address,
+			from,
+			payload,
+			signature: [0; 65],
+			signature_scheme: SECP256K1,
+		}
 	}
 
-	pub fn get_raw_hash(&self, with_ethereum: bool) -> [u8; 32] {
-		functions::get_transaction_hash(self, with_ethereum)
+	pub fn get_raw_hash(&self, token_id: &[u8], with_ethereum: bool) -> [u8; 32] {
+		functions::get_transaction_hash(self, token_id, with_ethereum)
 	}
 
 	pub fn set_signature(&mut self, signature: [u8; 65]) {
 		self.signature = signature;
 	}
+
+	pub fn set_signature_scheme(&mut self, signature_scheme: u8) {
+		self.signature_scheme = signature_scheme;
+	}
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
@@ -0,0 +1,51 @@
+//! Runtime API definition for the omniverse swap pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use pallet_omniverse_protocol::OmniverseTransactionData;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait OmniSwapApi {
+		/// The amount bought by selling `amount_in` of token x, or of token y if `token_in_is_x`
+		/// is false
+		fn quote_exact_in(
+			trading_pair: Vec<u8>,
+			token_in_is_x: bool,
+			amount_in: u128,
+		) -> Option<u128>;
+
+		/// The amount to sell of token x to buy `amount_out` of token y, or of token y for token x
+		/// if `token_out_is_y` is false
+		fn quote_exact_out(
+			trading_pair: Vec<u8>,
+			token_out_is_y: bool,
+			amount_out: u128,
+		) -> Option<u128>;
+
+		/// The `min_token` to pass to a swap selling `amount_in`, the quote of `quote_exact_in`
+		/// reduced by `slippage_bps`
+		fn quote_with_slippage(
+			trading_pair: Vec<u8>,
+			token_in_is_x: bool,
+			amount_in: u128,
+			slippage_bps: u16,
+		) -> Option<u128>;
+
+		/// The amounts of token x and token y paid out for burning `liquidity` of a trading pair
+		fn preview_remove_liquidity(trading_pair: Vec<u8>, liquidity: u128) -> Option<(u128, u128)>;
+
+		/// The reserves of a trading pair
+		fn get_reserves(trading_pair: Vec<u8>) -> Option<(u128, u128)>;
+
+		/// The balance of `pk` in the token `token_id` held by the swap pallet
+		fn swap_balance(pk: [u8; 64], token_id: Vec<u8>) -> u128;
+
+		/// The deposits of `pk` waiting to be confirmed: token_id, nonce and the omniverse
+		/// transaction
+		fn pending_deposits(pk: [u8; 64]) -> Vec<(Vec<u8>, u128, OmniverseTransactionData)>;
+
+		/// The withdrawals of `pk` waiting to be confirmed: token_id and amount
+		fn pending_withdrawals(pk: [u8; 64]) -> Vec<(Vec<u8>, u128)>;
+	}
+}
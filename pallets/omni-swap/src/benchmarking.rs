@@ -0,0 +1,373 @@
+//! Omniverse swap pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_support::{
+	dispatch::UnfilteredDispatchable,
+	traits::{EnsureOrigin, Get},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use pallet_assets::{traits::OmniverseTokenFactoryHandler, PALLET_NAME};
+use pallet_omniverse_protocol::traits::OmniverseAccounts;
+use sp_std::prelude::*;
+
+use crate::Pallet as OmniSwap;
+
+const RESERVE: u128 = 1_000_000_000;
+const MAX_PATH: u32 = 8;
+
+/// Pad the MPC members, then the retired MPC keys, with keys holding nothing up to `m` keys in
+/// all, so that checking the backing of a token reads the balances of `m` keys
+fn fill_mpc_keys<T: Config>(m: u32) {
+	let mut members = MpcMembers::<T>::get().into_inner();
+	let mut retired = Vec::new();
+	for i in members.len() as u32..m {
+		let mut key = [0xff; 64];
+		key[0..4].copy_from_slice(&i.to_le_bytes());
+		if members.len() < T::MaxMpcMembers::get() as usize {
+			members.push(key);
+		} else {
+			retired.push(key);
+		}
+	}
+	MpcMembers::<T>::put(BoundedVec::try_from(members).unwrap());
+	RetiredMpcMembers::<T>::put(BoundedVec::try_from(retired).unwrap());
+}
+
+/// A valid public key and the account it signs for, funded with both tokens of the pair, which
+/// the MPC holds
+fn funded_trader<T: Config>(
+	token_x_id: &Vec<u8>,
+	token_y_id: &Vec<u8>,
+) -> ([u8; 64], T::AccountId) {
+	let pk = GetDefaultMpc();
+	let caller = OmniSwap::<T>::to_account(&pk).unwrap();
+	let mpc = MpcMembers::<T>::get()[0];
+	for token_id in [token_x_id, token_y_id] {
+		T::BenchmarkHelper::mint(token_id.clone(), mpc, RESERVE * 2);
+		Balance::<T>::insert(pk, token_id, RESERVE * 2);
+	}
+	(pk, caller)
+}
+
+/// Create a pool with the protocol fee on so that the fee is minted as well
+fn create_pool<T: Config>() -> (Vec<u8>, [u8; 64], T::AccountId) {
+	let trading_pair = b"benchmark".to_vec();
+	let (token_x_id, token_y_id) = (vec![1], vec![2]);
+	let (pk, caller) = funded_trader::<T>(&token_x_id, &token_y_id);
+	FeeTo::<T>::put([1; 64]);
+	assert!(OmniSwap::<T>::add_liquidity(
+		RawOrigin::Signed(caller.clone()).into(),
+		trading_pair.clone(),
+		pk,
+		RESERVE,
+		RESERVE,
+		1,
+		1,
+		token_x_id,
+		token_y_id,
+		None,
+		None,
+	)
+	.is_ok());
+	(trading_pair, pk, caller)
+}
+
+benchmarks! {
+	swap_x2y {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), trading_pair.clone(), pk, 1_000, 1, None, None, None)
+	verify {
+		assert_eq!(TradingPairs::<T>::get(&trading_pair).map(|(x, _)| x), Some(RESERVE + 1_000));
+	}
+
+	swap_y2x {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), trading_pair.clone(), pk, 1_000, 1, None, None, None)
+	verify {
+		assert_eq!(TradingPairs::<T>::get(&trading_pair).map(|(_, y)| y), Some(RESERVE + 1_000));
+	}
+
+	add_liquidity {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+	}: _(
+		RawOrigin::Signed(caller),
+		trading_pair.clone(),
+		pk,
+		RESERVE,
+		RESERVE,
+		1,
+		1,
+		vec![1],
+		vec![2],
+		None,
+		None
+	)
+	verify {
+		assert_eq!(TradingPairs::<T>::get(&trading_pair), Some((RESERVE * 2, RESERVE * 2)));
+	}
+
+	remove_liquidity {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+		let liquidity = Liquidity::<T>::get((trading_pair.clone(), pk)).unwrap();
+	}: _(RawOrigin::Signed(caller), trading_pair.clone(), pk, liquidity / 2, 1, 1, None)
+	verify {
+		assert_eq!(
+			Liquidity::<T>::get((trading_pair, pk)),
+			Some(liquidity - liquidity / 2)
+		);
+	}
+
+	deposit {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let mpc = MpcMembers::<T>::get()[0];
+		let (token_id, data) = T::BenchmarkHelper::deposit_transaction(mpc, 1_000);
+		let caller = OmniSwap::<T>::to_account(&mpc).unwrap();
+		// The transfer is executed before it is deposited, so it is credited at once
+		assert!(T::OmniverseToken::send_transaction_external(token_id.clone(), &data).is_ok());
+		T::BenchmarkHelper::mint(token_id.clone(), mpc, 1_000);
+		T::OmniverseProtocol::execute(
+			data.from,
+			PALLET_NAME.to_vec(),
+			token_id.clone(),
+			data.nonce,
+		);
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), token_id.clone(), data.clone(), true)
+	verify {
+		assert_eq!(Balance::<T>::get(data.from, &token_id), Some(1_000));
+	}
+
+	withdraw {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let token_id = vec![1];
+		let (pk, caller) = funded_trader::<T>(&token_id, &vec![2]);
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), pk, token_id.clone(), 1_000)
+	verify {
+		assert_eq!(Withdrawals::<T>::get((pk, token_id)), Some(1_000));
+	}
+
+	deposit_comfirm {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let mpc = MpcMembers::<T>::get()[0];
+		let (token_id, data) = T::BenchmarkHelper::deposit_transaction(mpc, 1_000);
+		let caller = OmniSwap::<T>::to_account(&mpc).unwrap();
+		assert!(OmniSwap::<T>::deposit(
+			RawOrigin::Signed(caller.clone()).into(),
+			token_id.clone(),
+			data.clone(),
+			false
+		)
+		.is_ok());
+		// The transfer to the MPC is executed by the omniverse token
+		T::BenchmarkHelper::mint(token_id.clone(), mpc, 1_000);
+		T::OmniverseProtocol::execute(
+			data.from,
+			PALLET_NAME.to_vec(),
+			token_id.clone(),
+			data.nonce,
+		);
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), data.from, token_id.clone(), data.nonce)
+	verify {
+		assert_eq!(Balance::<T>::get(data.from, &token_id), Some(1_000));
+	}
+
+	reclaim_deposit {
+		let mpc = MpcMembers::<T>::get()[0];
+		let (token_id, data) = T::BenchmarkHelper::deposit_transaction(mpc, 1_000);
+		assert!(OmniSwap::<T>::deposit(
+			RawOrigin::Signed(OmniSwap::<T>::to_account(&mpc).unwrap()).into(),
+			token_id.clone(),
+			data.clone(),
+			false
+		)
+		.is_ok());
+		T::BenchmarkHelper::advance_time(T::DepositExpiry::get());
+		let caller = OmniSwap::<T>::to_account(&data.from).unwrap();
+	}: _(RawOrigin::Signed(caller), data.from, token_id.clone(), data.nonce)
+	verify {
+		assert!(!DepositRecords::<T>::contains_key((data.from, token_id, data.nonce)));
+	}
+
+	withdraw_comfirm {
+		let s in 0 .. T::MaxMpcMembers::get() - 1;
+		let pk = GetDefaultMpc();
+		let caller = OmniSwap::<T>::to_account(&pk).unwrap();
+		// The transfer pays the withdrawal out of the key sending it, which the other keys confirm
+		let (token_id, data) = T::BenchmarkHelper::deposit_transaction(pk, 1_000);
+		let hash = data.get_raw_hash(&token_id, false);
+		let (members, signatures): (Vec<_>, Vec<_>) =
+			T::BenchmarkHelper::mpc_signatures(hash, s).into_iter().unzip();
+		MpcMembers::<T>::put(BoundedVec::try_from([vec![data.from], members].concat()).unwrap());
+		MpcThreshold::<T>::put(s + 1);
+		Withdrawals::<T>::insert((pk, token_id.clone()), 1_000);
+		TotalDeposited::<T>::insert(&token_id, 1_000);
+	}: _(RawOrigin::Signed(caller), pk, token_id.clone(), data, signatures)
+	verify {
+		assert_eq!(Withdrawals::<T>::get((pk, token_id)), None);
+	}
+
+	cancel_withdrawal {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let token_id = vec![1];
+		let (pk, caller) = funded_trader::<T>(&token_id, &vec![2]);
+		fill_mpc_keys::<T>(m);
+		assert!(OmniSwap::<T>::withdraw(
+			RawOrigin::Signed(caller.clone()).into(),
+			pk,
+			token_id.clone(),
+			1_000
+		)
+		.is_ok());
+	}: _(RawOrigin::Signed(caller), pk, token_id.clone(), 1_000)
+	verify {
+		assert_eq!(Withdrawals::<T>::get((pk, token_id)), None);
+	}
+
+	swap_x2y_exact_out {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), trading_pair.clone(), pk, 1_000, RESERVE, None, None)
+	verify {
+		assert_eq!(TradingPairs::<T>::get(&trading_pair).map(|(_, y)| y), Some(RESERVE - 1_000));
+	}
+
+	swap_y2x_exact_out {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), trading_pair.clone(), pk, 1_000, RESERVE, None, None)
+	verify {
+		assert_eq!(TradingPairs::<T>::get(&trading_pair).map(|(x, _)| x), Some(RESERVE - 1_000));
+	}
+
+	swap_exact_tokens_for_tokens {
+		let p in 1 .. MAX_PATH;
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+		// Each hop trades back what the previous one bought
+		let path = vec![trading_pair.clone(); p as usize];
+	}: _(RawOrigin::Signed(caller), pk, 1_000_000, 1, path, None)
+	verify {
+		assert!(PairVolume::<T>::get(&trading_pair) != (0, 0));
+	}
+
+	remove_all_liquidity {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let (trading_pair, pk, caller) = create_pool::<T>();
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), trading_pair.clone(), pk, 1, 1, None)
+	verify {
+		assert_eq!(Liquidity::<T>::get((trading_pair, pk)), Some(0));
+	}
+
+	set_mpc_members {
+		let m in 1 .. T::MaxMpcMembers::get();
+		let members: Vec<[u8; 64]> = (0..m)
+			.map(|i| {
+				let mut member = [0; 64];
+				member[0..4].copy_from_slice(&i.to_le_bytes());
+				member
+			})
+			.collect();
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::set_mpc_members { members: members.clone(), threshold: m };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(MpcMembers::<T>::get(), members);
+	}
+
+	propose_mpc {
+		let old_mpc = MpcMembers::<T>::get()[0];
+		let (new_mpc, _) = T::BenchmarkHelper::mpc_signatures([0; 32], 1)[0];
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::propose_mpc { old_mpc, new_mpc };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(PendingMpc::<T>::get(), Some((old_mpc, new_mpc)));
+	}
+
+	accept_mpc {
+		let old_mpc = MpcMembers::<T>::get()[0];
+		let (new_mpc, _) = T::BenchmarkHelper::mpc_signatures([0; 32], 1)[0];
+		PendingMpc::<T>::put((old_mpc, new_mpc));
+		// The old key is retired as a deposit to it is still pending
+		MpcPendingDeposits::<T>::insert(old_mpc, 1);
+		let caller = OmniSwap::<T>::to_account(&new_mpc).unwrap();
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(MpcMembers::<T>::get()[0], new_mpc);
+	}
+
+	set_fee_to {
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::set_fee_to { fee_to: Some([1; 64]) };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(FeeTo::<T>::get(), Some([1; 64]));
+	}
+
+	set_paused {
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::set_paused { paused: true };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Paused::<T>::get());
+	}
+
+	set_bridge_fees {
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::set_bridge_fees { deposit_fee_bps: 10, withdraw_fee_bps: 20 };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(WithdrawFeeBps::<T>::get(), 20);
+	}
+
+	transfer_balance {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let token_id = vec![1];
+		let (pk, caller) = funded_trader::<T>(&token_id, &vec![2]);
+		fill_mpc_keys::<T>(m);
+	}: _(RawOrigin::Signed(caller), pk, [1; 64], token_id.clone(), 1_000)
+	verify {
+		assert_eq!(Balance::<T>::get([1; 64], &token_id), Some(1_000));
+	}
+
+	approve {
+		let token_id = vec![1];
+		let (pk, caller) = funded_trader::<T>(&token_id, &vec![2]);
+	}: _(RawOrigin::Signed(caller), pk, [1; 64], token_id.clone(), 1_000)
+	verify {
+		assert_eq!(Allowances::<T>::get((pk, [1; 64], token_id)), Some(1_000));
+	}
+
+	transfer_balance_from {
+		let m in 1 .. T::MaxMpcMembers::get() * 2;
+		let token_id = vec![1];
+		let (pk, caller) = funded_trader::<T>(&token_id, &vec![2]);
+		fill_mpc_keys::<T>(m);
+		// The allowance is only partly spent so that it is updated rather than removed
+		Balance::<T>::insert([1; 64], &token_id, 1_000);
+		Allowances::<T>::insert(([1; 64], pk, token_id.clone()), 2_000);
+	}: _(RawOrigin::Signed(caller), pk, [1; 64], [2; 64], token_id.clone(), 1_000)
+	verify {
+		assert_eq!(Balance::<T>::get([2; 64], &token_id), Some(1_000));
+	}
+
+	impl_benchmark_test_suite!(OmniSwap, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -14,27 +14,70 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+mod types;
+pub use types::*;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+pub mod migration;
+
 // current support assets
 // pub static PALLET_NAME: [u8; 6] = [0x61, 0x73, 0x73, 0x65, 0x74, 0x73];
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::UnixTime};
 	use frame_system::pallet_prelude::*;
 	use sp_std::vec::Vec;
 	// use sp_runtime::traits::TrailingZeroInput;
-	use pallet_assets::{traits::OmniverseTokenFactoryHandler, PALLET_NAME};
+	use pallet_assets::{traits::OmniverseTokenFactoryHandler, FactoryResult, PALLET_NAME};
 	use pallet_omniverse_protocol::{
-		traits::OmniverseAccounts, Fungible, OmniverseTransactionData,
+		traits::OmniverseAccounts, Fungible, OmniverseTransactionData, TRANSFER,
 	};
 	use secp256k1::PublicKey;
-	use sp_core::Hasher;
+	use sp_core::{Hasher, U256};
 	use sp_runtime::traits::BlakeTwo256;
 	use sp_runtime::traits::IntegerSquareRoot;
+	use sp_runtime::Permill;
+	use sp_runtime::SaturatedConversion;
+
+	use crate::PoolInfo;
+
+	/// The scale of the prices accumulated in `PriceCumulative`
+	pub const PRICE_PRECISION: u128 = 1_000_000_000_000;
+	/// The share of a swap input that is traded, out of `FEE_DENOMINATOR`, the rest is kept in the
+	/// reserves as the liquidity provider fee
+	pub const FEE_NUMERATOR: u128 = 997;
+	pub const FEE_DENOMINATOR: u128 = 1000;
+	/// The liquidity locked forever on the first mint of a pool
+	pub const MINIMUM_LIQUIDITY: u128 = 1000;
+	/// The public key the minimum liquidity is locked to, nobody can sign for it
+	pub const BURN_PK: [u8; 64] = [0; 64];
+	/// The `tokens_sold` of a swap that sells the whole balance of the input token
+	pub const SELL_ALL: u128 = u128::MAX;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
+
+	#[cfg(feature = "runtime-benchmarks")]
+	pub trait BenchmarkHelper {
+		/// Mint `amount` of the token `token_id` to `to`, the token is created if it does not exist
+		fn mint(token_id: Vec<u8>, to: [u8; 64], amount: u128);
+		/// A token and an omniverse transaction of it transferring `amount` to `to`, which is
+		/// accepted by the omniverse token
+		fn deposit_transaction(to: [u8; 64], amount: u128) -> (Vec<u8>, OmniverseTransactionData);
+		/// The public keys of `count` keys and their signatures of `hash`
+		fn mpc_signatures(hash: [u8; 32], count: u32) -> Vec<([u8; 64], [u8; 65])>;
+		/// Move the time returned by `Timestamp` forward by `secs`
+		fn advance_time(secs: u64);
+	}
+
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -42,6 +85,20 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type OmniverseProtocol: OmniverseAccounts;
 		type OmniverseToken: OmniverseTokenFactoryHandler;
+		/// The origin allowed to set the receiver of the protocol fee and to rotate the MPC members
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		type Timestamp: UnixTime;
+		/// The seconds after which a deposit that is still not executed can be reclaimed
+		#[pallet::constant]
+		type DepositExpiry: Get<u64>;
+		/// The maximum number of MPC members, and of retired MPC keys still serving deposits
+		#[pallet::constant]
+		type MaxMpcMembers: Get<u32>;
+		#[cfg(feature = "runtime-benchmarks")]
+		/// A set of helper functions for benchmarking.
+		type BenchmarkHelper: BenchmarkHelper;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::storage]
@@ -56,6 +113,13 @@ pub mod pallet {
 	#[pallet::getter(fn liquidity)]
 	pub type Liquidity<T: Config> = StorageMap<_, Blake2_128Concat, (Vec<u8>, [u8; 64]), u128>;
 
+	/// key: (trading pair, provider)
+	/// value: the block of the last liquidity change of the provider
+	#[pallet::storage]
+	#[pallet::getter(fn liquidity_updated_at)]
+	pub type LiquidityUpdatedAt<T: Config> =
+		StorageMap<_, Blake2_128Concat, (Vec<u8>, [u8; 64]), T::BlockNumber>;
+
 	// #[pallet::storage]
 	// #[pallet::getter(fn balance)]
 	// pub type Balance<T: Config> =
@@ -68,10 +132,18 @@ pub mod pallet {
 	// #[pallet::getter(fn public_key)]
 	// pub type PublicKey<T:Config> = StorageMap<_, Blake2_128Concat, T::AccountId, [u8; 64]>;
 
+	/// key: pk, token_id and nonce of the omniverse transaction
+	/// value: the omniverse transaction and the time it is deposited
 	#[pallet::storage]
 	#[pallet::getter(fn deposit_record)]
 	pub type DepositRecords<T: Config> =
-		StorageMap<_, Blake2_128Concat, ([u8; 64], Vec<u8>, u128), OmniverseTransactionData>;
+		StorageMap<_, Blake2_128Concat, ([u8; 64], Vec<u8>, u128), (OmniverseTransactionData, u64)>;
+
+	/// key: pk, token_id and nonce of the omniverse transaction of a credited deposit
+	#[pallet::storage]
+	#[pallet::getter(fn confirmed_deposit)]
+	pub type ConfirmedDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, ([u8; 64], Vec<u8>, u128), (), OptionQuery>;
 
 	/// key: pk and token_id
 	/// value: balance
@@ -80,15 +152,170 @@ pub mod pallet {
 	pub type Balance<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, [u8; 64], Blake2_128Concat, Vec<u8>, u128>;
 
+	/// key: token_id
+	/// value: the deposits credited less the withdrawals paid out by the MPC
+	#[pallet::storage]
+	#[pallet::getter(fn total_deposited)]
+	pub type TotalDeposited<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, u128, ValueQuery>;
+
+	/// key: owner pk, spender pk and token_id
+	/// value: the balance of the owner the spender is allowed to move
+	#[pallet::storage]
+	#[pallet::getter(fn allowance)]
+	pub type Allowances<T: Config> =
+		StorageMap<_, Blake2_128Concat, ([u8; 64], [u8; 64], Vec<u8>), u128>;
+
 	/// key: pk
 	/// value: withdraw amount
 	#[pallet::storage]
 	#[pallet::getter(fn withdrawals)]
 	pub type Withdrawals<T: Config> = StorageMap<_, Blake2_128Concat, ([u8; 64], Vec<u8>), u128>;
 
+	/// key: trading pair
+	/// value: (price_x_cumulative, price_y_cumulative, last updated block)
+	#[pallet::storage]
+	#[pallet::getter(fn price_cumulative)]
+	pub type PriceCumulative<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, (u128, u128, T::BlockNumber)>;
+
+	/// key: trading pair
+	/// value: the block at which the price accumulation started
+	#[pallet::storage]
+	#[pallet::getter(fn pair_created_at)]
+	pub type PairCreatedAt<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, T::BlockNumber>;
+
+	/// key: trading pair
+	/// value: the cumulative amounts of token X and token Y sold to the pair
+	#[pallet::storage]
+	#[pallet::getter(fn pair_volume)]
+	pub type PairVolume<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, (u128, u128), ValueQuery>;
+
+	/// key: canonical pair id
+	/// value: trading pair
+	#[pallet::storage]
+	#[pallet::getter(fn pair_of)]
+	pub type PairOf<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, Vec<u8>>;
+
+	/// The receiver of the protocol fee, it is off if `None`
+	#[pallet::storage]
+	#[pallet::getter(fn fee_to)]
+	pub type FeeTo<T: Config> = StorageValue<_, [u8; 64]>;
+
+	/// Whether trading, adding liquidity and deposits are halted
+	#[pallet::storage]
+	#[pallet::getter(fn paused)]
+	pub type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The fee in basis points taken from confirmed deposits for `FeeTo`
+	#[pallet::storage]
+	#[pallet::getter(fn deposit_fee_bps)]
+	pub type DepositFeeBps<T: Config> = StorageValue<_, u16, ValueQuery>;
+
+	/// The fee in basis points taken from withdrawals for `FeeTo`
 	#[pallet::storage]
-	#[pallet::getter(fn mpc)]
-	pub type Mpc<T: Config> = StorageValue<_, [u8; 64], ValueQuery, GetDefaultMpc>;
+	#[pallet::getter(fn withdraw_fee_bps)]
+	pub type WithdrawFeeBps<T: Config> = StorageValue<_, u16, ValueQuery>;
+
+	/// key: trading pair
+	/// value: sqrt(reserve_x * reserve_y) after the last liquidity event while the protocol fee is
+	/// on
+	#[pallet::storage]
+	#[pallet::getter(fn k_last)]
+	pub type KLast<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, u128>;
+
+	/// The public keys of the MPC members holding the custody of deposits
+	#[pallet::storage]
+	#[pallet::getter(fn mpc_members)]
+	pub type MpcMembers<T: Config> = StorageValue<
+		_,
+		BoundedVec<[u8; 64], T::MaxMpcMembers>,
+		ValueQuery,
+		GetDefaultMpcMembers<T>,
+	>;
+
+	/// The number of MPC members that must confirm a withdrawal
+	#[pallet::storage]
+	#[pallet::getter(fn mpc_threshold)]
+	pub type MpcThreshold<T: Config> = StorageValue<_, u32, ValueQuery, GetDefaultMpcThreshold>;
+
+	/// The MPC member proposed to be rotated out and the key proposed to replace it
+	#[pallet::storage]
+	#[pallet::getter(fn pending_mpc)]
+	pub type PendingMpc<T: Config> = StorageValue<_, ([u8; 64], [u8; 64])>;
+
+	/// The keys rotated out of the MPC members while deposits to them were still pending, they are
+	/// served until those deposits are confirmed
+	#[pallet::storage]
+	#[pallet::getter(fn retired_mpc_members)]
+	pub type RetiredMpcMembers<T: Config> =
+		StorageValue<_, BoundedVec<[u8; 64], T::MaxMpcMembers>, ValueQuery>;
+
+	/// key: MPC key
+	/// value: the number of pending deposits transferred to it
+	#[pallet::storage]
+	#[pallet::getter(fn mpc_pending_deposits)]
+	pub type MpcPendingDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, [u8; 64], u32, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		/// Genesis trading pairs: trading pair, token x id, token y id, reserve x, reserve y,
+		/// liquidity provider, liquidity of which `MINIMUM_LIQUIDITY` is locked
+		pub trading_pairs: Vec<(Vec<u8>, Vec<u8>, Vec<u8>, u128, u128, [u8; 64], u128)>,
+		/// Genesis MPC members, the default MPC is kept if it is empty
+		pub mpc_members: Vec<[u8; 64]>,
+		/// Genesis MPC threshold, the default is kept if it is zero
+		pub mpc_threshold: u32,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self {
+				trading_pairs: Default::default(),
+				mpc_members: Default::default(),
+				mpc_threshold: Default::default(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			for (trading_pair, token_x_id, token_y_id, reserve_x, reserve_y, provider, liquidity) in
+				&self.trading_pairs
+			{
+				let reversed =
+					Pallet::<T>::get_or_create_pair(trading_pair, token_x_id, token_y_id)
+						.expect("Invalid genesis trading pair");
+				let reserves =
+					if reversed { (*reserve_y, *reserve_x) } else { (*reserve_x, *reserve_y) };
+				TradingPairs::<T>::insert(trading_pair, reserves);
+				// Lock the minimum liquidity as the first mint does, so the pair is never drained
+				assert!(*liquidity > MINIMUM_LIQUIDITY, "Genesis liquidity below the minimum");
+				TotalLiquidity::<T>::insert(trading_pair, liquidity);
+				Liquidity::<T>::insert((trading_pair.clone(), BURN_PK), MINIMUM_LIQUIDITY);
+				Liquidity::<T>::insert(
+					(trading_pair.clone(), *provider),
+					liquidity - MINIMUM_LIQUIDITY,
+				);
+			}
+
+			if !self.mpc_members.is_empty() {
+				let members = BoundedVec::<_, T::MaxMpcMembers>::try_from(self.mpc_members.clone())
+					.expect("Too many genesis MPC members");
+				MpcMembers::<T>::put(members);
+			}
+			if self.mpc_threshold > 0 {
+				assert!(
+					self.mpc_threshold as usize <= MpcMembers::<T>::get().len(),
+					"MPC threshold exceeds the members"
+				);
+				MpcThreshold::<T>::put(self.mpc_threshold);
+			}
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
@@ -96,16 +323,52 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// Event documentation should end with an array that provides descriptive names for event
-		SwapX2YTokens(Vec<u8>, [u8; 64], u128, u128),
-		SwapY2XTokens(Vec<u8>, [u8; 64], u128, u128),
-		AddLiquidity(Vec<u8>, [u8; 64], u128, u128),
-		RemoveLiquidity(Vec<u8>, [u8; 64], u128, u128),
+		/// trading_pair, pair_id, public_key, tokens_sold, tokens_bought, fee in the sold token
+		SwapX2YTokens(Vec<u8>, Vec<u8>, [u8; 64], u128, u128, u128),
+		/// trading_pair, pair_id, token_x_id, token_y_id
+		PairCreated(Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>),
+		/// trading_pair, pair_id, public_key, tokens_sold, tokens_bought, fee in the sold token
+		SwapY2XTokens(Vec<u8>, Vec<u8>, [u8; 64], u128, u128, u128),
+		/// trading_pair, pair_id, public_key, amount_x, amount_y, liquidity_minted,
+		/// total_liquidity
+		AddLiquidity(Vec<u8>, Vec<u8>, [u8; 64], u128, u128, u128, u128),
+		/// trading_pair, pair_id, public_key, amount_x, amount_y, liquidity_burned,
+		/// total_liquidity
+		RemoveLiquidity(Vec<u8>, Vec<u8>, [u8; 64], u128, u128, u128, u128),
 		/// public_key, token_id, nonce
 		PendingDeposit([u8; 64], Vec<u8>, u128),
 		/// public_key, token_id, nonce
 		DepositComfirmed([u8; 64], Vec<u8>, u128),
+		/// public_key, token_id, nonce
+		DepositExpired([u8; 64], Vec<u8>, u128),
 		/// public_key, token_id, amount
 		Withdrawal([u8; 64], Vec<u8>, u128),
+		/// public_key, token_id, amount
+		WithdrawalCancelled([u8; 64], Vec<u8>, u128),
+		/// public_key, token_id, amount
+		WithdrawalConfirmed([u8; 64], Vec<u8>, u128),
+		/// public_key, token_id, amount, remaining
+		PartialWithdrawalConfirmed([u8; 64], Vec<u8>, u128, u128),
+		/// public_key
+		FeeToSet(Option<[u8; 64]>),
+		/// paused
+		PausedSet(bool),
+		/// deposit_fee_bps, withdraw_fee_bps
+		BridgeFeesSet(u16, u16),
+		/// public_key, token_id, fee
+		BridgeFeeCharged([u8; 64], Vec<u8>, u128),
+		/// members, threshold
+		MpcMembersSet(Vec<[u8; 64]>, u32),
+		/// old_mpc, new_mpc
+		MpcProposed([u8; 64], [u8; 64]),
+		/// old_mpc, new_mpc
+		MpcAccepted([u8; 64], [u8; 64]),
+		/// from, to, token_id, amount
+		BalanceTransferred([u8; 64], [u8; 64], Vec<u8>, u128),
+		/// owner, spender, token_id, amount
+		Approval([u8; 64], [u8; 64], Vec<u8>, u128),
+		/// owner, spender, recipient, token_id, amount
+		TransferredFrom([u8; 64], [u8; 64], [u8; 64], Vec<u8>, u128),
 	}
 
 	// Errors inform users that something went wrong.
@@ -116,6 +379,8 @@ pub mod pallet {
 		/// Errors should have helpful documentation associated with them.
 		DecodePayloadFailed,
 		SerializePublicKeyFailed,
+		/// The runtime account id can not be decoded from a public key
+		AccountConversionFailed,
 		StorageOverflow,
 		InvalidValue,
 		TradingPairNotExist,
@@ -127,19 +392,27 @@ pub mod pallet {
 		InsufficientAmount,
 		OmniverseTransferFailed,
 		TokenIdNotExist,
+		/// The tokens passed do not match the ones the trading pair was created with
 		MismatchTokenId,
 		InsufficientBalance,
 		NotOmniverseTransfer,
+		/// Deprecated, swaps fail with `InsufficientOutputAmount` instead. Kept so that the
+		/// indices of the later errors do not change, to be removed in the next release
 		GetXTokenLessThenDesired,
+		/// Deprecated, swaps fail with `InsufficientOutputAmount` instead. Kept so that the
+		/// indices of the later errors do not change, to be removed in the next release
 		GetYTokenLessThenDesired,
 		PublicKeyNotExist,
 		MismatchReceiptor,
 		DepositExist,
 		NotDeposit,
+		/// The deposit has already been credited
 		IsComfirmed,
 		TxNotExisted,
 		/// Deposit tx mismatch record tx
 		OmniverseTxMismatch,
+		/// Deprecated, `deposit_comfirm` fails with `DepositNotExecuted` instead. Kept so that the
+		/// indices of the later errors do not change, to be removed in the next release
 		OmniverseTxNotExecuted,
 		BalanceNotEnough,
 
@@ -149,6 +422,55 @@ pub mod pallet {
 		///
 		WithdrawalNotExist,
 		WithdrawAmountMismatch,
+		/// The price implied by the desired amounts deviates too much from the TWAP
+		PriceDeviationTooHigh,
+		/// The result of the pricing math does not fit in u128
+		ArithmeticOverflow,
+		/// The input required by an exact output swap exceeds the maximum
+		ExceedMaxInput,
+		/// The block number has passed the deadline of the call
+		DeadlinePassed,
+		/// Both tokens of the trading pair are the same
+		SameToken,
+		/// The tokens are already traded in another trading pair
+		PairAlreadyExists,
+		/// The execution price deviates from the spot price by more than the maximum price impact
+		PriceImpactTooHigh,
+		/// The pallet is paused by the admin
+		Paused,
+		/// A signature confirming a withdrawal is not from an MPC member
+		InvalidMpcSignature,
+		/// Fewer MPC members than the threshold confirmed a withdrawal
+		InsufficientMpcSignatures,
+		/// There is no MPC rotation to accept
+		NoPendingMpc,
+		/// The deposit can not be reclaimed before it expires
+		DepositNotExpired,
+		/// The omniverse transaction of the deposit is executed, it must be confirmed instead
+		DepositAlreadyExecuted,
+		/// The path is empty or its trading pairs are not chained by a common token
+		InvalidPath,
+		/// The output of a swap in either direction, or of a multi-hop swap, is zero or below the
+		/// minimum the caller accepts
+		InsufficientOutputAmount,
+		/// The amount exceeds what the spender is allowed to move
+		InsufficientAllowance,
+		/// The liquidity burned is too small to return both tokens of the pair
+		InsufficientLiquidityBurned,
+		/// The amounts added are below their minimums or they mint no liquidity
+		InsufficientLiquidityMinted,
+		/// The product of the reserves decreases after a swap
+		KInvariantViolated,
+		/// The balance exceeds what the MPC holds of the token
+		BalanceNotBacked,
+		/// The omniverse transfer conflicts with another one signed with the same nonce
+		OmniverseTransferMalicious,
+		/// The omniverse transfer has already been sent
+		OmniverseTransferDuplicated,
+		/// The omniverse transfer of the deposit is still pending in the delayed queue
+		DepositNotExecuted,
+		/// The MPC members, or the retired MPC keys, exceed `MaxMpcMembers`
+		TooManyMpcMembers,
 	}
 
 	/// for default mpc account
@@ -162,55 +484,108 @@ pub mod pallet {
 		]
 	}
 
+	/// for default mpc members
+	#[pallet::type_value]
+	pub fn GetDefaultMpcMembers<T: Config>() -> BoundedVec<[u8; 64], T::MaxMpcMembers> {
+		BoundedVec::try_from(sp_std::vec![GetDefaultMpc()]).unwrap_or_default()
+	}
+
+	/// for default mpc threshold
+	#[pallet::type_value]
+	pub fn GetDefaultMpcThreshold() -> u32 {
+		1
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migration::migrate_to_v1::<T>()
+		}
+	}
+
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Deposit
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		///
+		/// If `auto_confirm` is true and the omniverse transaction has already been executed, the
+		/// balance is credited immediately instead of waiting for `deposit_comfirm`.
+		#[pallet::weight(T::WeightInfo::deposit(Pallet::<T>::max_mpc_keys()))]
 		pub fn deposit(
 			origin: OriginFor<T>,
 			token_id: Vec<u8>,
 			data: OmniverseTransactionData,
+			auto_confirm: bool,
 		) -> DispatchResult {
 			ensure_signed(origin)?;
-			// Transfer X token to MPC account
-			let mpc = Mpc::<T>::get();
+			Self::ensure_not_paused()?;
+			// Transfer X token to an MPC member
 			let fungible = Fungible::decode(&mut data.payload.as_slice())
 				.map_err(|_| Error::<T>::DecodePayloadFailed)?;
+			ensure!(fungible.op == TRANSFER && fungible.amount > 0, Error::<T>::InvalidValue);
 			let to: [u8; 64] =
 				fungible.ex_data.try_into().map_err(|_| Error::<T>::SerializePublicKeyFailed)?;
-			ensure!(to == mpc, Error::<T>::InvalidValue);
-			T::OmniverseToken::send_transaction_external(token_id.clone(), &data)
-				.ok()
-				.ok_or(Error::<T>::OmniverseTransferFailed)?;
-			// let omni_tx = OmniverseTx::new(data.clone(), T::Timestamp::now().as_secs());
+			ensure!(Self::is_mpc(&to), Error::<T>::InvalidValue);
+			// A deposit is pending or credited once, it can not be replayed after confirmation
+			let key = (data.from, token_id.clone(), data.nonce);
 			ensure!(
-				!DepositRecords::<T>::contains_key(&(data.from, token_id.clone(), data.nonce)),
+				!DepositRecords::<T>::contains_key(&key) &&
+					!ConfirmedDeposits::<T>::contains_key(&key),
 				Error::<T>::DepositExist
 			);
-			DepositRecords::<T>::insert(&(data.from, token_id.clone(), data.nonce), data.clone());
+			// The transfer may have been sent before the deposit, only a malicious one is rejected
+			match T::OmniverseToken::send_transaction_external(token_id.clone(), &data) {
+				Ok(FactoryResult::Success) | Ok(FactoryResult::ProtocolDuplicated) => {},
+				Ok(FactoryResult::ProtocolMalicious) =>
+					return Err(Error::<T>::OmniverseTransferMalicious.into()),
+				Err(_) => return Err(Error::<T>::OmniverseTransferFailed.into()),
+			}
+			// let omni_tx = OmniverseTx::new(data.clone(), T::Timestamp::now().as_secs());
+			if auto_confirm {
+				let executed = T::OmniverseProtocol::get_transaction_data(
+					data.from,
+					PALLET_NAME.to_vec(),
+					token_id.clone(),
+					data.nonce,
+				)
+				.map_or(false, |omni_tx| omni_tx.executed && omni_tx.tx_data == data);
+				if executed {
+					return Self::credit_deposit(token_id, &data).map_err(Into::into);
+				}
+			}
+			DepositRecords::<T>::insert(
+				&(data.from, token_id.clone(), data.nonce),
+				(data.clone(), T::Timestamp::now().as_secs()),
+			);
+			let pending = MpcPendingDeposits::<T>::get(to)
+				.checked_add(1)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			MpcPendingDeposits::<T>::insert(to, pending);
 			Self::deposit_event(Event::PendingDeposit(data.from, token_id, data.nonce));
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		#[pallet::weight(T::WeightInfo::withdraw(Pallet::<T>::max_mpc_keys()))]
 		pub fn withdraw(
 			origin: OriginFor<T>,
 			pk: [u8; 64],
 			token_id: Vec<u8>,
 			amount: u128,
 		) -> DispatchResult {
-			// TODO: Is it necessary to verify whether "from" is an MPC account? Currently, it is open to anyone.
 			let sender = ensure_signed(origin)?;
 			let owner = Self::to_account(&pk)?;
 			ensure!(sender == owner, Error::<T>::NoPermission);
 
 			let balance = Balance::<T>::get(pk, &token_id).unwrap_or(0);
 			ensure!(amount > 0 && balance >= amount, Error::<T>::InvalidValue);
-			Withdrawals::<T>::insert((pk, token_id.clone()), amount);
+			Self::ensure_backed(&token_id)?;
 			Balance::<T>::insert(pk, &token_id, balance - amount);
+			let amount = Self::charge_bridge_fee(pk, &token_id, amount, WithdrawFeeBps::<T>::get());
+			// Add to the pending withdrawal if there is one
+			let pending = Withdrawals::<T>::get((pk, token_id.clone())).unwrap_or(0);
+			Withdrawals::<T>::insert((pk, token_id.clone()), pending.saturating_add(amount));
 
 			Self::deposit_event(Event::Withdrawal(pk, token_id, amount));
 			Ok(())
@@ -218,7 +593,7 @@ pub mod pallet {
 
 		/// Once the omniverse transaction has been executed, any account is
 		/// eligible to initiate the conclusive confirmation of the final deposit.
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		#[pallet::weight(T::WeightInfo::deposit_comfirm(Pallet::<T>::max_mpc_keys()))]
 		pub fn deposit_comfirm(
 			origin: OriginFor<T>,
 			pk: [u8; 64],
@@ -226,7 +601,7 @@ pub mod pallet {
 			nonce: u128,
 		) -> DispatchResult {
 			ensure_signed(origin)?;
-			let data = DepositRecords::<T>::get(&(pk, token_id.clone(), nonce))
+			let (data, _) = DepositRecords::<T>::get(&(pk, token_id.clone(), nonce))
 				.ok_or(Error::<T>::NotDeposit)?;
 			let omni_tx = T::OmniverseProtocol::get_transaction_data(
 				pk,
@@ -237,116 +612,539 @@ pub mod pallet {
 			.ok_or(Error::<T>::TxNotExisted)?;
 
 			ensure!(data == omni_tx.tx_data, Error::<T>::OmniverseTxMismatch);
-			ensure!(omni_tx.executed, Error::<T>::OmniverseTxNotExecuted);
+			ensure!(omni_tx.executed, Error::<T>::DepositNotExecuted);
 
 			DepositRecords::<T>::remove(&(pk, token_id.clone(), nonce));
-			// let balance
-			let mut balance = Balance::<T>::get(pk, &token_id).unwrap_or(0);
-			let fungible = Fungible::decode(&mut data.payload.as_slice())
-				.map_err(|_| Error::<T>::DecodePayloadFailed)?;
-			balance += fungible.amount;
-			Balance::<T>::insert(pk, &token_id, balance);
-			Self::deposit_event(Event::DepositComfirmed(data.from, token_id, data.nonce));
+			Self::release_pending_deposit(&data);
+			Self::credit_deposit(token_id, &data)?;
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		/// Remove a deposit whose omniverse transaction is still not executed `DepositExpiry` after
+		/// it is deposited
+		///
+		/// Deposits recorded before the storage v1 migration are deemed deposited at the time of the
+		/// runtime upgrade.
+		#[pallet::weight(T::WeightInfo::reclaim_deposit())]
+		pub fn reclaim_deposit(
+			origin: OriginFor<T>,
+			pk: [u8; 64],
+			token_id: Vec<u8>,
+			nonce: u128,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+			let (data, deposited_at) = DepositRecords::<T>::get(&(pk, token_id.clone(), nonce))
+				.ok_or(Error::<T>::NotDeposit)?;
+			ensure!(
+				T::Timestamp::now().as_secs()
+					>= deposited_at.saturating_add(T::DepositExpiry::get()),
+				Error::<T>::DepositNotExpired
+			);
+			// Only executed transactions are pruned, a missing one is not proof that it never ran
+			let omni_tx = T::OmniverseProtocol::get_transaction_data(
+				pk,
+				PALLET_NAME.to_vec(),
+				token_id.clone(),
+				nonce,
+			)
+			.ok_or(Error::<T>::TxNotExisted)?;
+			ensure!(!omni_tx.executed, Error::<T>::DepositAlreadyExecuted);
+
+			DepositRecords::<T>::remove(&(pk, token_id.clone(), nonce));
+			Self::release_pending_deposit(&data);
+			Self::deposit_event(Event::DepositExpired(pk, token_id, nonce));
+			Ok(())
+		}
+
+		/// Settle a pending withdrawal with the omniverse transfer sent by an MPC member.
+		///
+		/// The sender of the transfer confirms it by signing it, `signatures` are the signatures of
+		/// the raw hash of the transfer by other members, at least `MpcThreshold` distinct members
+		/// must confirm it.
+		///
+		/// A pending withdrawal is settled at most once: the first call that removes the
+		/// `Withdrawals` entry wins, and any later confirmation fails with `WithdrawalNotExist`
+		/// without sending another transfer.
+		#[pallet::weight(T::WeightInfo::withdraw_comfirm(signatures.len() as u32))]
 		pub fn withdraw_comfirm(
 			origin: OriginFor<T>,
 			pk: [u8; 64],
 			token_id: Vec<u8>,
 			data: OmniverseTransactionData,
+			signatures: Vec<[u8; 65]>,
 		) -> DispatchResult {
 			ensure_signed(origin)?;
 			let withdrawal = Withdrawals::<T>::get((pk, token_id.clone()))
 				.ok_or(Error::<T>::WithdrawalNotExist)?;
+			// The withdrawal must be paid out of the custody
+			ensure!(Self::is_mpc(&data.from), Error::<T>::MismatchReceiptor);
+			let mpc_members = MpcMembers::<T>::get();
+			let hash = data.get_raw_hash(&token_id, false);
+			let mut confirmations = sp_std::vec![data.from];
+			for signature in signatures.iter() {
+				let signer = sp_io::crypto::secp256k1_ecdsa_recover(signature, &hash)
+					.map_err(|_| Error::<T>::InvalidMpcSignature)?;
+				ensure!(mpc_members.contains(&signer), Error::<T>::InvalidMpcSignature);
+				if !confirmations.contains(&signer) {
+					confirmations.push(signer);
+				}
+			}
+			ensure!(
+				confirmations.len() as u32 >= MpcThreshold::<T>::get(),
+				Error::<T>::InsufficientMpcSignatures
+			);
 			let fungible = Fungible::decode(&mut data.payload.as_slice())
 				.map_err(|_| Error::<T>::DecodePayloadFailed)?;
-			ensure!(withdrawal == fungible.amount, Error::<T>::WithdrawAmountMismatch);
+			ensure!(
+				fungible.amount > 0 && fungible.amount <= withdrawal,
+				Error::<T>::WithdrawAmountMismatch
+			);
 			let dest_pk: [u8; 64] =
 				fungible.ex_data.try_into().map_err(|_| Error::<T>::SerializePublicKeyFailed)?;
 			ensure!(pk == dest_pk, Error::<T>::ToAccountMismatch);
 
-			Withdrawals::<T>::remove((pk, token_id.clone()));
-			T::OmniverseToken::send_transaction_external(token_id, &data)
-				.ok()
-				.ok_or(Error::<T>::OmniverseTransferFailed)?;
+			let remaining = withdrawal - fungible.amount;
+			if remaining == 0 {
+				Withdrawals::<T>::remove((pk, token_id.clone()));
+			} else {
+				Withdrawals::<T>::insert((pk, token_id.clone()), remaining);
+			}
+			// A transfer that has already been sent must not settle the withdrawal again
+			match T::OmniverseToken::send_transaction_external(token_id.clone(), &data) {
+				Ok(FactoryResult::Success) => {},
+				Ok(FactoryResult::ProtocolDuplicated) =>
+					return Err(Error::<T>::OmniverseTransferDuplicated.into()),
+				Ok(FactoryResult::ProtocolMalicious) =>
+					return Err(Error::<T>::OmniverseTransferMalicious.into()),
+				Err(_) => return Err(Error::<T>::OmniverseTransferFailed.into()),
+			}
+			TotalDeposited::<T>::mutate(&token_id, |total| {
+				*total = total.saturating_sub(fungible.amount)
+			});
+			Self::deposit_event(Event::WithdrawalConfirmed(pk, token_id.clone(), fungible.amount));
+			// The MPC may pay the withdrawal out in several tranches
+			if remaining > 0 {
+				Self::deposit_event(Event::PartialWithdrawalConfirmed(
+					pk,
+					token_id,
+					fungible.amount,
+					remaining,
+				));
+			}
+			Ok(())
+		}
+
+		/// Cancel `amount` of a pending withdrawal and credit it back to the balance.
+		///
+		/// It races with `withdraw_comfirm` in the same way, only the part of the withdrawal that is
+		/// still pending can be cancelled.
+		#[pallet::weight(T::WeightInfo::cancel_withdrawal(Pallet::<T>::max_mpc_keys()))]
+		pub fn cancel_withdrawal(
+			origin: OriginFor<T>,
+			pk: [u8; 64],
+			token_id: Vec<u8>,
+			amount: u128,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+
+			let withdrawal = Withdrawals::<T>::get((pk, token_id.clone()))
+				.ok_or(Error::<T>::WithdrawalNotExist)?;
+			ensure!(amount > 0 && amount <= withdrawal, Error::<T>::WithdrawAmountMismatch);
+			Self::ensure_backed(&token_id)?;
+			let remaining = withdrawal - amount;
+			if remaining == 0 {
+				Withdrawals::<T>::remove((pk, token_id.clone()));
+			} else {
+				Withdrawals::<T>::insert((pk, token_id.clone()), remaining);
+			}
+			let balance = Balance::<T>::get(pk, &token_id).unwrap_or(0);
+			Balance::<T>::insert(pk, &token_id, balance + amount);
+
+			Self::deposit_event(Event::WithdrawalCancelled(pk, token_id, amount));
 			Ok(())
 		}
 
-		/// Convert X token to Y token
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		/// Convert X token to Y token, the Y token is credited to `recipient` if supplied, otherwise
+		/// to `pk`. Passing `SELL_ALL` as `tokens_sold` sells the whole balance of `pk`
+		#[pallet::weight(T::WeightInfo::swap_x2y(Pallet::<T>::max_mpc_keys()))]
 		pub fn swap_x2y(
 			origin: OriginFor<T>,
 			trading_pair: Vec<u8>,
 			pk: [u8; 64],
 			tokens_sold: u128,
 			min_token: u128,
+			deadline: Option<T::BlockNumber>,
+			max_price_impact_bps: Option<u16>,
+			recipient: Option<[u8; 64]>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			let owner = Self::to_account(&pk)?;
 			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			Self::ensure_deadline(deadline)?;
 			ensure!(tokens_sold > 0 && min_token > 0, Error::<T>::InvalidValue);
 			let (token_x_id, token_y_id) =
 				TokenId::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			let balance_x = Balance::<T>::get(pk, &token_x_id).unwrap_or(0);
+			let tokens_sold = if tokens_sold == SELL_ALL { balance_x } else { tokens_sold };
+			ensure!(tokens_sold > 0, Error::<T>::InvalidValue);
 			ensure!(balance_x >= tokens_sold, Error::<T>::BalanceNotEnough);
+			Self::ensure_backed(&token_x_id)?;
+			Self::ensure_backed(&token_y_id)?;
 
 			let (reserve_x, reserve_y) =
 				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
-			let tokens_bought: u128 = get_input_price(tokens_sold, reserve_x, reserve_y);
-			ensure!(tokens_bought >= min_token, Error::<T>::GetYTokenLessThenDesired);
+			ensure!(reserve_x > 0 && reserve_y > 0, Error::<T>::InsufficientLiquidity);
+			let tokens_bought: u128 = get_input_price(tokens_sold, reserve_x, reserve_y)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			ensure!(tokens_bought > 0, Error::<T>::InsufficientOutputAmount);
+			ensure!(tokens_bought >= min_token, Error::<T>::InsufficientOutputAmount);
+			Self::ensure_price_impact(
+				tokens_sold,
+				tokens_bought,
+				reserve_x,
+				reserve_y,
+				max_price_impact_bps,
+			)?;
+			// the fee is kept in the reserves, so their product never decreases
+			ensure!(
+				k_invariant_holds(tokens_sold, tokens_bought, reserve_x, reserve_y),
+				Error::<T>::KInvariantViolated
+			);
+			Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
 			<TradingPairs<T>>::insert(
 				&trading_pair,
 				(reserve_x + tokens_sold, reserve_y - tokens_bought),
 			);
+			Self::add_volume(&trading_pair, tokens_sold, 0);
 
-			// update token_x and token_y balance
-			let balance_y = Balance::<T>::get(pk, &token_y_id).unwrap_or(0);
+			// update token_x and token_y balance, the bought token is credited to the recipient
+			let recipient = recipient.unwrap_or(pk);
+			let balance_y = Balance::<T>::get(recipient, &token_y_id).unwrap_or(0);
 			Balance::<T>::insert(pk, &token_x_id, balance_x - tokens_sold);
-			Balance::<T>::insert(pk, &token_y_id, balance_y + tokens_bought);
+			Balance::<T>::insert(recipient, &token_y_id, balance_y + tokens_bought);
 
-			Self::deposit_event(Event::SwapX2YTokens(trading_pair, pk, tokens_sold, tokens_bought));
+			let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+			Self::deposit_event(Event::SwapX2YTokens(
+				trading_pair,
+				pair_id,
+				pk,
+				tokens_sold,
+				tokens_bought,
+				swap_fee(tokens_sold),
+			));
 			Ok(())
 		}
 
-		/// Convert Y token to X token
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		/// Convert Y token to X token, the X token is credited to `recipient` if supplied, otherwise
+		/// to `pk`. Passing `SELL_ALL` as `tokens_sold` sells the whole balance of `pk`
+		#[pallet::weight(T::WeightInfo::swap_y2x(Pallet::<T>::max_mpc_keys()))]
 		pub fn swap_y2x(
 			origin: OriginFor<T>,
 			trading_pair: Vec<u8>,
 			pk: [u8; 64],
 			tokens_sold: u128,
 			min_token: u128,
+			deadline: Option<T::BlockNumber>,
+			max_price_impact_bps: Option<u16>,
+			recipient: Option<[u8; 64]>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			let owner = Self::to_account(&pk)?;
 			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			Self::ensure_deadline(deadline)?;
 			ensure!(tokens_sold > 0 && min_token > 0, Error::<T>::InvalidValue);
 			let (token_x_id, token_y_id) =
 				TokenId::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			let balance_y = Balance::<T>::get(pk, &token_y_id).unwrap_or(0);
+			let tokens_sold = if tokens_sold == SELL_ALL { balance_y } else { tokens_sold };
+			ensure!(tokens_sold > 0, Error::<T>::InvalidValue);
 			ensure!(balance_y >= tokens_sold, Error::<T>::BalanceNotEnough);
+			Self::ensure_backed(&token_x_id)?;
+			Self::ensure_backed(&token_y_id)?;
+
+			let (reserve_x, reserve_y) =
+				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+			ensure!(reserve_x > 0 && reserve_y > 0, Error::<T>::InsufficientLiquidity);
+			let tokens_bought = get_input_price(tokens_sold, reserve_y, reserve_x)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			ensure!(tokens_bought > 0, Error::<T>::InsufficientOutputAmount);
+			ensure!(tokens_bought >= min_token, Error::<T>::InsufficientOutputAmount);
+			Self::ensure_price_impact(
+				tokens_sold,
+				tokens_bought,
+				reserve_y,
+				reserve_x,
+				max_price_impact_bps,
+			)?;
+			// the fee is kept in the reserves, so their product never decreases
+			ensure!(
+				k_invariant_holds(tokens_sold, tokens_bought, reserve_y, reserve_x),
+				Error::<T>::KInvariantViolated
+			);
+			Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
+			<TradingPairs<T>>::insert(
+				&trading_pair,
+				(reserve_x - tokens_bought, reserve_y + tokens_sold),
+			);
+			Self::add_volume(&trading_pair, 0, tokens_sold);
+
+			// update token_x and token_y balance, the bought token is credited to the recipient
+			let recipient = recipient.unwrap_or(pk);
+			let balance_x = Balance::<T>::get(recipient, &token_x_id).unwrap_or(0);
+			Balance::<T>::insert(recipient, &token_x_id, balance_x + tokens_bought);
+			Balance::<T>::insert(pk, &token_y_id, balance_y - tokens_sold);
+
+			let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+			Self::deposit_event(Event::SwapY2XTokens(
+				trading_pair,
+				pair_id,
+				pk,
+				tokens_sold,
+				tokens_bought,
+				swap_fee(tokens_sold),
+			));
+			Ok(())
+		}
+
+		/// Convert X token to an exact amount of Y token
+		#[pallet::weight(T::WeightInfo::swap_x2y_exact_out(Pallet::<T>::max_mpc_keys()))]
+		pub fn swap_x2y_exact_out(
+			origin: OriginFor<T>,
+			trading_pair: Vec<u8>,
+			pk: [u8; 64],
+			tokens_bought: u128,
+			max_tokens_sold: u128,
+			deadline: Option<T::BlockNumber>,
+			max_price_impact_bps: Option<u16>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			Self::ensure_deadline(deadline)?;
+			ensure!(tokens_bought > 0 && max_tokens_sold > 0, Error::<T>::InvalidValue);
+			let (token_x_id, token_y_id) =
+				TokenId::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+
+			let (reserve_x, reserve_y) =
+				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+			ensure!(reserve_x > 0 && reserve_y > 0, Error::<T>::InsufficientLiquidity);
+			ensure!(tokens_bought < reserve_y, Error::<T>::InsufficientLiquidity);
+			let tokens_sold = get_output_price(tokens_bought, reserve_x, reserve_y)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			ensure!(tokens_sold <= max_tokens_sold, Error::<T>::ExceedMaxInput);
+			Self::ensure_price_impact(
+				tokens_sold,
+				tokens_bought,
+				reserve_x,
+				reserve_y,
+				max_price_impact_bps,
+			)?;
+			// the fee is kept in the reserves, so their product never decreases
+			ensure!(
+				k_invariant_holds(tokens_sold, tokens_bought, reserve_x, reserve_y),
+				Error::<T>::KInvariantViolated
+			);
+			let balance_x = Balance::<T>::get(pk, &token_x_id).unwrap_or(0);
+			ensure!(balance_x >= tokens_sold, Error::<T>::BalanceNotEnough);
+			Self::ensure_backed(&token_x_id)?;
+			Self::ensure_backed(&token_y_id)?;
+			Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
+			<TradingPairs<T>>::insert(
+				&trading_pair,
+				(reserve_x + tokens_sold, reserve_y - tokens_bought),
+			);
+			Self::add_volume(&trading_pair, tokens_sold, 0);
+
+			// update token_x and token_y balance
+			let balance_y = Balance::<T>::get(pk, &token_y_id).unwrap_or(0);
+			Balance::<T>::insert(pk, &token_x_id, balance_x - tokens_sold);
+			Balance::<T>::insert(pk, &token_y_id, balance_y + tokens_bought);
+
+			let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+			Self::deposit_event(Event::SwapX2YTokens(
+				trading_pair,
+				pair_id,
+				pk,
+				tokens_sold,
+				tokens_bought,
+				swap_fee(tokens_sold),
+			));
+			Ok(())
+		}
+
+		/// Convert Y token to an exact amount of X token
+		#[pallet::weight(T::WeightInfo::swap_y2x_exact_out(Pallet::<T>::max_mpc_keys()))]
+		pub fn swap_y2x_exact_out(
+			origin: OriginFor<T>,
+			trading_pair: Vec<u8>,
+			pk: [u8; 64],
+			tokens_bought: u128,
+			max_tokens_sold: u128,
+			deadline: Option<T::BlockNumber>,
+			max_price_impact_bps: Option<u16>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			Self::ensure_deadline(deadline)?;
+			ensure!(tokens_bought > 0 && max_tokens_sold > 0, Error::<T>::InvalidValue);
+			let (token_x_id, token_y_id) =
+				TokenId::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 
 			let (reserve_x, reserve_y) =
 				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
-			let tokens_bought = get_input_price(tokens_sold, reserve_y, reserve_x);
-			ensure!(tokens_bought >= min_token, Error::<T>::GetXTokenLessThenDesired);
+			ensure!(reserve_x > 0 && reserve_y > 0, Error::<T>::InsufficientLiquidity);
+			ensure!(tokens_bought < reserve_x, Error::<T>::InsufficientLiquidity);
+			let tokens_sold = get_output_price(tokens_bought, reserve_y, reserve_x)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			ensure!(tokens_sold <= max_tokens_sold, Error::<T>::ExceedMaxInput);
+			Self::ensure_price_impact(
+				tokens_sold,
+				tokens_bought,
+				reserve_y,
+				reserve_x,
+				max_price_impact_bps,
+			)?;
+			// the fee is kept in the reserves, so their product never decreases
+			ensure!(
+				k_invariant_holds(tokens_sold, tokens_bought, reserve_y, reserve_x),
+				Error::<T>::KInvariantViolated
+			);
+			let balance_y = Balance::<T>::get(pk, &token_y_id).unwrap_or(0);
+			ensure!(balance_y >= tokens_sold, Error::<T>::BalanceNotEnough);
+			Self::ensure_backed(&token_x_id)?;
+			Self::ensure_backed(&token_y_id)?;
+			Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
 			<TradingPairs<T>>::insert(
 				&trading_pair,
 				(reserve_x - tokens_bought, reserve_y + tokens_sold),
 			);
+			Self::add_volume(&trading_pair, 0, tokens_sold);
 
 			// update token_x and token_y balance
 			let balance_x = Balance::<T>::get(pk, &token_x_id).unwrap_or(0);
 			Balance::<T>::insert(pk, &token_x_id, balance_x + tokens_bought);
 			Balance::<T>::insert(pk, &token_y_id, balance_y - tokens_sold);
 
-			Self::deposit_event(Event::SwapY2XTokens(trading_pair, pk, tokens_sold, tokens_bought));
+			let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+			Self::deposit_event(Event::SwapY2XTokens(
+				trading_pair,
+				pair_id,
+				pk,
+				tokens_sold,
+				tokens_bought,
+				swap_fee(tokens_sold),
+			));
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		/// Sell `amount_in` through the trading pairs of `path` in turn, each hop sells what the
+		/// previous one bought, see `get_amounts_out` for the direction of the route
+		#[pallet::weight(T::WeightInfo::swap_exact_tokens_for_tokens(
+			path.len() as u32,
+			Pallet::<T>::max_mpc_keys(),
+		))]
+		pub fn swap_exact_tokens_for_tokens(
+			origin: OriginFor<T>,
+			pk: [u8; 64],
+			amount_in: u128,
+			amount_out_min: u128,
+			path: Vec<Vec<u8>>,
+			deadline: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			Self::ensure_deadline(deadline)?;
+			ensure!(amount_in > 0 && amount_out_min > 0, Error::<T>::InvalidValue);
+
+			// The amounts are computed hop by hop as a trading pair may be traded more than once
+			let route = Self::route(amount_in, &path)?;
+			let (token_in, _) = &route[0];
+			let balance_in = Balance::<T>::get(pk, token_in).unwrap_or(0);
+			ensure!(balance_in >= amount_in, Error::<T>::BalanceNotEnough);
+			for (token, _) in route.iter() {
+				Self::ensure_backed(token)?;
+			}
+
+			let mut tokens_sold = amount_in;
+			for (i, trading_pair) in path.into_iter().enumerate() {
+				let (token_in, _) = route[i].clone();
+				let (token_out, _) = route[i + 1].clone();
+				let (token_x_id, token_y_id) =
+					TokenId::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+				let (reserve_x, reserve_y) =
+					TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+				ensure!(reserve_x > 0 && reserve_y > 0, Error::<T>::InsufficientLiquidity);
+				let sell_x = token_in == token_x_id;
+				let (input_reserve, output_reserve) =
+					if sell_x { (reserve_x, reserve_y) } else { (reserve_y, reserve_x) };
+				let tokens_bought = get_input_price(tokens_sold, input_reserve, output_reserve)
+					.ok_or(Error::<T>::ArithmeticOverflow)?;
+				ensure!(tokens_bought > 0, Error::<T>::InsufficientOutputAmount);
+				// the fee is kept in the reserves, so their product never decreases
+				ensure!(
+					k_invariant_holds(tokens_sold, tokens_bought, input_reserve, output_reserve),
+					Error::<T>::KInvariantViolated
+				);
+				Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
+				if sell_x {
+					<TradingPairs<T>>::insert(
+						&trading_pair,
+						(reserve_x + tokens_sold, reserve_y - tokens_bought),
+					);
+					Self::add_volume(&trading_pair, tokens_sold, 0);
+				} else {
+					<TradingPairs<T>>::insert(
+						&trading_pair,
+						(reserve_x - tokens_bought, reserve_y + tokens_sold),
+					);
+					Self::add_volume(&trading_pair, 0, tokens_sold);
+				}
+
+				// the token bought by a hop is sold by the next one
+				let balance_in = Balance::<T>::get(pk, &token_in).unwrap_or(0);
+				Balance::<T>::insert(pk, &token_in, balance_in - tokens_sold);
+				let balance_out = Balance::<T>::get(pk, &token_out).unwrap_or(0);
+				Balance::<T>::insert(pk, &token_out, balance_out + tokens_bought);
+
+				let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+				if sell_x {
+					Self::deposit_event(Event::SwapX2YTokens(
+						trading_pair,
+						pair_id,
+						pk,
+						tokens_sold,
+						tokens_bought,
+						swap_fee(tokens_sold),
+					));
+				} else {
+					Self::deposit_event(Event::SwapY2XTokens(
+						trading_pair,
+						pair_id,
+						pk,
+						tokens_sold,
+						tokens_bought,
+						swap_fee(tokens_sold),
+					));
+				}
+				tokens_sold = tokens_bought;
+			}
+			// Failing here reverts every hop
+			ensure!(tokens_sold >= amount_out_min, Error::<T>::InsufficientOutputAmount);
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::add_liquidity(Pallet::<T>::max_mpc_keys()))]
 		pub fn add_liquidity(
 			origin: OriginFor<T>,
 			trading_pair: Vec<u8>,
@@ -357,23 +1155,41 @@ pub mod pallet {
 			amount_y_min: u128,
 			token_x_id: Vec<u8>,
 			token_y_id: Vec<u8>,
+			max_twap_deviation_bps: Option<u16>,
+			deadline: Option<T::BlockNumber>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			let owner = Self::to_account(&pk)?;
 			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			Self::ensure_deadline(deadline)?;
 			ensure!(amount_x_desired > 0 && amount_y_desired > 0, Error::<T>::InvalidValue);
 
-			if !TokenId::<T>::contains_key(&trading_pair) {
-				<TokenId<T>>::insert(&trading_pair, (token_x_id.clone(), token_y_id.clone()));
+			// resolve the tokens and amounts to the canonical order of the pair
+			let mut x = (token_x_id, amount_x_desired, amount_x_min);
+			let mut y = (token_y_id, amount_y_desired, amount_y_min);
+			if Self::get_or_create_pair(&trading_pair, &x.0, &y.0)? {
+				sp_std::mem::swap(&mut x, &mut y);
 			}
+			let (token_x_id, amount_x_desired, amount_x_min) = x;
+			let (token_y_id, amount_y_desired, amount_y_min) = y;
 
-			let tranding_pair = TradingPairs::<T>::get(&trading_pair);
+			let (reserve_x, reserve_y) =
+				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			let amount_x: u128;
 			let amount_y: u128;
-			if tranding_pair.is_some() {
-				let (reserve_x, reserve_y) =
-					TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
-				let amount_y_optimal = quote(amount_x_desired, reserve_x, reserve_y);
+			if reserve_x > 0 || reserve_y > 0 {
+				if let Some(max_deviation_bps) = max_twap_deviation_bps {
+					Self::ensure_twap_deviation(
+						&trading_pair,
+						amount_x_desired,
+						amount_y_desired,
+						max_deviation_bps,
+					)?;
+				}
+				Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
+				let amount_y_optimal = quote(amount_x_desired, reserve_x, reserve_y)
+					.ok_or(Error::<T>::ArithmeticOverflow)?;
 				if amount_y_optimal <= amount_y_desired {
 					ensure!(
 						amount_y_optimal > 0 && amount_y_min > 0,
@@ -382,7 +1198,8 @@ pub mod pallet {
 					amount_x = amount_x_desired;
 					amount_y = amount_y_optimal;
 				} else {
-					let amount_x_optimal = quote(amount_y_desired, reserve_y, reserve_x);
+					let amount_x_optimal = quote(amount_y_desired, reserve_y, reserve_x)
+						.ok_or(Error::<T>::ArithmeticOverflow)?;
 					ensure!(amount_x_optimal <= amount_x_desired, Error::<T>::ExceedDesiredAmount);
 					ensure!(
 						amount_x_optimal > 0 && amount_x_min > 0,
@@ -391,16 +1208,15 @@ pub mod pallet {
 					amount_x = amount_x_optimal;
 					amount_y = amount_y_desired;
 				}
-				<TradingPairs<T>>::insert(
-					&trading_pair,
-					(reserve_x + amount_x, reserve_y + amount_y),
-				);
 			} else {
 				amount_x = amount_x_desired;
 				amount_y = amount_y_desired;
-				<TradingPairs<T>>::insert(&trading_pair, (amount_x, amount_y));
-				<TotalLiquidity<T>>::insert(&trading_pair, 0u128);
 			}
+			ensure!(
+				amount_x >= amount_x_min && amount_y >= amount_y_min,
+				Error::<T>::InsufficientLiquidityMinted
+			);
+			<TradingPairs<T>>::insert(&trading_pair, (reserve_x + amount_x, reserve_y + amount_y));
 
 			let balance_x = Balance::<T>::get(pk, &token_x_id).unwrap_or(0);
 			let balance_y = Balance::<T>::get(pk, &token_y_id).unwrap_or(0);
@@ -408,35 +1224,55 @@ pub mod pallet {
 				balance_x >= amount_x && balance_y >= amount_y,
 				Error::<T>::InsufficientBalance
 			);
+			Self::ensure_backed(&token_x_id)?;
+			Self::ensure_backed(&token_y_id)?;
 
 			Balance::<T>::insert(pk, &token_x_id, balance_x - amount_x);
 			Balance::<T>::insert(pk, &token_y_id, balance_y - amount_y);
 
 			let key = (trading_pair.clone(), pk);
 			// mint
+			let fee_on = Self::mint_fee(&trading_pair, reserve_x, reserve_y);
 			let (balance_x, balance_y) =
 				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			let mut total_supply =
 				TotalLiquidity::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			let liquidity: u128;
 			if total_supply == 0 {
-				liquidity = (amount_x * amount_y).integer_sqrt().saturating_sub(1000);
-				total_supply = liquidity;
+				total_supply = initial_liquidity(amount_x, amount_y);
+				liquidity = total_supply.saturating_sub(MINIMUM_LIQUIDITY);
+				<Liquidity<T>>::insert((trading_pair.clone(), BURN_PK), total_supply - liquidity);
 			} else {
 				// liquidity = Math.min(amount0.mul(_totalSupply) / _reserve0, amount1.mul(_totalSupply) / _reserve1);
-				liquidity = (amount_x.saturating_mul(total_supply) / (balance_x - amount_x))
-					.min(amount_y.saturating_mul(total_supply) / (balance_y - amount_y));
+				liquidity = quote(amount_x, balance_x - amount_x, total_supply)
+					.zip(quote(amount_y, balance_y - amount_y, total_supply))
+					.map(|(liquidity_x, liquidity_y)| liquidity_x.min(liquidity_y))
+					.ok_or(Error::<T>::ArithmeticOverflow)?;
 				total_supply += liquidity;
 			}
+			ensure!(liquidity > 0, Error::<T>::InsufficientLiquidityMinted);
 			let balances = Liquidity::<T>::get(&key).unwrap_or(0) + liquidity;
 			<Liquidity<T>>::insert(&key, balances);
+			<LiquidityUpdatedAt<T>>::insert(&key, frame_system::Pallet::<T>::block_number());
 			<TotalLiquidity<T>>::insert(&trading_pair, total_supply);
+			if fee_on {
+				<KLast<T>>::insert(&trading_pair, initial_liquidity(balance_x, balance_y));
+			}
 
-			Self::deposit_event(Event::AddLiquidity(trading_pair, pk, amount_x, amount_y));
+			let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+			Self::deposit_event(Event::AddLiquidity(
+				trading_pair,
+				pair_id,
+				pk,
+				amount_x,
+				amount_y,
+				liquidity,
+				total_supply,
+			));
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
+		#[pallet::weight(T::WeightInfo::remove_liquidity(Pallet::<T>::max_mpc_keys()))]
 		pub fn remove_liquidity(
 			origin: OriginFor<T>,
 			trading_pair: Vec<u8>,
@@ -444,23 +1280,507 @@ pub mod pallet {
 			liquidity: u128,
 			amount_x_min: u128,
 			amount_y_min: u128,
+			deadline: Option<T::BlockNumber>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			let owner = Self::to_account(&pk)?;
 			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_deadline(deadline)?;
+
+			Self::burn_liquidity(trading_pair, pk, liquidity, amount_x_min, amount_y_min)?;
+			Ok(())
+		}
+
+		/// Burn all the liquidity of `pk` in the trading pair
+		#[pallet::weight(T::WeightInfo::remove_all_liquidity(Pallet::<T>::max_mpc_keys()))]
+		pub fn remove_all_liquidity(
+			origin: OriginFor<T>,
+			trading_pair: Vec<u8>,
+			pk: [u8; 64],
+			amount_x_min: u128,
+			amount_y_min: u128,
+			deadline: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_deadline(deadline)?;
+
+			let liquidity = Liquidity::<T>::get((trading_pair.clone(), pk)).unwrap_or(0);
+			ensure!(liquidity > 0, Error::<T>::InvalidValue);
+			Self::burn_liquidity(trading_pair, pk, liquidity, amount_x_min, amount_y_min)?;
+			Ok(())
+		}
+
+		/// Replace the MPC members and the number of them that must confirm a withdrawal, a single
+		/// member can not do it as it would bypass the threshold.
+		///
+		/// The balances are backed by what the new members hold from then on, the tokens held by
+		/// the members replaced must be moved to the new ones beforehand, or the calls checking
+		/// the backing fail with `BalanceNotBacked`. `propose_mpc` rotates a single key while the
+		/// old one keeps backing the deposits pending on it.
+		#[pallet::weight(T::WeightInfo::set_mpc_members(members.len() as u32))]
+		pub fn set_mpc_members(
+			origin: OriginFor<T>,
+			members: Vec<[u8; 64]>,
+			threshold: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(threshold > 0 && threshold as usize <= members.len(), Error::<T>::InvalidValue);
+			let bounded = BoundedVec::<_, T::MaxMpcMembers>::try_from(members.clone())
+				.map_err(|_| Error::<T>::TooManyMpcMembers)?;
+			for (i, member) in members.iter().enumerate() {
+				ensure!(!members[..i].contains(member), Error::<T>::InvalidValue);
+			}
+			MpcMembers::<T>::put(bounded);
+			MpcThreshold::<T>::put(threshold);
+			Self::deposit_event(Event::MpcMembersSet(members, threshold));
+			Ok(())
+		}
+
+		/// Propose `new_mpc` to replace the key `old_mpc` of an MPC member, it takes effect once
+		/// `new_mpc` accepts it
+		#[pallet::weight(T::WeightInfo::propose_mpc())]
+		pub fn propose_mpc(
+			origin: OriginFor<T>,
+			old_mpc: [u8; 64],
+			new_mpc: [u8; 64],
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let members = MpcMembers::<T>::get();
+			ensure!(members.contains(&old_mpc), Error::<T>::InvalidValue);
+			ensure!(!members.contains(&new_mpc), Error::<T>::InvalidValue);
+			Self::to_account(&new_mpc)?;
+			PendingMpc::<T>::put((old_mpc, new_mpc));
+			Self::deposit_event(Event::MpcProposed(old_mpc, new_mpc));
+			Ok(())
+		}
+
+		/// Accept the pending MPC rotation, signed by the account of the proposed key.
+		///
+		/// The rotated out key keeps being served for deposits and withdrawals until the deposits
+		/// pending on it are confirmed.
+		#[pallet::weight(T::WeightInfo::accept_mpc())]
+		pub fn accept_mpc(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let (old_mpc, new_mpc) = PendingMpc::<T>::get().ok_or(Error::<T>::NoPendingMpc)?;
+			ensure!(Self::to_account(&new_mpc)? == sender, Error::<T>::NoPermission);
+
+			let mut members = MpcMembers::<T>::get();
+			let member = members
+				.iter_mut()
+				.find(|member| **member == old_mpc)
+				.ok_or(Error::<T>::NoPendingMpc)?;
+			*member = new_mpc;
+			MpcMembers::<T>::put(members);
+			PendingMpc::<T>::kill();
+			if MpcPendingDeposits::<T>::get(old_mpc) > 0 {
+				RetiredMpcMembers::<T>::try_append(old_mpc)
+					.map_err(|_| Error::<T>::TooManyMpcMembers)?;
+			}
+			Self::deposit_event(Event::MpcAccepted(old_mpc, new_mpc));
+			Ok(())
+		}
+
+		/// Set the receiver of the protocol fee, `None` turns the fee off
+		#[pallet::weight(T::WeightInfo::set_fee_to())]
+		pub fn set_fee_to(origin: OriginFor<T>, fee_to: Option<[u8; 64]>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			FeeTo::<T>::set(fee_to);
+			Self::deposit_event(Event::FeeToSet(fee_to));
+			Ok(())
+		}
+
+		/// Halt or resume trading, adding liquidity and deposits, withdrawals and removing
+		/// liquidity stay enabled so users can exit
+		#[pallet::weight(T::WeightInfo::set_paused())]
+		pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Paused::<T>::put(paused);
+			Self::deposit_event(Event::PausedSet(paused));
+			Ok(())
+		}
+
+		/// Set the fees in basis points taken from deposits and withdrawals for `FeeTo`, they are
+		/// not charged while `FeeTo` is not set. The fee of a withdrawal is taken when it is
+		/// requested and is not refunded if it is cancelled.
+		#[pallet::weight(T::WeightInfo::set_bridge_fees())]
+		pub fn set_bridge_fees(
+			origin: OriginFor<T>,
+			deposit_fee_bps: u16,
+			withdraw_fee_bps: u16,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(
+				deposit_fee_bps <= 10_000 && withdraw_fee_bps <= 10_000,
+				Error::<T>::InvalidValue
+			);
+			DepositFeeBps::<T>::put(deposit_fee_bps);
+			WithdrawFeeBps::<T>::put(withdraw_fee_bps);
+			Self::deposit_event(Event::BridgeFeesSet(deposit_fee_bps, withdraw_fee_bps));
+			Ok(())
+		}
+
+		/// Move `amount` of the `token_id` balance of `from_pk` to `to_pk` inside the pallet
+		#[pallet::weight(T::WeightInfo::transfer_balance(Pallet::<T>::max_mpc_keys()))]
+		pub fn transfer_balance(
+			origin: OriginFor<T>,
+			from_pk: [u8; 64],
+			to_pk: [u8; 64],
+			token_id: Vec<u8>,
+			amount: u128,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&from_pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			ensure!(amount > 0, Error::<T>::InvalidValue);
+
+			Self::move_balance(from_pk, to_pk, &token_id, amount)?;
+			Self::deposit_event(Event::BalanceTransferred(from_pk, to_pk, token_id, amount));
+			Ok(())
+		}
+
+		/// Allow `spender_pk` to move up to `amount` of the `token_id` balance of `owner_pk`, it
+		/// replaces the previous allowance
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			owner_pk: [u8; 64],
+			spender_pk: [u8; 64],
+			token_id: Vec<u8>,
+			amount: u128,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::to_account(&owner_pk)?;
+			ensure!(sender == owner, Error::<T>::NoPermission);
+
+			if amount == 0 {
+				Allowances::<T>::remove((owner_pk, spender_pk, token_id.clone()));
+			} else {
+				Allowances::<T>::insert((owner_pk, spender_pk, token_id.clone()), amount);
+			}
+			Self::deposit_event(Event::Approval(owner_pk, spender_pk, token_id, amount));
+			Ok(())
+		}
+
+		/// Move `amount` of the `token_id` balance of `owner_pk` to `recipient` against the
+		/// allowance granted to `spender_pk`
+		#[pallet::weight(T::WeightInfo::transfer_balance_from(Pallet::<T>::max_mpc_keys()))]
+		pub fn transfer_balance_from(
+			origin: OriginFor<T>,
+			spender_pk: [u8; 64],
+			owner_pk: [u8; 64],
+			recipient: [u8; 64],
+			token_id: Vec<u8>,
+			amount: u128,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let spender = Self::to_account(&spender_pk)?;
+			ensure!(sender == spender, Error::<T>::NoPermission);
+			Self::ensure_not_paused()?;
+			ensure!(amount > 0, Error::<T>::InvalidValue);
+
+			let key = (owner_pk, spender_pk, token_id.clone());
+			let allowance = Allowances::<T>::get(&key).unwrap_or(0);
+			ensure!(allowance >= amount, Error::<T>::InsufficientAllowance);
+			Self::move_balance(owner_pk, recipient, &token_id, amount)?;
+			if allowance == amount {
+				Allowances::<T>::remove(&key);
+			} else {
+				Allowances::<T>::insert(&key, allowance - amount);
+			}
+			Self::deposit_event(Event::TransferredFrom(
+				owner_pk, spender_pk, recipient, token_id, amount,
+			));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		pub fn to_account(public_key: &[u8; 64]) -> Result<T::AccountId, Error<T>> {
+			let mut pk_full: [u8; 65] = [0; 65];
+			pk_full[1..65].copy_from_slice(public_key);
+			pk_full[0] = 4;
+			let public_key = PublicKey::from_slice(&pk_full[..])
+				.map_err(|_| Error::<T>::SerializePublicKeyFailed)?;
+			let public_key_compressed = public_key.serialize();
+			let hash = BlakeTwo256::hash(&public_key_compressed);
+			T::AccountId::decode(&mut &hash[..]).map_err(|_| Error::<T>::AccountConversionFailed)
+		}
+
+		/// The full state of a pool in one read
+		pub fn pool_info(trading_pair: &Vec<u8>) -> Option<PoolInfo<T::BlockNumber>> {
+			let (reserve_x, reserve_y) = TradingPairs::<T>::get(trading_pair)?;
+			let total_liquidity = TotalLiquidity::<T>::get(trading_pair)?;
+			let (token_x_id, token_y_id) = TokenId::<T>::get(trading_pair)?;
+			let (_, _, last_updated) = PriceCumulative::<T>::get(trading_pair)?;
+			Some(PoolInfo {
+				reserve_x,
+				reserve_y,
+				total_liquidity,
+				token_x_id,
+				token_y_id,
+				fee_tier: ((FEE_DENOMINATOR - FEE_NUMERATOR) * 10_000 / FEE_DENOMINATOR) as u32,
+				last_updated,
+			})
+		}
+
+		/// The reserves of a trading pair
+		pub fn get_reserves(trading_pair: Vec<u8>) -> Option<(u128, u128)> {
+			TradingPairs::<T>::get(trading_pair)
+		}
+
+		/// The balance of `pk` in the token `token_id` held by the pallet
+		pub fn swap_balance(pk: [u8; 64], token_id: Vec<u8>) -> u128 {
+			Balance::<T>::get(pk, token_id).unwrap_or(0)
+		}
+
+		/// The deposits of `pk` waiting to be confirmed: token_id, nonce and the omniverse
+		/// transaction
+		pub fn pending_deposits(pk: [u8; 64]) -> Vec<(Vec<u8>, u128, OmniverseTransactionData)> {
+			DepositRecords::<T>::iter()
+				.filter(|((from, _, _), _)| *from == pk)
+				.map(|((_, token_id, nonce), (data, _))| (token_id, nonce, data))
+				.collect()
+		}
+
+		/// The withdrawals of `pk` waiting to be confirmed: token_id and amount
+		pub fn pending_withdrawals(pk: [u8; 64]) -> Vec<(Vec<u8>, u128)> {
+			Withdrawals::<T>::iter()
+				.filter(|((from, _), _)| *from == pk)
+				.map(|((_, token_id), amount)| (token_id, amount))
+				.collect()
+		}
+
+		/// The liquidity of `pk` in a trading pair, the total liquidity of the trading pair and the
+		/// share of `pk` in it
+		pub fn liquidity_share(
+			trading_pair: Vec<u8>,
+			pk: [u8; 64],
+		) -> Option<(u128, u128, Permill)> {
+			let total_liquidity = TotalLiquidity::<T>::get(&trading_pair)?;
+			let liquidity = Liquidity::<T>::get((trading_pair, pk)).unwrap_or(0);
+			let share = if total_liquidity == 0 {
+				Permill::zero()
+			} else {
+				Permill::from_rational(liquidity, total_liquidity)
+			};
+			Some((liquidity, total_liquidity, share))
+		}
+
+		/// The amounts of token x and token y paid out for burning `liquidity` of a trading pair
+		/// now, before any protocol fee is minted. `None` if the trading pair has no liquidity
+		pub fn preview_remove_liquidity(
+			trading_pair: Vec<u8>,
+			liquidity: u128,
+		) -> Option<(u128, u128)> {
+			let (reserve_x, reserve_y) = TradingPairs::<T>::get(&trading_pair)?;
+			let total_supply = TotalLiquidity::<T>::get(&trading_pair)?;
+			if total_supply == 0 || liquidity > total_supply {
+				return None;
+			}
+			burned_amounts(liquidity, reserve_x, reserve_y, total_supply)
+		}
+
+		/// The amount bought by selling `amount_in` of token x, or of token y if `token_in_is_x`
+		/// is false
+		pub fn quote_exact_in(
+			trading_pair: Vec<u8>,
+			token_in_is_x: bool,
+			amount_in: u128,
+		) -> Option<u128> {
+			let (reserve_x, reserve_y) = TradingPairs::<T>::get(trading_pair)?;
+			if token_in_is_x {
+				get_input_price(amount_in, reserve_x, reserve_y)
+			} else {
+				get_input_price(amount_in, reserve_y, reserve_x)
+			}
+		}
+
+		/// The amount to sell of token x to buy `amount_out` of token y, or of token y for token x
+		/// if `token_out_is_y` is false. `None` if the reserve can not cover `amount_out`
+		pub fn quote_exact_out(
+			trading_pair: Vec<u8>,
+			token_out_is_y: bool,
+			amount_out: u128,
+		) -> Option<u128> {
+			let (reserve_x, reserve_y) = TradingPairs::<T>::get(trading_pair)?;
+			let (reserve_in, reserve_out) =
+				if token_out_is_y { (reserve_x, reserve_y) } else { (reserve_y, reserve_x) };
+			if amount_out >= reserve_out {
+				return None;
+			}
+			get_output_price(amount_out, reserve_in, reserve_out)
+		}
+
+		/// The `min_token` to pass to a swap selling `amount_in`, the quote of `quote_exact_in`
+		/// reduced by `slippage_bps`
+		pub fn quote_with_slippage(
+			trading_pair: Vec<u8>,
+			token_in_is_x: bool,
+			amount_in: u128,
+			slippage_bps: u16,
+		) -> Option<u128> {
+			if slippage_bps > 10_000 {
+				return None;
+			}
+			let amount_out = Self::quote_exact_in(trading_pair, token_in_is_x, amount_in)?;
+			let min_token = U256::from(amount_out)
+				.checked_mul((10_000 - slippage_bps).into())?
+				.checked_div(10_000u32.into())?;
+			min_token.try_into().ok()
+		}
+
+		/// The amounts along a route selling `amount_in` through the trading pairs of `path`, the
+		/// first one is `amount_in`. The token sold to the first pair is the one not traded in the
+		/// second pair, or token x for a single pair
+		pub fn get_amounts_out(amount_in: u128, path: Vec<Vec<u8>>) -> Result<Vec<u128>, Error<T>> {
+			let route = Self::route(amount_in, &path)?;
+			Ok(route.into_iter().map(|(_, amount)| amount).collect())
+		}
+
+		/// The token sold to each trading pair of `path` and its amount, followed by the token and
+		/// the amount bought from the last one
+		fn route(amount_in: u128, path: &[Vec<u8>]) -> Result<Vec<(Vec<u8>, u128)>, Error<T>> {
+			let first = path.first().ok_or(Error::<T>::InvalidPath)?;
+			let (x_id, y_id) = TokenId::<T>::get(first).ok_or(Error::<T>::TradingPairNotExist)?;
+			let token_in = match path.get(1) {
+				Some(next) => {
+					let (next_x_id, next_y_id) =
+						TokenId::<T>::get(next).ok_or(Error::<T>::TradingPairNotExist)?;
+					if y_id == next_x_id || y_id == next_y_id {
+						x_id
+					} else {
+						y_id
+					}
+				},
+				None => x_id,
+			};
+
+			let mut route = sp_std::vec![(token_in, amount_in)];
+			for trading_pair in path.iter() {
+				let (token_in, amount) = route.last().cloned().ok_or(Error::<T>::InvalidPath)?;
+				let (x_id, y_id) =
+					TokenId::<T>::get(trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+				let (reserve_x, reserve_y) =
+					TradingPairs::<T>::get(trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+				let (amount_out, token_out) = if token_in == x_id {
+					(get_input_price(amount, reserve_x, reserve_y), y_id)
+				} else if token_in == y_id {
+					(get_input_price(amount, reserve_y, reserve_x), x_id)
+				} else {
+					return Err(Error::<T>::InvalidPath);
+				};
+				route.push((token_out, amount_out.ok_or(Error::<T>::InsufficientLiquidity)?));
+			}
+			Ok(route)
+		}
+
+		/// Ensure the current block has not passed `deadline`, if one is supplied
+		fn ensure_deadline(deadline: Option<T::BlockNumber>) -> Result<(), Error<T>> {
+			if let Some(deadline) = deadline {
+				ensure!(
+					frame_system::Pallet::<T>::block_number() <= deadline,
+					Error::<T>::DeadlinePassed
+				);
+			}
+			Ok(())
+		}
+
+		/// The most MPC keys whose balances are summed up to check the backing of a token, the
+		/// members and the retired keys
+		fn max_mpc_keys() -> u32 {
+			T::MaxMpcMembers::get().saturating_mul(2)
+		}
+
+		/// Whether `key` is an MPC member, or a retired one still serving its pending deposits
+		fn is_mpc(key: &[u8; 64]) -> bool {
+			MpcMembers::<T>::get().contains(key) || RetiredMpcMembers::<T>::get().contains(key)
+		}
+
+		/// Ensure what is owed of `token_id`, the balances and pending withdrawals together, is
+		/// covered by what the MPC holds of the token. It is credited for the tokens transferred
+		/// to the MPC, so it can only exceed them if the two have drifted apart
+		fn ensure_backed(token_id: &Vec<u8>) -> Result<(), Error<T>> {
+			let custody = MpcMembers::<T>::get()
+				.iter()
+				.chain(RetiredMpcMembers::<T>::get().iter())
+				.fold(0u128, |custody, mpc| {
+					custody.saturating_add(T::OmniverseToken::balance_of(token_id, mpc))
+				});
+			ensure!(TotalDeposited::<T>::get(token_id) <= custody, Error::<T>::BalanceNotBacked);
+			Ok(())
+		}
+
+		/// Release the MPC key a confirmed deposit was transferred to, a retired key is dropped
+		/// once its last pending deposit is confirmed
+		fn release_pending_deposit(data: &OmniverseTransactionData) {
+			let to = Fungible::decode(&mut data.payload.as_slice())
+				.ok()
+				.and_then(|fungible| <[u8; 64]>::try_from(fungible.ex_data).ok());
+			if let Some(to) = to {
+				let pending = MpcPendingDeposits::<T>::get(to).saturating_sub(1);
+				if pending == 0 {
+					MpcPendingDeposits::<T>::remove(to);
+					RetiredMpcMembers::<T>::mutate(|retired| retired.retain(|key| *key != to));
+				} else {
+					MpcPendingDeposits::<T>::insert(to, pending);
+				}
+			}
+		}
+
+		/// Ensure the pallet is not paused by the admin
+		fn ensure_not_paused() -> Result<(), Error<T>> {
+			ensure!(!Paused::<T>::get(), Error::<T>::Paused);
+			Ok(())
+		}
+
+		/// Ensure the price impact of a swap does not exceed `max_price_impact_bps`, if one is
+		/// supplied
+		fn ensure_price_impact(
+			input_amount: u128,
+			output_amount: u128,
+			input_reserve: u128,
+			output_reserve: u128,
+			max_price_impact_bps: Option<u16>,
+		) -> Result<(), Error<T>> {
+			if let Some(max_price_impact_bps) = max_price_impact_bps {
+				let impact =
+					price_impact_bps(input_amount, output_amount, input_reserve, output_reserve)
+						.ok_or(Error::<T>::ArithmeticOverflow)?;
+				ensure!(impact <= max_price_impact_bps.into(), Error::<T>::PriceImpactTooHigh);
+			}
+			Ok(())
+		}
+
+		/// Burn `liquidity` of `pk` and credit the reserves it owns to the balances of `pk`
+		fn burn_liquidity(
+			trading_pair: Vec<u8>,
+			pk: [u8; 64],
+			liquidity: u128,
+			amount_x_min: u128,
+			amount_y_min: u128,
+		) -> Result<(), Error<T>> {
+			let (reserve_x, reserve_y) =
+				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
+			Self::update_price_cumulative(&trading_pair, reserve_x, reserve_y);
+			let fee_on = Self::mint_fee(&trading_pair, reserve_x, reserve_y);
 
 			let key = (trading_pair.clone(), pk);
 			let balances = Liquidity::<T>::get(&key).unwrap_or(0);
 			ensure!(balances >= liquidity, Error::<T>::InvalidValue);
 
 			// burn
-			let (reserve_x, reserve_y) =
-				TradingPairs::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			<Liquidity<T>>::insert(&key, balances - liquidity);
+			<LiquidityUpdatedAt<T>>::insert(&key, frame_system::Pallet::<T>::block_number());
 			let total_supply =
 				TotalLiquidity::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
-			let amount_x = liquidity.saturating_mul(reserve_x) / total_supply;
-			let amount_y = liquidity.saturating_mul(reserve_y) / total_supply;
+			ensure!(total_supply > 0, Error::<T>::InsufficientLiquidity);
+			let (amount_x, amount_y) =
+				burned_amounts(liquidity, reserve_x, reserve_y, total_supply)
+					.ok_or(Error::<T>::ArithmeticOverflow)?;
+			ensure!(amount_x > 0 && amount_y > 0, Error::<T>::InsufficientLiquidityBurned);
 			ensure!(
 				amount_x >= amount_x_min && amount_y >= amount_y_min,
 				Error::<T>::InsufficientAmount
@@ -468,59 +1788,384 @@ pub mod pallet {
 
 			<TotalLiquidity<T>>::insert(&trading_pair, total_supply - liquidity);
 			<TradingPairs<T>>::insert(&trading_pair, (reserve_x - amount_x, reserve_y - amount_y));
+			if fee_on {
+				<KLast<T>>::insert(
+					&trading_pair,
+					initial_liquidity(reserve_x - amount_x, reserve_y - amount_y),
+				);
+			}
 
 			let (token_x_id, token_y_id) =
 				TokenId::<T>::get(&trading_pair).ok_or(Error::<T>::TradingPairNotExist)?;
 			let balance_x = Balance::<T>::get(pk, &token_x_id).unwrap_or(0);
 			let balance_y = Balance::<T>::get(pk, &token_y_id).unwrap_or(0);
+			Self::ensure_backed(&token_x_id)?;
+			Self::ensure_backed(&token_y_id)?;
 
 			Balance::<T>::insert(pk, &token_x_id, balance_x + amount_x);
 			Balance::<T>::insert(pk, &token_y_id, balance_y + amount_y);
-			Self::deposit_event(Event::RemoveLiquidity(trading_pair, pk, amount_x, amount_y));
+			let pair_id = canonical_pair_id(&token_x_id, &token_y_id);
+			Self::deposit_event(Event::RemoveLiquidity(
+				trading_pair,
+				pair_id,
+				pk,
+				amount_x,
+				amount_y,
+				liquidity,
+				total_supply - liquidity,
+			));
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1).ref_time())]
-		pub fn set_mpc(origin: OriginFor<T>, new_mpc: [u8; 64]) -> DispatchResult {
-			let sender = ensure_signed(origin)?;
-			let mpc = Self::to_account(&new_mpc)?;
-			ensure!(mpc == sender, Error::<T>::NoPermission);
-			Mpc::<T>::set(new_mpc);
+		/// Mint 1/6 of the growth of sqrt(k) since the last liquidity event as liquidity to
+		/// `FeeTo`, the reserves must be the ones before the event, returns whether the protocol
+		/// fee is on
+		fn mint_fee(trading_pair: &Vec<u8>, reserve_x: u128, reserve_y: u128) -> bool {
+			let root_k_last = KLast::<T>::get(trading_pair).unwrap_or(0);
+			let fee_to = match FeeTo::<T>::get() {
+				Some(fee_to) => fee_to,
+				None => {
+					if root_k_last != 0 {
+						KLast::<T>::remove(trading_pair);
+					}
+					return false;
+				},
+			};
+
+			let root_k = initial_liquidity(reserve_x, reserve_y);
+			if root_k_last != 0 && root_k > root_k_last {
+				let total_supply = TotalLiquidity::<T>::get(trading_pair).unwrap_or(0);
+				let numerator = U256::from(total_supply) * U256::from(root_k - root_k_last);
+				let denominator = U256::from(root_k) * U256::from(5) + U256::from(root_k_last);
+				let liquidity = (numerator / denominator).low_u128();
+				if liquidity > 0 {
+					let key = (trading_pair.clone(), fee_to);
+					<Liquidity<T>>::insert(
+						&key,
+						Liquidity::<T>::get(&key).unwrap_or(0) + liquidity,
+					);
+					<TotalLiquidity<T>>::insert(trading_pair, total_supply + liquidity);
+				}
+			}
+			true
+		}
+
+		/// Credit the amount of an executed deposit to the balance of its sender
+		fn credit_deposit(
+			token_id: Vec<u8>,
+			data: &OmniverseTransactionData,
+		) -> Result<(), Error<T>> {
+			// A deposit is credited once whichever way it is confirmed
+			let key = (data.from, token_id.clone(), data.nonce);
+			ensure!(!ConfirmedDeposits::<T>::contains_key(&key), Error::<T>::IsComfirmed);
+			ConfirmedDeposits::<T>::insert(&key, ());
+			let fungible = Fungible::decode(&mut data.payload.as_slice())
+				.map_err(|_| Error::<T>::DecodePayloadFailed)?;
+			TotalDeposited::<T>::mutate(&token_id, |total| {
+				*total = total.saturating_add(fungible.amount)
+			});
+			Self::ensure_backed(&token_id)?;
+			let amount = Self::charge_bridge_fee(
+				data.from,
+				&token_id,
+				fungible.amount,
+				DepositFeeBps::<T>::get(),
+			);
+			// let balance
+			let mut balance = Balance::<T>::get(data.from, &token_id).unwrap_or(0);
+			balance += amount;
+			Balance::<T>::insert(data.from, &token_id, balance);
+			Self::deposit_event(Event::DepositComfirmed(data.from, token_id, data.nonce));
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		pub fn to_account(public_key: &[u8; 64]) -> Result<T::AccountId, Error<T>> {
-			let mut pk_full: [u8; 65] = [0; 65];
-			pk_full[1..65].copy_from_slice(public_key);
-			pk_full[0] = 4;
-			let public_key = PublicKey::from_slice(&pk_full[..])
-				.map_err(|_| Error::<T>::SerializePublicKeyFailed)?;
-			let public_key_compressed = public_key.serialize();
-			let hash = BlakeTwo256::hash(&public_key_compressed);
-			Ok(T::AccountId::decode(&mut &hash[..]).unwrap())
+		/// Credit the fee of `bps` on `amount` to `FeeTo` if it is set, returns the amount left
+		fn charge_bridge_fee(pk: [u8; 64], token_id: &Vec<u8>, amount: u128, bps: u16) -> u128 {
+			let fee_to = match FeeTo::<T>::get() {
+				Some(fee_to) if bps > 0 => fee_to,
+				_ => return amount,
+			};
+			let fee = bridge_fee(amount, bps);
+			if fee > 0 {
+				let balance = Balance::<T>::get(fee_to, token_id).unwrap_or(0);
+				Balance::<T>::insert(fee_to, token_id, balance.saturating_add(fee));
+				Self::deposit_event(Event::BridgeFeeCharged(pk, token_id.clone(), fee));
+			}
+			amount - fee
+		}
+
+		/// Get the trading pair or create it with the tokens stored in canonical order, returns
+		/// whether `token_x_id` and `token_y_id` are passed in the reverse of that order
+		pub(crate) fn get_or_create_pair(
+			trading_pair: &Vec<u8>,
+			token_x_id: &Vec<u8>,
+			token_y_id: &Vec<u8>,
+		) -> Result<bool, Error<T>> {
+			ensure!(token_x_id != token_y_id, Error::<T>::SameToken);
+			let (x_id, y_id) = sort_token_ids(token_x_id, token_y_id);
+			let reversed = &x_id != token_x_id;
+			if let Some(token_ids) = TokenId::<T>::get(trading_pair) {
+				ensure!(token_ids == (x_id, y_id), Error::<T>::MismatchTokenId);
+				return Ok(reversed);
+			}
+
+			let pair_id = canonical_pair_id(&x_id, &y_id);
+			ensure!(!PairOf::<T>::contains_key(&pair_id), Error::<T>::PairAlreadyExists);
+			<PairOf<T>>::insert(&pair_id, trading_pair);
+			<TokenId<T>>::insert(trading_pair, (x_id.clone(), y_id.clone()));
+			<TradingPairs<T>>::insert(trading_pair, (0u128, 0u128));
+			<TotalLiquidity<T>>::insert(trading_pair, 0u128);
+			let now = frame_system::Pallet::<T>::block_number();
+			<PriceCumulative<T>>::insert(trading_pair, (0u128, 0u128, now));
+			<PairCreatedAt<T>>::insert(trading_pair, now);
+			Self::deposit_event(Event::PairCreated(trading_pair.clone(), pair_id, x_id, y_id));
+			Ok(reversed)
+		}
+
+		/// Debit `amount` of `token_id` from the balance of `from` and credit it to `to`
+		pub(crate) fn move_balance(
+			from: [u8; 64],
+			to: [u8; 64],
+			token_id: &Vec<u8>,
+			amount: u128,
+		) -> DispatchResult {
+			let balance_from = Balance::<T>::get(from, token_id).unwrap_or(0);
+			ensure!(balance_from >= amount, Error::<T>::BalanceNotEnough);
+			Self::ensure_backed(token_id)?;
+			Balance::<T>::insert(from, token_id, balance_from - amount);
+			let balance_to = Balance::<T>::get(to, token_id).unwrap_or(0);
+			Balance::<T>::insert(to, token_id, balance_to.saturating_add(amount));
+			Ok(())
+		}
+
+		/// Accumulate the prices of the pair weighted by the blocks elapsed since the last update,
+		/// the reserves must be the ones before the trade
+		pub(crate) fn update_price_cumulative(
+			trading_pair: &Vec<u8>,
+			reserve_x: u128,
+			reserve_y: u128,
+		) {
+			let now = frame_system::Pallet::<T>::block_number();
+			let (mut price_x_cumulative, mut price_y_cumulative, last_block) =
+				PriceCumulative::<T>::get(trading_pair).unwrap_or((0, 0, now));
+			let elapsed: u128 = now.saturating_sub(last_block).saturated_into();
+			if elapsed > 0 && reserve_x > 0 && reserve_y > 0 {
+				price_x_cumulative = price_x_cumulative
+					.saturating_add(spot_price(reserve_x, reserve_y).saturating_mul(elapsed));
+				price_y_cumulative = price_y_cumulative
+					.saturating_add(spot_price(reserve_y, reserve_x).saturating_mul(elapsed));
+			}
+			PriceCumulative::<T>::insert(
+				trading_pair,
+				(price_x_cumulative, price_y_cumulative, now),
+			);
+		}
+
+		/// Accumulate the amounts of token X and token Y sold to the pair
+		pub(crate) fn add_volume(trading_pair: &Vec<u8>, volume_x: u128, volume_y: u128) {
+			PairVolume::<T>::mutate(trading_pair, |(x, y)| {
+				*x = x.saturating_add(volume_x);
+				*y = y.saturating_add(volume_y);
+			});
+		}
+
+		/// The time-weighted average price of token X in token Y since the pair was created,
+		/// scaled by `PRICE_PRECISION`
+		pub fn twap(trading_pair: &Vec<u8>) -> Option<u128> {
+			let (price_x_cumulative, _, last_block) = PriceCumulative::<T>::get(trading_pair)?;
+			let created_at = PairCreatedAt::<T>::get(trading_pair)?;
+			let (reserve_x, reserve_y) = TradingPairs::<T>::get(trading_pair)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let elapsed: u128 = now.saturating_sub(created_at).saturated_into();
+			if elapsed == 0 || reserve_x == 0 {
+				return None;
+			}
+			// The reserves have not changed since the last update
+			let pending: u128 = now.saturating_sub(last_block).saturated_into();
+			let price_x_cumulative = price_x_cumulative
+				.saturating_add(spot_price(reserve_x, reserve_y).saturating_mul(pending));
+			Some(price_x_cumulative / elapsed)
+		}
+
+		/// Ensure the price implied by `amount_x` and `amount_y` is within `max_deviation_bps` of
+		/// the TWAP, it passes if the pair has no price history yet
+		fn ensure_twap_deviation(
+			trading_pair: &Vec<u8>,
+			amount_x: u128,
+			amount_y: u128,
+			max_deviation_bps: u16,
+		) -> Result<(), Error<T>> {
+			if let Some(twap) = Self::twap(trading_pair) {
+				let price = spot_price(amount_x, amount_y);
+				let deviation = if price > twap { price - twap } else { twap - price };
+				ensure!(
+					deviation.saturating_mul(10_000)
+						<= twap.saturating_mul(max_deviation_bps as u128),
+					Error::<T>::PriceDeviationTooHigh
+				);
+			}
+			Ok(())
 		}
 	}
 
 	// impl<T: Config> Pallet<T> {
-	pub fn get_input_price(input_amount: u128, input_reserve: u128, output_reserve: u128) -> u128 {
-		// ensure!(input_reserve > 0 && output_reserve > 0u128);
-		let numerator: u128 = input_amount * output_reserve;
-		let denominator: u128 = input_reserve + input_amount;
-		numerator / denominator
+	/// The liquidity provider fee kept in the reserves out of `input_amount`
+	pub fn swap_fee(input_amount: u128) -> u128 {
+		input_amount.saturating_mul(FEE_DENOMINATOR - FEE_NUMERATOR) / FEE_DENOMINATOR
+	}
+
+	/// The intermediate products are computed in 256 bits, returns `None` if the result does not
+	/// fit in u128
+	pub fn get_input_price(
+		input_amount: u128,
+		input_reserve: u128,
+		output_reserve: u128,
+	) -> Option<u128> {
+		if input_reserve == 0 || output_reserve == 0 {
+			return None;
+		}
+		let input_amount_with_fee = U256::from(input_amount).checked_mul(FEE_NUMERATOR.into())?;
+		let numerator = input_amount_with_fee.checked_mul(output_reserve.into())?;
+		let denominator = U256::from(input_reserve)
+			.checked_mul(FEE_DENOMINATOR.into())?
+			.checked_add(input_amount_with_fee)?;
+		numerator.checked_div(denominator)?.try_into().ok()
+	}
+
+	/// The intermediate products are computed in 256 bits, returns `None` if the result does not
+	/// fit in u128
+	pub fn get_output_price(
+		output_amout: u128,
+		input_reserve: u128,
+		output_reserve: u128,
+	) -> Option<u128> {
+		if input_reserve == 0 || output_reserve == 0 {
+			return None;
+		}
+		let numerator = U256::from(input_reserve)
+			.checked_mul(output_amout.into())?
+			.checked_mul(FEE_DENOMINATOR.into())?;
+		let denominator = U256::from(output_reserve.checked_sub(output_amout)?)
+			.checked_mul(FEE_NUMERATOR.into())?;
+		let input_amount: u128 = numerator.checked_div(denominator)?.try_into().ok()?;
+		input_amount.checked_add(1)
+	}
+
+	/// the deviation of the execution price `output_amount / input_amount` from the spot price
+	/// `output_reserve / input_reserve` in basis points, the swap fee is part of the deviation
+	pub fn price_impact_bps(
+		input_amount: u128,
+		output_amount: u128,
+		input_reserve: u128,
+		output_reserve: u128,
+	) -> Option<u128> {
+		let spot = U256::from(input_amount).checked_mul(output_reserve.into())?;
+		let execution = U256::from(output_amount).checked_mul(input_reserve.into())?;
+		if spot.is_zero() || execution >= spot {
+			return Some(0);
+		}
+		let impact = (spot - execution).checked_mul(10_000u32.into())?.checked_div(spot)?;
+		impact.try_into().ok()
+	}
+
+	/// whether the product of the reserves, less the swap fee of the input, does not decrease when
+	/// `input_amount` is sold for `output_amount`
+	pub fn k_invariant_holds(
+		input_amount: u128,
+		output_amount: u128,
+		input_reserve: u128,
+		output_reserve: u128,
+	) -> bool {
+		if output_amount > output_reserve {
+			return false
+		}
+		// (input_reserve + input_amount * 0.997) * (output_reserve - output_amount) >=
+		// input_reserve * output_reserve, scaled by FEE_DENOMINATOR
+		let input_adjusted = U256::from(input_reserve) * U256::from(FEE_DENOMINATOR) +
+			U256::from(input_amount) * U256::from(FEE_NUMERATOR);
+		let k_after = input_adjusted.full_mul(U256::from(output_reserve - output_amount));
+		let k_before = (U256::from(input_reserve) * U256::from(output_reserve))
+			.full_mul(U256::from(FEE_DENOMINATOR));
+		k_after >= k_before
 	}
 
-	pub fn get_output_price(output_amout: u128, input_reserve: u128, output_reserve: u128) -> u128 {
-		// ensure!(input_reserve > 0u128 && output_reserve > 0u128);
-		let numerator: u128 = input_reserve * output_amout;
-		let denominator: u128 = output_reserve - output_amout;
-		numerator / denominator
+	/// the id of the pool trading `token_a_id` against `token_b_id`, it does not depend on the order
+	/// of the tokens
+	pub fn canonical_pair_id(token_a_id: &Vec<u8>, token_b_id: &Vec<u8>) -> Vec<u8> {
+		BlakeTwo256::hash(&sort_token_ids(token_a_id, token_b_id).encode())
+			.as_bytes()
+			.to_vec()
+	}
+
+	/// `token_a_id` and `token_b_id` in the order they are stored as token X and token Y of a
+	/// trading pair
+	pub fn sort_token_ids(token_a_id: &Vec<u8>, token_b_id: &Vec<u8>) -> (Vec<u8>, Vec<u8>) {
+		if token_a_id <= token_b_id {
+			(token_a_id.clone(), token_b_id.clone())
+		} else {
+			(token_b_id.clone(), token_a_id.clone())
+		}
+	}
+
+	/// the fee of `bps` basis points charged on bridging `amount`, no more than `amount`
+	pub fn bridge_fee(amount: u128, bps: u16) -> u128 {
+		// the fee of at most 10_000 basis points always fits in u128
+		(U256::from(amount) * U256::from(bps.min(10_000)) / U256::from(10_000)).as_u128()
+	}
+
+	/// the price of token X in token Y, scaled by `PRICE_PRECISION`
+	pub fn spot_price(reserve_x: u128, reserve_y: u128) -> u128 {
+		reserve_y.saturating_mul(PRICE_PRECISION) / reserve_x
+	}
+
+	/// the liquidity of the first mint, sqrt(amount_x * amount_y), the product is computed in 256
+	/// bits when it overflows u128
+	pub fn initial_liquidity(amount_x: u128, amount_y: u128) -> u128 {
+		match amount_x.checked_mul(amount_y) {
+			Some(product) => product.integer_sqrt(),
+			// the square root of the product of two u128 always fits in u128
+			None => (U256::from(amount_x) * U256::from(amount_y)).integer_sqrt().as_u128(),
+		}
 	}
 
 	/// given some amount of an asset and pair reserves, returns an equivalent amount of the other asset
-	pub fn quote(amount_x: u128, reserve_x: u128, reserve_y: u128) -> u128 {
-		amount_x * reserve_y / reserve_x
+	pub fn quote(amount_x: u128, reserve_x: u128, reserve_y: u128) -> Option<u128> {
+		let amount_y = U256::from(amount_x).checked_mul(reserve_y.into())?;
+		amount_y.checked_div(reserve_x.into())?.try_into().ok()
+	}
+
+	/// The amounts of token x and token y paid out for burning `liquidity` out of `total_supply`
+	pub fn burned_amounts(
+		liquidity: u128,
+		reserve_x: u128,
+		reserve_y: u128,
+		total_supply: u128,
+	) -> Option<(u128, u128)> {
+		Some((
+			quote(liquidity, total_supply, reserve_x)?,
+			quote(liquidity, total_supply, reserve_y)?,
+		))
+	}
+
+	/// `quote` for tokens of different decimals, the reserves are the price in whole tokens, e.g.
+	/// (1, 2) when one X is worth two Y, and the amounts are in the smallest units of each token
+	pub fn quote_scaled(
+		amount_x: u128,
+		reserve_x: u128,
+		reserve_y: u128,
+		decimals_x: u8,
+		decimals_y: u8,
+	) -> Option<u128> {
+		let mut amount_y = U256::from(amount_x).checked_mul(reserve_y.into())?;
+		let mut divisor = U256::from(reserve_x);
+		if decimals_y >= decimals_x {
+			amount_y = amount_y
+				.checked_mul(U256::from(10).checked_pow((decimals_y - decimals_x).into())?)?;
+		} else {
+			divisor = divisor
+				.checked_mul(U256::from(10).checked_pow((decimals_x - decimals_y).into())?)?;
+		}
+		amount_y.checked_div(divisor)?.try_into().ok()
 	}
 	// }
 }
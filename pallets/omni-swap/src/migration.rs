@@ -0,0 +1,50 @@
+//! Storage migrations of the pallet.
+use super::*;
+use frame_support::{
+	storage::migration::take_storage_value,
+	traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion, UnixTime},
+	BoundedVec,
+};
+use pallet_omniverse_protocol::migration::v0;
+
+/// Migrate the pallet storage to v1, the deposited transactions are translated to carry the
+/// signature scheme, which is secp256k1 for all of them, and stamped with the time of the upgrade
+/// as the time they were deposited at. The single MPC key is moved to the MPC members.
+pub fn migrate_to_v1<T: Config>() -> frame_support::weights::Weight {
+	let on_chain_storage_version = Pallet::<T>::on_chain_storage_version();
+	log::info!(
+		target: "runtime::omniverse-swap",
+		"Running migration storage v1 for omniverse swap with storage version {:?}",
+		on_chain_storage_version,
+	);
+
+	if on_chain_storage_version < 1 {
+		let mut count = 0u64;
+		let now = T::Timestamp::now().as_secs();
+		DepositRecords::<T>::translate::<v0::OmniverseTransactionData, _>(|_, data| {
+			count += 1;
+			Some((data.upgrade(), now))
+		});
+		if let Some(mpc) =
+			take_storage_value::<[u8; 64]>(Pallet::<T>::name().as_bytes(), b"Mpc", &[])
+		{
+			count += 1;
+			MpcMembers::<T>::put(BoundedVec::try_from(sp_std::vec![mpc]).unwrap_or_default());
+		}
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!(
+			target: "runtime::omniverse-swap",
+			"Running migration storage v1 for omniverse swap with storage version {:?} done",
+			on_chain_storage_version,
+		);
+		// calculate and return migration weights
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	} else {
+		log::warn!(
+			target: "runtime::omniverse-swap",
+			"Attempted to apply migration to v1 but failed because storage version is {:?}",
+			on_chain_storage_version,
+		);
+		T::DbWeight::get().reads(1)
+	}
+}
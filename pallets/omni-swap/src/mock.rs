@@ -6,7 +6,7 @@ use frame_support::{
 	parameter_types,
 	traits::{ConstU16, ConstU32, ConstU64, UnixTime},
 };
-use pallet_assets::{traits::OmniverseTokenFactoryHandler, FactoryResult};
+use pallet_assets::{traits::OmniverseTokenFactoryHandler, FactoryResult, PALLET_NAME};
 use pallet_omniverse_protocol::{
 	traits::OmniverseAccounts, OmniverseTransactionData, OmniverseTx, VerifyError, VerifyResult,
 };
@@ -37,6 +37,13 @@ frame_support::construct_runtime!(
 parameter_types! {
 	static Frozen: HashMap<(u32, u64), u128> = Default::default();
 	static Hooks: Vec<Hook> = Default::default();
+	// key: (pk, token_id, nonce)
+	static TransactionData: HashMap<([u8; 64], Vec<u8>, u128), OmniverseTx> = Default::default();
+	static TimePast: u64 = 0;
+	// replaces the result of the omniverse token if it is set
+	pub static MockFactoryResult: Option<FactoryResult> = None;
+	pub const DepositExpiry: u64 = 100;
+	pub const MaxMpcMembers: u32 = 4;
 }
 pub struct TestFreezer;
 impl pallet_assets::FrozenBalance<u32, u64, u128> for TestFreezer {
@@ -92,6 +99,10 @@ impl pallet_assets::Config for Test {
 	type MetadataDepositPerByte = ConstU64<1>;
 	type ApprovalDeposit = ConstU64<1>;
 	type StringLimit = ConstU32<50>;
+	type MaxPayloadSize = ConstU32<256>;
+	type MaxDelayedQueue = ConstU32<100>;
+	type MaxTokenIdLength = ConstU32<32>;
+	type MaxMembers = ConstU32<4>;
 	type Freezer = TestFreezer;
 	type WeightInfo = ();
 	type Extra = ();
@@ -124,17 +135,39 @@ impl OmniverseTokenFactoryHandler for OmniverseToken {
 		token_id: Vec<u8>,
 		data: &OmniverseTransactionData,
 	) -> Result<FactoryResult, DispatchError> {
+		if let Some(result) = MockFactoryResult::get() {
+			return Ok(result);
+		}
+		// The protocol rejects a transaction it has already recorded
+		if let Some(omni_tx) = OmniverseProtocol::get_transaction_data(
+			data.from,
+			PALLET_NAME.to_vec(),
+			token_id.clone(),
+			data.nonce,
+		) {
+			if omni_tx.tx_data == *data {
+				return Ok(FactoryResult::ProtocolDuplicated);
+			}
+		}
 		assert_ok!(Assets::send_transaction(
 			RuntimeOrigin::signed(1),
 			token_id.to_vec(),
 			data.clone()
 		));
-		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-			data.clone(),
-			Timestamp::now().as_secs(),
-		)));
+		OmniverseProtocol::set_transaction_data(
+			token_id,
+			OmniverseTx::new(data.clone(), Timestamp::now().as_secs()),
+		);
 		Ok(FactoryResult::Success)
 	}
+
+	fn decimals(token_id: &Vec<u8>) -> Option<u8> {
+		<Assets as OmniverseTokenFactoryHandler>::decimals(token_id)
+	}
+
+	fn balance_of(token_id: &Vec<u8>, pk: &[u8; 64]) -> u128 {
+		<Assets as OmniverseTokenFactoryHandler>::balance_of(token_id, pk)
+	}
 }
 
 impl omni_swap::Config for Test {
@@ -142,6 +175,13 @@ impl omni_swap::Config for Test {
 	// type OmniverseToken = Type;
 	type OmniverseToken = OmniverseToken;
 	type OmniverseProtocol = OmniverseProtocol;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type Timestamp = Timestamp;
+	type DepositExpiry = DepositExpiry;
+	type MaxMpcMembers = MaxMpcMembers;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = BenchmarkHelper;
+	type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
@@ -155,20 +195,20 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 	let mut ext: sp_io::TestExternalities = storage.into();
 	// Clear thread local vars for https://github.com/paritytech/substrate/issues/10479.
 	ext.execute_with(|| take_hooks());
+	TransactionData::take();
+	TimePast::take();
 	ext.execute_with(|| System::set_block_number(1));
 	ext
 }
 
-pub static mut TRANSACTION_DATA: Option<OmniverseTx> = None;
-
 #[derive(Default)]
 pub struct OmniverseProtocol();
 
 impl OmniverseProtocol {
-	pub fn set_transaction_data(tx_data: Option<OmniverseTx>) {
-		unsafe {
-			TRANSACTION_DATA = tx_data;
-		}
+	pub fn set_transaction_data(token_id: Vec<u8>, tx_data: OmniverseTx) {
+		TransactionData::mutate(|m| {
+			m.insert((tx_data.tx_data.from, token_id, tx_data.tx_data.nonce), tx_data);
+		});
 	}
 }
 
@@ -178,6 +218,7 @@ impl OmniverseAccounts for OmniverseProtocol {
 		_token_id: &[u8],
 		data: &OmniverseTransactionData,
 		_with_ethereum: bool,
+		_relayed: bool,
 	) -> Result<VerifyResult, VerifyError> {
 		if data.signature == [0; 65] {
 			return Err(VerifyError::SignatureError);
@@ -198,22 +239,25 @@ impl OmniverseAccounts for OmniverseProtocol {
 		1
 	}
 
+	fn get_cooling_down_time() -> u64 {
+		0
+	}
+
 	fn get_transaction_data(
-		_pk: [u8; 64],
+		pk: [u8; 64],
 		_pallet_name: Vec<u8>,
-		_token_id: Vec<u8>,
-		_nonce: u128,
+		token_id: Vec<u8>,
+		nonce: u128,
 	) -> Option<OmniverseTx> {
-		unsafe { TRANSACTION_DATA.clone() }
+		TransactionData::get().get(&(pk, token_id, nonce)).cloned()
 	}
 
-	fn execute(_pk: [u8; 64], _pallet_name: Vec<u8>, _token_id: Vec<u8>, _nonce: u128) {
-		unsafe {
-			match TRANSACTION_DATA.as_mut() {
-				Some(tx_data) => tx_data.executed = true,
-				None => {},
+	fn execute(pk: [u8; 64], _pallet_name: Vec<u8>, token_id: Vec<u8>, nonce: u128) {
+		TransactionData::mutate(|m| {
+			if let Some(tx_data) = m.get_mut(&(pk, token_id, nonce)) {
+				tx_data.executed = true;
 			}
-		}
+		});
 	}
 }
 
@@ -221,25 +265,107 @@ pub(crate) fn take_hooks() -> Vec<Hook> {
 	Hooks::take()
 }
 
-pub static mut TIME_PAST: u64 = 0;
-
 pub struct Timestamp {}
 
 impl Timestamp {
 	pub fn past(t: u64) {
-		unsafe {
-			TIME_PAST = TIME_PAST + t;
-		}
+		TimePast::mutate(|p| *p += t);
 	}
 }
 
 impl UnixTime for Timestamp {
 	fn now() -> core::time::Duration {
-		unsafe {
-			let mut now = SystemTime::now();
-			let dur = Duration::from_secs(TIME_PAST);
-			now.add_assign(dur);
-			now.duration_since(SystemTime::UNIX_EPOCH).unwrap()
+		let mut now = SystemTime::now();
+		let dur = Duration::from_secs(TimePast::get());
+		now.add_assign(dur);
+		now.duration_since(SystemTime::UNIX_EPOCH).unwrap()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct BenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper {
+	/// The owner of the tokens created for the benchmarks
+	fn issuer() -> [u8; 64] {
+		let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+		let public_key = secp256k1::PublicKey::from_secret_key(secp256k1::SECP256K1, &secret_key);
+		public_key.serialize_uncompressed()[1..].try_into().unwrap()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl omni_swap::BenchmarkHelper for BenchmarkHelper {
+	fn mint(token_id: Vec<u8>, to: [u8; 64], amount: u128) {
+		use codec::Encode;
+		use pallet_omniverse_protocol::{Fungible, MINT};
+
+		let pk = Self::issuer();
+		if Assets::tokens_info(&token_id).is_none() {
+			assert_ok!(Balances::transfer(
+				RuntimeOrigin::signed(1),
+				OmniSwap::to_account(&pk).unwrap(),
+				50
+			));
+			assert_ok!(Assets::create_token(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_id.clone(),
+				Some(Vec::new()),
+				None
+			));
 		}
+
+		// The mocked protocol accepts any signature which is not empty
+		let payload = Fungible::new(MINT, to.to_vec(), amount).encode();
+		let mut data = OmniverseTransactionData::new(0, 1, token_id.clone(), pk, payload);
+		data.set_signature([1; 65]);
+		assert_ok!(Assets::send_transaction(
+			RuntimeOrigin::signed(1),
+			token_id.clone(),
+			data.clone()
+		));
+		OmniverseProtocol::set_transaction_data(
+			token_id.clone(),
+			OmniverseTx::new(data, Timestamp::now().as_secs()),
+		);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+	}
+
+	fn deposit_transaction(to: [u8; 64], amount: u128) -> (Vec<u8>, OmniverseTransactionData) {
+		use codec::Encode;
+		use pallet_omniverse_protocol::{Fungible, TRANSFER};
+
+		let pk = Self::issuer();
+		let token_id = b"benchmark".to_vec();
+		Self::mint(token_id.clone(), pk, amount);
+
+		let payload = Fungible::new(TRANSFER, to.to_vec(), amount).encode();
+		let mut data = OmniverseTransactionData::new(1, 1, token_id.clone(), pk, payload);
+		data.set_signature([1; 65]);
+		(token_id, data)
+	}
+
+	fn mpc_signatures(hash: [u8; 32], count: u32) -> Vec<([u8; 64], [u8; 65])> {
+		use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+
+		let message = Message::from_slice(&hash).unwrap();
+		(0..count)
+			.map(|i| {
+				let secret_key = SecretKey::from_slice(&[i as u8 + 2; 32]).unwrap();
+				let public_key = PublicKey::from_secret_key(SECP256K1, &secret_key);
+				let (recovery_id, sig) =
+					SECP256K1.sign_ecdsa_recoverable(&message, &secret_key).serialize_compact();
+				let mut signature = [0; 65];
+				signature[0..64].copy_from_slice(&sig);
+				signature[64] = recovery_id.to_i32() as u8;
+				(public_key.serialize_uncompressed()[1..].try_into().unwrap(), signature)
+			})
+			.collect()
+	}
+
+	fn advance_time(secs: u64) {
+		Timestamp::past(secs);
 	}
 }
@@ -1,13 +1,17 @@
 use crate::mock::*;
 use codec::{Decode, Encode};
 // use frame_support::assert_ok;
-use frame_support::{assert_ok, traits::UnixTime};
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	traits::{GenesisBuild, GetStorageVersion, StorageVersion, UnixTime},
+};
+use pallet_assets::traits::OmniverseTokenFactoryHandler;
 use pallet_omniverse_protocol::{Fungible, OmniverseTransactionData, OmniverseTx, MINT, TRANSFER};
 use secp256k1::rand::rngs::OsRng;
 use secp256k1::rand::RngCore;
 use secp256k1::{ecdsa::RecoverableSignature, Message, PublicKey, Secp256k1, SecretKey};
 use sp_core::Hasher;
-use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::{traits::BlakeTwo256, DispatchError, Permill};
 
 const CHAIN_ID: u32 = 1;
 static SECRET_KEY: [u8; 32] = [
@@ -52,7 +56,7 @@ fn mint(
 	let payload = Fungible::new(MINT, to.to_vec(), amount).encode();
 	let mut tx_data =
 		OmniverseTransactionData::new(nonce, CHAIN_ID, token_id.clone(), pk_from, payload);
-	let h = tx_data.get_raw_hash(false);
+	let h = tx_data.get_raw_hash(token_id, false);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -65,10 +69,10 @@ fn mint(
 		token_id.to_vec(),
 		tx_data.clone()
 	));
-	OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-		tx_data,
-		Timestamp::now().as_secs(),
-	)));
+	OmniverseProtocol::set_transaction_data(
+		token_id.clone(),
+		OmniverseTx::new(tx_data, Timestamp::now().as_secs()),
+	);
 	assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
 }
 
@@ -86,7 +90,7 @@ fn encode_transfer(
 	// let data = TokenOpcode::new(TRANSFER, transfer_data).encode();
 	let mut tx_data =
 		OmniverseTransactionData::new(nonce, CHAIN_ID, token_id.clone(), pk_from, payload);
-	let h = tx_data.get_raw_hash(false);
+	let h = tx_data.get_raw_hash(token_id, false);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -102,7 +106,7 @@ fn deposit(
 	amount: u128,
 	nonce: u128,
 ) {
-	let mpc = OmniSwap::mpc();
+	let mpc = OmniSwap::mpc_members()[0];
 	let mpc_pk = to_public_key(&mpc);
 	let account = get_account_id_from_pk(mpc_pk.serialize().as_slice());
 	if Balances::free_balance(account) < 10 {
@@ -113,7 +117,8 @@ fn deposit(
 	assert_ok!(OmniSwap::deposit(
 		RuntimeOrigin::signed(1),
 		token_id.clone(),
-		transfer_data.clone()
+		transfer_data.clone(),
+		false
 	));
 	assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
 }
@@ -373,12 +378,7 @@ fn it_works_for_swap_y2x() {
 		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
 		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
 
-		let mut token_x_id = [0u8; 32];
-		OsRng.fill_bytes(&mut token_x_id);
-		let mut token_y_id = [0u8; 32];
-		OsRng.fill_bytes(&mut token_y_id);
-		let token_x_id = token_x_id.to_vec();
-		let token_y_id = token_y_id.to_vec();
+		let (token_x_id, token_y_id) = random_token_ids();
 		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
 		let account = get_account_id_from_pk(public_key.serialize().as_slice());
 		fund_account(account);
@@ -445,7 +445,9 @@ fn it_works_for_swap_y2x() {
 				100,
 				1,
 				token_x_id.clone(),
-				token_y_id.clone()
+				token_y_id.clone(),
+				None,
+				None
 			),
 			()
 		);
@@ -458,12 +460,3877 @@ fn it_works_for_swap_y2x() {
 				trading_pair.clone(),
 				pk,
 				swap_amount,
-				1
+				1,
+				None,
+				None,
+				None
 			),
 			()
 		);
-		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((999001, 10010)));
-		assert_eq!(OmniSwap::balance(&pk, &token_x_id).unwrap_or(0), 999);
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((999004, 10010)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id).unwrap_or(0), 996);
 		assert_eq!(OmniSwap::balance(&pk, &token_y_id).unwrap_or(0), 0);
 	});
 }
+
+/// Two random token ids in the order they are stored in a trading pair
+fn random_token_ids() -> (Vec<u8>, Vec<u8>) {
+	let mut token_a_id = [0u8; 32];
+	OsRng.fill_bytes(&mut token_a_id);
+	let mut token_b_id = [0u8; 32];
+	OsRng.fill_bytes(&mut token_b_id);
+	crate::sort_token_ids(&token_a_id.to_vec(), &token_b_id.to_vec())
+}
+
+/// Create token X and token Y owned by `key_pair`, then mint and deposit the given amounts into
+/// the swap pallet for it
+fn init_tokens(
+	secp: &Secp256k1<secp256k1::All>,
+	key_pair: &(SecretKey, PublicKey),
+	token_x_amount: u128,
+	token_y_amount: u128,
+) -> (Vec<u8>, Vec<u8>) {
+	let (token_x_id, token_y_id) = random_token_ids();
+	let pk: [u8; 64] = key_pair.1.serialize_uncompressed()[1..].try_into().expect("");
+	let account = get_account_id_from_pk(key_pair.1.serialize().as_slice());
+	fund_account(account);
+
+	let mut nonce = 0u128;
+	for (token_id, amount) in [(&token_x_id, token_x_amount), (&token_y_id, token_y_amount)] {
+		assert_ok!(Assets::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_id.clone(),
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+		mint(secp, token_id, key_pair, &pk, amount, nonce);
+		nonce += 1;
+		deposit(secp, token_id, key_pair, amount, nonce);
+		assert_ok!(OmniSwap::deposit_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_id.clone(),
+			nonce
+		));
+		nonce += 1;
+	}
+
+	(token_x_id, token_y_id)
+}
+
+#[test]
+fn it_fails_for_add_liquidity_deviating_from_twap() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		System::set_block_number(11);
+		assert_eq!(OmniSwap::twap(&trading_pair), Some(crate::PRICE_PRECISION));
+
+		// Twice the TWAP price
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				2000,
+				1,
+				1,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				Some(100),
+				None
+			),
+			crate::Error::<Test>::PriceDeviationTooHigh
+		);
+
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			Some(100),
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1001000, 1001000)));
+	});
+}
+
+#[test]
+fn it_works_for_initial_liquidity_with_overflowing_product() {
+	assert_eq!(crate::initial_liquidity(1000000, 4000000), 2000000);
+	assert_eq!(crate::initial_liquidity(u128::MAX, u128::MAX), u128::MAX);
+	assert_eq!(crate::initial_liquidity(1 << 100, 1 << 60), 1 << 80);
+}
+
+#[test]
+fn it_works_for_add_liquidity_first_mint_with_overflowing_product() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let amount = 1u128 << 100;
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), amount, amount);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			amount,
+			amount,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(amount));
+		assert_eq!(OmniSwap::liquidity((trading_pair, pk)), Some(amount - 1000));
+	});
+}
+
+#[test]
+fn it_works_for_minimum_liquidity_locked_on_first_mint() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			4000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(63245));
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), pk)), Some(62245));
+		assert_eq!(
+			OmniSwap::liquidity((trading_pair.clone(), crate::BURN_PK)),
+			Some(crate::MINIMUM_LIQUIDITY)
+		);
+		let sum: u128 = crate::Liquidity::<Test>::iter()
+			.filter(|((pair, _), _)| pair == &trading_pair)
+			.map(|(_, liquidity)| liquidity)
+			.sum();
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(sum));
+	});
+}
+
+#[test]
+fn it_fails_for_add_liquidity_with_same_token() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000000,
+				1000000,
+				1,
+				1,
+				token_x_id.clone(),
+				token_x_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::SameToken
+		);
+		assert_eq!(OmniSwap::token_id(&trading_pair), None);
+	});
+}
+
+#[test]
+fn it_works_for_liquidity_amounts_in_events() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		let pair_id = crate::canonical_pair_id(&token_x_id, &token_y_id);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			4000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		let minted = OmniSwap::liquidity((trading_pair.clone(), pk)).unwrap();
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::AddLiquidity(
+			trading_pair.clone(),
+			pair_id.clone(),
+			pk,
+			1000000,
+			4000,
+			minted,
+			OmniSwap::total_liquidity(&trading_pair).unwrap(),
+		)));
+
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			10000,
+			1,
+			1,
+			None
+		));
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), pk)), Some(minted - 10000));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::RemoveLiquidity(
+			trading_pair.clone(),
+			pair_id,
+			pk,
+			158115,
+			632,
+			10000,
+			53245,
+		)));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(53245));
+	});
+}
+
+#[test]
+fn it_works_for_remove_all_liquidity() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_noop!(
+			OmniSwap::remove_all_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1,
+				1,
+				None
+			),
+			crate::Error::<Test>::InvalidValue
+		);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			4000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::remove_all_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1,
+			1,
+			None
+		));
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), pk)), Some(0));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(crate::MINIMUM_LIQUIDITY));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000000 - 984188, 4000 - 3936)));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::RemoveLiquidity(
+			trading_pair,
+			crate::canonical_pair_id(&token_x_id, &token_y_id),
+			pk,
+			984188,
+			3936,
+			62245,
+			crate::MINIMUM_LIQUIDITY,
+		)));
+	});
+}
+
+#[test]
+fn it_works_for_adding_liquidity_after_removing_all() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 4000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		// The minimum liquidity stays locked once the only provider leaves
+		assert_ok!(OmniSwap::remove_all_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1,
+			1,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000, 1000)));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(1000));
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), crate::BURN_PK)), Some(1000));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1999000));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id), Some(3999000));
+
+		// The next mint adds to the locked reserves at their price
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			4000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1001000, 1001000)));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(1001000));
+		assert_eq!(OmniSwap::liquidity((trading_pair, pk)), Some(1000000));
+	});
+}
+
+#[test]
+fn it_works_for_protocol_fee_on() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+		let fee_to = [7u8; 64];
+		assert_noop!(
+			OmniSwap::set_fee_to(RuntimeOrigin::signed(account), Some(fee_to)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(OmniSwap::set_fee_to(RuntimeOrigin::root(), Some(fee_to)));
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			100000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			90661,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000546, 1000000)));
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			1,
+			None
+		));
+
+		// 1/6 of the growth of sqrt(k) from 1000000 to 1000272
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), fee_to)), Some(45));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(999045));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((999546, 999001)));
+		assert_eq!(OmniSwap::k_last(&trading_pair), Some(999273));
+	});
+}
+
+#[test]
+fn it_works_for_protocol_fee_off() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			100000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			90661,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000546, 1000000)));
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			1,
+			None
+		));
+
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(999000));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((999546, 999000)));
+		assert_eq!(OmniSwap::k_last(&trading_pair), None);
+		let fee_minted = crate::Liquidity::<Test>::iter().any(|((pair, owner), _)| {
+			pair == trading_pair && owner != pk && owner != crate::BURN_PK
+		});
+		assert!(!fee_minted);
+	});
+}
+
+#[test]
+fn it_works_for_swap_sell_all() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		// Sell the whole balance of X, then the whole balance of Y
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			crate::SELL_ALL,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::swap_balance(pk, token_x_id.clone()), 0);
+		assert_eq!(OmniSwap::get_reserves(trading_pair.clone()).map(|(x, _)| x), Some(2000000));
+
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			crate::SELL_ALL,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::swap_balance(pk, token_y_id), 0);
+
+		// There is nothing left to sell
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				crate::SELL_ALL,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::InvalidValue
+		);
+	});
+}
+
+#[test]
+fn it_fails_for_swap_below_min_token() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+
+		// Both directions fail with the same error below the minimum
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1000,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientOutputAmount
+		);
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				1000,
+				1000,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientOutputAmount
+		);
+	});
+}
+
+#[test]
+fn it_works_for_preview_remove_liquidity() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::preview_remove_liquidity(trading_pair.clone(), 1000), None);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		// Move the reserves away from the ratio they are seeded with
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			10000,
+			1,
+			None,
+			None,
+			None
+		));
+
+		let liquidity = 400000;
+		let (amount_x, amount_y) =
+			OmniSwap::preview_remove_liquidity(trading_pair.clone(), liquidity).unwrap();
+		let balance_x = OmniSwap::swap_balance(pk, token_x_id.clone());
+		let balance_y = OmniSwap::swap_balance(pk, token_y_id.clone());
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			liquidity,
+			1,
+			1,
+			None
+		));
+		assert_eq!(OmniSwap::swap_balance(pk, token_x_id), balance_x + amount_x);
+		assert_eq!(OmniSwap::swap_balance(pk, token_y_id), balance_y + amount_y);
+
+		crate::TotalLiquidity::<Test>::insert(&trading_pair, 0);
+		assert_eq!(OmniSwap::preview_remove_liquidity(trading_pair, 0), None);
+	});
+}
+
+#[test]
+fn it_works_for_liquidity_share() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let other_secret_key = SecretKey::from_slice(&[2u8; 32]).unwrap();
+		let other_public_key = PublicKey::from_secret_key(&secp, &other_secret_key);
+		let other_pk: [u8; 64] =
+			other_public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let other_account = get_account_id_from_pk(other_public_key.serialize().as_slice());
+		crate::Balance::<Test>::insert(other_pk, &token_x_id, 1000000);
+		crate::Balance::<Test>::insert(other_pk, &token_y_id, 1000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::liquidity_share(trading_pair.clone(), pk), None);
+		for (account, pk) in [(account, pk), (other_account, other_pk)] {
+			assert_ok!(OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000000,
+				1000000,
+				1,
+				1,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				None,
+				None
+			));
+		}
+
+		let (liquidity, total_liquidity, share) =
+			OmniSwap::liquidity_share(trading_pair.clone(), pk).unwrap();
+		assert_eq!((liquidity, total_liquidity), (999000, 2000000));
+		assert_eq!(share, Permill::from_parts(499500));
+		let (other_liquidity, _, other_share) =
+			OmniSwap::liquidity_share(trading_pair.clone(), other_pk).unwrap();
+		assert_eq!(other_liquidity, 1000000);
+		assert_eq!(other_share, Permill::from_percent(50));
+		// The minimum liquidity locked on the first mint owns the rest
+		let (_, _, locked_share) = OmniSwap::liquidity_share(trading_pair, crate::BURN_PK).unwrap();
+		assert_eq!(share + other_share + locked_share, Permill::one());
+	});
+}
+
+#[test]
+fn it_works_for_swap_to_recipient() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		let recipient = [2u8; 64];
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			Some(recipient)
+		));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(999000));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id), Some(1000000));
+		assert_eq!(OmniSwap::balance(&recipient, &token_y_id), Some(996));
+
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			Some(recipient)
+		));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(999000));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id), Some(999000));
+		assert_eq!(OmniSwap::balance(&recipient, &token_x_id), Some(997));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000003, 1000004)));
+	});
+}
+
+#[test]
+fn it_works_for_pause() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		assert_noop!(
+			OmniSwap::set_paused(RuntimeOrigin::signed(account), true),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(OmniSwap::set_paused(RuntimeOrigin::root(), true));
+		assert!(OmniSwap::paused());
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::PausedSet(true)));
+
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::Paused
+		);
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::Paused
+		);
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1000,
+				1,
+				1,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::Paused
+		);
+		let mpc = OmniSwap::mpc_members()[0];
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 100, 4);
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id.clone(), transfer_data, false),
+			crate::Error::<Test>::Paused
+		);
+
+		// Users can still exit
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_ok!(OmniSwap::cancel_withdrawal(
+			RuntimeOrigin::signed(account),
+			pk,
+			token_x_id.clone(),
+			100
+		));
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			1,
+			None
+		));
+
+		assert_ok!(OmniSwap::set_paused(RuntimeOrigin::root(), false));
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair,
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+	});
+}
+
+#[test]
+fn it_works_for_price_cumulative_over_blocks() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			2000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::price_cumulative(&trading_pair), Some((0, 0, 1)));
+
+		// 10 blocks at the price of 2 y per x
+		System::set_block_number(11);
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(
+			OmniSwap::price_cumulative(&trading_pair),
+			Some((2 * crate::PRICE_PRECISION * 10, crate::PRICE_PRECISION / 2 * 10, 11))
+		);
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1001000, 1998008)));
+
+		// 10 more blocks at the price after the swap
+		System::set_block_number(21);
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			1,
+			None
+		));
+		assert_eq!(
+			OmniSwap::price_cumulative(&trading_pair),
+			Some((
+				2 * crate::PRICE_PRECISION * 10 + crate::spot_price(1001000, 1998008) * 10,
+				crate::PRICE_PRECISION / 2 * 10 + crate::spot_price(1998008, 1001000) * 10,
+				21
+			))
+		);
+	});
+}
+
+#[test]
+fn it_works_for_get_or_create_pair_only_once() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		for _ in 0..2 {
+			assert_ok!(OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000000,
+				1000000,
+				1,
+				1,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				None,
+				None
+			));
+		}
+		assert!(!OmniSwap::get_or_create_pair(&trading_pair, &token_x_id, &token_y_id).unwrap());
+		assert!(OmniSwap::get_or_create_pair(&trading_pair, &token_y_id, &token_x_id).unwrap());
+		assert_noop!(
+			OmniSwap::get_or_create_pair(&trading_pair, &token_x_id, &vec![1])
+				.map_err(DispatchError::from),
+			crate::Error::<Test>::MismatchTokenId
+		);
+
+		let pair_created = System::events()
+			.into_iter()
+			.filter(|record| {
+				matches!(record.event, RuntimeEvent::OmniSwap(crate::Event::PairCreated(..)))
+			})
+			.count();
+		assert_eq!(pair_created, 1);
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((2000000, 2000000)));
+	});
+}
+
+#[test]
+fn it_fails_for_withdraw_comfirm_not_from_mpc() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+
+		// The transfer originates from the user rather than the MPC
+		let data = encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(RuntimeOrigin::signed(1), pk, token_x_id, data, vec![]),
+			crate::Error::<Test>::MismatchReceiptor
+		);
+	});
+}
+
+#[test]
+fn it_works_for_deposit_with_auto_confirm() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		let mpc = OmniSwap::mpc_members()[0];
+		let mpc_account = get_account_id_from_pk(to_public_key(&mpc).serialize().as_slice());
+		if Balances::free_balance(mpc_account) < 10 {
+			fund_account(mpc_account);
+		}
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+
+		// The transfer to the MPC has been executed before depositing
+		let nonce = 5u128;
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 100, nonce);
+		assert_ok!(Assets::send_transaction(
+			RuntimeOrigin::signed(1),
+			token_x_id.clone(),
+			transfer_data.clone()
+		));
+		OmniverseProtocol::set_transaction_data(
+			token_x_id.clone(),
+			OmniverseTx::new(transfer_data.clone(), Timestamp::now().as_secs()),
+		);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+
+		assert_ok!(OmniSwap::deposit(
+			RuntimeOrigin::signed(1),
+			token_x_id.clone(),
+			transfer_data,
+			true
+		));
+		assert_eq!(OmniSwap::deposit_record((pk, token_x_id.clone(), nonce)), None);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1100));
+	});
+}
+
+#[test]
+fn it_works_for_reclaim_expired_deposit() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		let mpc = OmniSwap::mpc_members()[0];
+		let mpc_account = get_account_id_from_pk(to_public_key(&mpc).serialize().as_slice());
+		if Balances::free_balance(mpc_account) < 10 {
+			fund_account(mpc_account);
+		}
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+
+		// The first deposit is executed, the second one never is
+		let executed_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 50, 5);
+		assert_ok!(OmniSwap::deposit(
+			RuntimeOrigin::signed(1),
+			token_x_id.clone(),
+			executed_data,
+			false
+		));
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		let stale_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 50, 6);
+		assert_ok!(OmniSwap::deposit(
+			RuntimeOrigin::signed(1),
+			token_x_id.clone(),
+			stale_data.clone(),
+			false
+		));
+
+		assert_noop!(
+			OmniSwap::reclaim_deposit(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 6),
+			crate::Error::<Test>::DepositNotExpired
+		);
+
+		Timestamp::past(DepositExpiry::get());
+		assert_noop!(
+			OmniSwap::reclaim_deposit(RuntimeOrigin::signed(1), pk, token_x_id.clone(), 6),
+			crate::Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			OmniSwap::reclaim_deposit(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 5),
+			crate::Error::<Test>::DepositAlreadyExecuted
+		);
+		// A deposit whose transaction is not recorded, e.g. pruned once executed, is kept
+		assert_noop!(
+			OmniSwap::reclaim_deposit(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 6),
+			crate::Error::<Test>::TxNotExisted
+		);
+		OmniverseProtocol::set_transaction_data(
+			token_x_id.clone(),
+			OmniverseTx::new(stale_data, Timestamp::now().as_secs()),
+		);
+		assert_ok!(OmniSwap::reclaim_deposit(
+			RuntimeOrigin::signed(account),
+			pk,
+			token_x_id.clone(),
+			6
+		));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::DepositExpired(
+			pk,
+			token_x_id.clone(),
+			6,
+		)));
+		assert_eq!(OmniSwap::deposit_record((pk, token_x_id.clone(), 6)), None);
+		assert_eq!(OmniSwap::mpc_pending_deposits(mpc), 1);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1000));
+	});
+}
+
+#[test]
+fn it_works_for_pool_info() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::pool_info(&trading_pair), None);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		System::set_block_number(5);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		assert_eq!(
+			OmniSwap::pool_info(&trading_pair),
+			Some(crate::PoolInfo {
+				reserve_x: 1001000,
+				reserve_y: 1001000,
+				total_liquidity: 1001000,
+				token_x_id,
+				token_y_id,
+				fee_tier: 30,
+				last_updated: 5,
+			})
+		);
+	});
+}
+
+#[test]
+fn it_works_for_quote_exact_in() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::get_reserves(trading_pair.clone()), None);
+		assert_eq!(OmniSwap::quote_exact_in(trading_pair.clone(), true, 1000), None);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1500000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::get_reserves(trading_pair.clone()), Some((1000000, 1500000)));
+		assert_eq!(OmniSwap::quote_exact_in(trading_pair.clone(), true, 1000), Some(1494));
+		assert_eq!(OmniSwap::quote_exact_in(trading_pair.clone(), false, 1000), Some(664));
+
+		// The quote is what a swap actually buys
+		let balance_y = OmniSwap::balance(&pk, &token_y_id).unwrap();
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id).unwrap(), balance_y + 1494);
+	});
+}
+
+#[test]
+fn it_works_for_quote_exact_out() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::quote_exact_out(trading_pair.clone(), true, 1000), None);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1500000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		let amount_in = OmniSwap::quote_exact_out(trading_pair.clone(), true, 1494).unwrap();
+		assert_eq!(Some(amount_in), crate::get_output_price(1494, 1000000, 1500000));
+		assert_eq!(OmniSwap::quote_exact_out(trading_pair.clone(), false, 664), Some(1000));
+
+		// Selling the quoted amount buys at least the amount asked for
+		assert!(OmniSwap::quote_exact_in(trading_pair.clone(), true, amount_in).unwrap() >= 1494);
+
+		// The reserve can not be drained
+		assert_eq!(OmniSwap::quote_exact_out(trading_pair.clone(), true, 1500000), None);
+		assert_eq!(OmniSwap::quote_exact_out(trading_pair.clone(), true, 1500001), None);
+		assert_eq!(OmniSwap::quote_exact_out(trading_pair, false, 1000000), None);
+	});
+}
+
+#[test]
+fn it_works_for_quote_with_slippage() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::quote_with_slippage(trading_pair.clone(), true, 1000, 50), None);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1500000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		let expected = crate::get_input_price(1000, 1000000, 1500000).unwrap() * 9950 / 10000;
+		assert_eq!(expected, 1486);
+		assert_eq!(
+			OmniSwap::quote_with_slippage(trading_pair.clone(), true, 1000, 50),
+			Some(expected)
+		);
+		assert_eq!(OmniSwap::quote_with_slippage(trading_pair.clone(), true, 1000, 0), Some(1494));
+		assert_eq!(OmniSwap::quote_with_slippage(trading_pair.clone(), true, 1000, 10001), None);
+
+		// The minimum is accepted by the swap it was quoted for
+		let min_token =
+			OmniSwap::quote_with_slippage(trading_pair.clone(), true, 1000, 50).unwrap();
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair,
+			pk,
+			1000,
+			min_token,
+			None,
+			None,
+			None
+		));
+	});
+}
+
+#[test]
+fn it_works_for_token_and_swap_balances() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (token_x_id, token_y_id) = init_tokens(&secp, &(secret_key, public_key), 1000, 500);
+		let mpc = OmniSwap::mpc_members()[0];
+
+		// The deposits are held by the MPC on the assets side and credited to `pk` by the swap
+		assert_eq!(Assets::token_balance(token_x_id.clone(), pk), 0);
+		assert_eq!(Assets::token_balance(token_x_id.clone(), mpc), 1000);
+		assert_eq!(Assets::token_balance(token_y_id.clone(), mpc), 500);
+		assert_eq!(OmniSwap::swap_balance(pk, token_x_id.clone()), 1000);
+		assert_eq!(OmniSwap::swap_balance(pk, token_y_id.clone()), 500);
+		assert_eq!(OmniSwap::swap_balance(mpc, token_x_id.clone()), 0);
+
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+		assert_eq!(Assets::token_balance(token_x_id.clone(), pk), 100);
+		assert_eq!(OmniSwap::swap_balance(pk, token_x_id), 1000);
+		assert_eq!(Assets::token_balance(vec![0], pk), 0);
+	});
+}
+
+#[test]
+fn it_works_for_get_amounts_out() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_a_id, token_b_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+		let (token_c_id, token_d_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		// A-B, C-B and D-A
+		for (trading_pair, amount_x, amount_y, x_id, y_id) in [
+			(vec![1], 1000000, 1500000, &token_a_id, &token_b_id),
+			(vec![2], 1000000, 400000, &token_c_id, &token_b_id),
+			(vec![3], 100000, 100000, &token_d_id, &token_a_id),
+		] {
+			assert_ok!(OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				amount_x,
+				amount_y,
+				1,
+				1,
+				x_id.clone(),
+				y_id.clone(),
+				None,
+				None
+			));
+		}
+
+		// A -> B -> C
+		assert_eq!(
+			OmniSwap::get_amounts_out(1000, vec![vec![1], vec![2]]).ok(),
+			Some(vec![1000, 1494, 3709])
+		);
+		// C -> B -> A
+		assert_eq!(
+			OmniSwap::get_amounts_out(1000, vec![vec![2], vec![1]]).ok(),
+			Some(vec![1000, 398, 264])
+		);
+		// A single hop sells token x
+		assert_eq!(OmniSwap::get_amounts_out(1000, vec![vec![1]]).ok(), Some(vec![1000, 1494]));
+
+		assert_err!(
+			OmniSwap::get_amounts_out(1000, vec![]).map_err(DispatchError::from),
+			crate::Error::<Test>::InvalidPath
+		);
+		assert_err!(
+			OmniSwap::get_amounts_out(1000, vec![vec![1], vec![4]]).map_err(DispatchError::from),
+			crate::Error::<Test>::TradingPairNotExist
+		);
+		// C-B and D-A do not share a token
+		assert_err!(
+			OmniSwap::get_amounts_out(1000, vec![vec![2], vec![3]]).map_err(DispatchError::from),
+			crate::Error::<Test>::InvalidPath
+		);
+	});
+}
+
+#[test]
+fn it_works_for_swap_exact_tokens_for_tokens() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_a_id, token_b_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+		let (token_c_id, _) = init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		// A-B and C-B
+		for (trading_pair, amount_x, amount_y, x_id, y_id) in [
+			(vec![1], 1000000, 1500000, &token_a_id, &token_b_id),
+			(vec![2], 1000000, 400000, &token_c_id, &token_b_id),
+		] {
+			assert_ok!(OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				amount_x,
+				amount_y,
+				1,
+				1,
+				x_id.clone(),
+				y_id.clone(),
+				None,
+				None
+			));
+		}
+		let balance_a = OmniSwap::balance(&pk, &token_a_id).unwrap();
+		let balance_b = OmniSwap::balance(&pk, &token_b_id).unwrap();
+		let balance_c = OmniSwap::balance(&pk, &token_c_id).unwrap();
+
+		// A hop buying nothing fails
+		assert_noop!(
+			OmniSwap::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(account),
+				pk,
+				1,
+				1,
+				vec![vec![2], vec![1]],
+				None
+			),
+			crate::Error::<Test>::InsufficientOutputAmount
+		);
+
+		// The output is below the minimum
+		assert_noop!(
+			OmniSwap::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(account),
+				pk,
+				1000,
+				3710,
+				vec![vec![1], vec![2]],
+				None
+			),
+			crate::Error::<Test>::InsufficientOutputAmount
+		);
+
+		// A -> B -> C
+		assert_ok!(OmniSwap::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(account),
+			pk,
+			1000,
+			3709,
+			vec![vec![1], vec![2]],
+			None
+		));
+		assert_eq!(OmniSwap::balance(&pk, &token_a_id), Some(balance_a - 1000));
+		assert_eq!(OmniSwap::balance(&pk, &token_b_id), Some(balance_b));
+		assert_eq!(OmniSwap::balance(&pk, &token_c_id), Some(balance_c + 3709));
+		assert_eq!(OmniSwap::trading_pairs(&vec![1]), Some((1001000, 1498506)));
+		assert_eq!(OmniSwap::trading_pairs(&vec![2]), Some((996291, 401494)));
+	});
+}
+
+#[test]
+fn it_works_for_swap_fee_growing_reserves() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id.clone(),
+			None,
+			None
+		));
+		let (reserve_x, reserve_y) = OmniSwap::trading_pairs(&trading_pair).unwrap();
+		let k = reserve_x * reserve_y;
+
+		// Round trip
+		let balance_y = OmniSwap::balance(&pk, &token_y_id).unwrap();
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			10000,
+			1,
+			None,
+			None,
+			None
+		));
+		let bought = OmniSwap::balance(&pk, &token_y_id).unwrap() - balance_y;
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			bought,
+			1,
+			None,
+			None,
+			None
+		));
+
+		let (reserve_x, reserve_y) = OmniSwap::trading_pairs(&trading_pair).unwrap();
+		assert!(reserve_x * reserve_y > k);
+	});
+}
+
+/// Build the transfer the MPC sends to settle a withdrawal, the mock protocol only rejects empty
+/// signatures
+fn encode_withdrawal(
+	token_id: &Vec<u8>,
+	to: &[u8; 64],
+	amount: u128,
+	nonce: u128,
+) -> OmniverseTransactionData {
+	let payload = Fungible::new(TRANSFER, to.to_vec(), amount).encode();
+	let mut tx_data = OmniverseTransactionData::new(
+		nonce,
+		CHAIN_ID,
+		token_id.clone(),
+		OmniSwap::mpc_members()[0],
+		payload,
+	);
+	tx_data.set_signature([1; 65]);
+	tx_data
+}
+
+#[test]
+fn it_works_for_withdraw_comfirm_event() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			encode_withdrawal(&token_x_id, &pk, 60, 0),
+			vec![]
+		));
+		System::assert_has_event(RuntimeEvent::OmniSwap(crate::Event::WithdrawalConfirmed(
+			pk,
+			token_x_id.clone(),
+			60,
+		)));
+		System::assert_last_event(RuntimeEvent::OmniSwap(
+			crate::Event::PartialWithdrawalConfirmed(pk, token_x_id, 60, 40),
+		));
+	});
+}
+
+#[test]
+fn it_works_for_withdraw_comfirm_in_tranches() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+
+		// A tranche larger than what is pending is rejected
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				encode_withdrawal(&token_x_id, &pk, 101, 0),
+				vec![]
+			),
+			crate::Error::<Test>::WithdrawAmountMismatch
+		);
+
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			encode_withdrawal(&token_x_id, &pk, 30, 0),
+			vec![]
+		));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), Some(70));
+		System::assert_last_event(RuntimeEvent::OmniSwap(
+			crate::Event::PartialWithdrawalConfirmed(pk, token_x_id.clone(), 30, 70),
+		));
+
+		// The last tranche settles the withdrawal without a partial confirmation
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			encode_withdrawal(&token_x_id, &pk, 70, 1),
+			vec![]
+		));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), None);
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::WithdrawalConfirmed(
+			pk,
+			token_x_id.clone(),
+			70,
+		)));
+
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(900));
+		assert_eq!(Assets::tokens(&token_x_id, &pk), 100);
+	});
+}
+
+#[test]
+fn it_fails_for_withdraw_comfirm_twice() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		let data = encode_withdrawal(&token_x_id, &pk, 100, 0);
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			data.clone(),
+			vec![]
+		));
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), None);
+
+		// The withdrawal has been settled, a second confirmation must not pay it again
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				data,
+				vec![]
+			),
+			crate::Error::<Test>::WithdrawalNotExist
+		);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(900));
+		assert_eq!(Assets::tokens(&token_x_id, &pk), 100);
+	});
+}
+
+#[test]
+fn it_works_for_mpc_threshold() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		let members: Vec<(SecretKey, [u8; 64])> = [[3u8; 32], [4u8; 32], [5u8; 32]]
+			.iter()
+			.map(|key| {
+				let secret_key = SecretKey::from_slice(key).unwrap();
+				let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+				(secret_key, public_key.serialize_uncompressed()[1..].try_into().expect(""))
+			})
+			.collect();
+		let member_pks: Vec<[u8; 64]> = members.iter().map(|(_, pk)| *pk).collect();
+		crate::MpcMembers::<Test>::put(frame_support::bounded_vec![member_pks[0]]);
+
+		assert_noop!(
+			OmniSwap::set_mpc_members(RuntimeOrigin::root(), member_pks.clone(), 4),
+			crate::Error::<Test>::InvalidValue
+		);
+		assert_ok!(OmniSwap::set_mpc_members(RuntimeOrigin::root(), member_pks.clone(), 2));
+		assert_eq!(OmniSwap::mpc_members(), member_pks);
+		assert_eq!(OmniSwap::mpc_threshold(), 2);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		let payload = Fungible::new(TRANSFER, pk.to_vec(), 100).encode();
+		let mut data =
+			OmniverseTransactionData::new(0, CHAIN_ID, token_x_id.clone(), member_pks[0], payload);
+		data.set_signature([1; 65]);
+		let message =
+			Message::from_slice(data.get_raw_hash(&token_x_id, false).as_slice()).unwrap();
+		let sign = |secret_key: &SecretKey| {
+			get_sig_slice(&secp.sign_ecdsa_recoverable(&message, secret_key))
+		};
+
+		// The sender alone does not reach the threshold, signing again does not count twice
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				data.clone(),
+				vec![]
+			),
+			crate::Error::<Test>::InsufficientMpcSignatures
+		);
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				data.clone(),
+				vec![sign(&members[0].0)]
+			),
+			crate::Error::<Test>::InsufficientMpcSignatures
+		);
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				data.clone(),
+				vec![sign(&secret_key)]
+			),
+			crate::Error::<Test>::InvalidMpcSignature
+		);
+
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			data,
+			vec![sign(&members[2].0)]
+		));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id)), None);
+	});
+}
+
+#[test]
+fn it_fails_for_lone_mpc_member_rotating_the_set() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let members: Vec<([u8; 64], u64)> = [[3u8; 32], [4u8; 32], [5u8; 32]]
+			.iter()
+			.map(|key| {
+				let public_key =
+					PublicKey::from_secret_key(&secp, &SecretKey::from_slice(key).unwrap());
+				(
+					public_key.serialize_uncompressed()[1..].try_into().expect(""),
+					get_account_id_from_pk(public_key.serialize().as_slice()),
+				)
+			})
+			.collect();
+		let member_pks: Vec<[u8; 64]> = members[..2].iter().map(|(pk, _)| *pk).collect();
+		assert_ok!(OmniSwap::set_mpc_members(RuntimeOrigin::root(), member_pks, 2));
+
+		// A single member can neither replace the members and the threshold nor its own key
+		let (member_pk, member_account) = members[0];
+		let (new_pk, _) = members[2];
+		assert_noop!(
+			OmniSwap::set_mpc_members(RuntimeOrigin::signed(member_account), vec![member_pk], 1),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			OmniSwap::propose_mpc(RuntimeOrigin::signed(member_account), member_pk, new_pk),
+			DispatchError::BadOrigin
+		);
+		assert_eq!(OmniSwap::pending_mpc(), None);
+	});
+}
+
+#[test]
+fn it_works_for_mpc_rotation() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		let keys: Vec<([u8; 64], u64)> = [[3u8; 32], [4u8; 32], [5u8; 32]]
+			.iter()
+			.map(|key| {
+				let public_key =
+					PublicKey::from_secret_key(&secp, &SecretKey::from_slice(key).unwrap());
+				(
+					public_key.serialize_uncompressed()[1..].try_into().expect(""),
+					get_account_id_from_pk(public_key.serialize().as_slice()),
+				)
+			})
+			.collect();
+		let (old_mpc, old_account) = keys[0];
+		let (new_mpc, new_account) = keys[1];
+		let (_, wrong_account) = keys[2];
+		crate::MpcMembers::<Test>::put(frame_support::bounded_vec![old_mpc]);
+
+		// A deposit to the old key is still pending during the rotation
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+		deposit(&secp, &token_x_id, &(secret_key, public_key), 100, 5);
+		assert_eq!(OmniSwap::mpc_pending_deposits(old_mpc), 1);
+
+		assert_noop!(
+			OmniSwap::propose_mpc(RuntimeOrigin::signed(old_account), old_mpc, new_mpc),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			OmniSwap::propose_mpc(RuntimeOrigin::root(), new_mpc, old_mpc),
+			crate::Error::<Test>::InvalidValue
+		);
+		assert_noop!(
+			OmniSwap::accept_mpc(RuntimeOrigin::signed(new_account)),
+			crate::Error::<Test>::NoPendingMpc
+		);
+		assert_ok!(OmniSwap::propose_mpc(RuntimeOrigin::root(), old_mpc, new_mpc));
+		assert_eq!(OmniSwap::pending_mpc(), Some((old_mpc, new_mpc)));
+		assert_eq!(OmniSwap::mpc_members(), vec![old_mpc]);
+
+		assert_noop!(
+			OmniSwap::accept_mpc(RuntimeOrigin::signed(wrong_account)),
+			crate::Error::<Test>::NoPermission
+		);
+		assert_ok!(OmniSwap::accept_mpc(RuntimeOrigin::signed(new_account)));
+		assert_eq!(OmniSwap::pending_mpc(), None);
+		assert_eq!(OmniSwap::mpc_members(), vec![new_mpc]);
+		assert_eq!(OmniSwap::retired_mpc_members(), vec![old_mpc]);
+
+		// The old key is dropped once its pending deposit is confirmed
+		assert_ok!(OmniSwap::deposit_comfirm(RuntimeOrigin::signed(1), pk, token_x_id.clone(), 5));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1100));
+		assert_eq!(OmniSwap::mpc_pending_deposits(old_mpc), 0);
+		assert!(OmniSwap::retired_mpc_members().is_empty());
+	});
+}
+
+#[test]
+fn it_fails_for_too_many_mpc_members() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let keys: Vec<([u8; 64], u64)> = (3u8..3 + MaxMpcMembers::get() as u8 + 2)
+			.map(|key| {
+				let public_key =
+					PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[key; 32]).unwrap());
+				(
+					public_key.serialize_uncompressed()[1..].try_into().expect(""),
+					get_account_id_from_pk(public_key.serialize().as_slice()),
+				)
+			})
+			.collect();
+		let key_pks: Vec<[u8; 64]> = keys.iter().map(|(pk, _)| *pk).collect();
+
+		let max = MaxMpcMembers::get() as usize;
+		assert_noop!(
+			OmniSwap::set_mpc_members(RuntimeOrigin::root(), key_pks[..max + 1].to_vec(), 1),
+			crate::Error::<Test>::TooManyMpcMembers
+		);
+
+		// The old key can not be retired once the retired keys are full
+		let (old_mpc, _) = keys[0];
+		let (new_mpc, new_account) = keys[1];
+		crate::MpcMembers::<Test>::put(frame_support::bounded_vec![old_mpc]);
+		crate::RetiredMpcMembers::<Test>::put(
+			frame_support::BoundedVec::try_from(key_pks[2..max + 2].to_vec()).unwrap(),
+		);
+		crate::MpcPendingDeposits::<Test>::insert(old_mpc, 1);
+		assert_ok!(OmniSwap::propose_mpc(RuntimeOrigin::root(), old_mpc, new_mpc));
+		assert_noop!(
+			OmniSwap::accept_mpc(RuntimeOrigin::signed(new_account)),
+			crate::Error::<Test>::TooManyMpcMembers
+		);
+	});
+}
+
+#[test]
+fn it_works_for_canonical_pair_id_in_events() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		// The same tokens in both orientations
+		for (token_a_id, token_b_id) in [(&token_x_id, &token_y_id), (&token_y_id, &token_x_id)] {
+			assert_ok!(OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				vec![1],
+				pk,
+				1000000,
+				1000000,
+				1,
+				1,
+				token_a_id.clone(),
+				token_b_id.clone(),
+				None,
+				None
+			));
+		}
+
+		let pair_ids: Vec<(Vec<u8>, Vec<u8>)> = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::OmniSwap(crate::Event::AddLiquidity(trading_pair, pair_id, ..)) => {
+					Some((trading_pair, pair_id))
+				},
+				_ => None,
+			})
+			.collect();
+		let pair_id = crate::canonical_pair_id(&token_x_id, &token_y_id);
+		assert_eq!(pair_ids, vec![(vec![1], pair_id.clone()), (vec![1], pair_id)]);
+	});
+}
+
+#[test]
+fn it_works_for_canonical_token_order() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 4000000);
+
+		// Created with the tokens in the reverse order
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			2000000,
+			1000000,
+			1,
+			1,
+			token_y_id.clone(),
+			token_x_id.clone(),
+			None,
+			None
+		));
+		assert_eq!(
+			OmniSwap::token_id(&trading_pair),
+			Some((token_x_id.clone(), token_y_id.clone()))
+		);
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000000, 2000000)));
+
+		// The canonical order resolves to the same pool
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			2000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((2000000, 4000000)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(0));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id), Some(0));
+		let pair_id = crate::canonical_pair_id(&token_x_id, &token_y_id);
+		assert_eq!(OmniSwap::pair_of(&pair_id), Some(trading_pair));
+
+		// Another trading pair of the same tokens is rejected
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				vec![2],
+				pk,
+				1000000,
+				1000000,
+				1,
+				1,
+				token_y_id.clone(),
+				token_x_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::PairAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn it_works_for_pricing_math_near_u128_max() {
+	let half = u128::MAX / 2;
+	assert_eq!(
+		crate::get_input_price(half, half, half),
+		Some(84942794146263306978714192190654208016)
+	);
+	assert_eq!(crate::quote(half, half, half), Some(half));
+	assert_eq!(crate::quote(half, 1, 4), None);
+	assert_eq!(crate::get_output_price(half, half, half), None);
+	assert_eq!(crate::get_output_price(3, u128::MAX, 4), None);
+}
+
+#[test]
+fn it_works_for_burned_amounts_near_u128_max() {
+	let half = u128::MAX / 2;
+	assert_eq!(
+		crate::burned_amounts(half, half, 1 << 100, u128::MAX),
+		Some((half / 2, (1 << 99) - 1))
+	);
+	assert_eq!(crate::burned_amounts(1, 2, 3, 0), None);
+}
+
+#[test]
+fn it_works_for_quote_scaled() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		assert_eq!(OmniverseToken::decimals(&token_x_id), None);
+
+		for (token_id, decimals) in [(&token_x_id, 6), (&token_y_id, 18)] {
+			assert_ok!(Assets::set_metadata(
+				RuntimeOrigin::signed(account),
+				Assets::token_id_2_asset_id(token_id).unwrap(),
+				token_id.clone(),
+				token_id.clone(),
+				decimals
+			));
+		}
+		let decimals_x = OmniverseToken::decimals(&token_x_id).unwrap();
+		let decimals_y = OmniverseToken::decimals(&token_y_id).unwrap();
+		assert_eq!((decimals_x, decimals_y), (6, 18));
+
+		// One X is worth two Y
+		assert_eq!(crate::quote(1_000_000, 1, 2), Some(2_000_000));
+		assert_eq!(
+			crate::quote_scaled(1_000_000, 1, 2, decimals_x, decimals_y),
+			Some(2_000_000_000_000_000_000)
+		);
+		assert_eq!(
+			crate::quote_scaled(2_000_000_000_000_000_000, 2, 1, decimals_y, decimals_x),
+			Some(1_000_000)
+		);
+		assert_eq!(crate::quote_scaled(1, 1, 1, 0, 255), None);
+	});
+}
+
+#[test]
+fn it_fails_for_add_liquidity_below_minimums() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		// The first liquidity must mint more than the locked minimum
+		let trading_pair = vec![1];
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1000,
+				1,
+				1,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidityMinted
+		);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1500000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		// The optimal amount of y is 1500, below its minimum
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				2000,
+				1,
+				1600,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidityMinted
+		);
+		// The optimal amount of x is 1000, below its minimum
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				2000,
+				1500,
+				1200,
+				1,
+				token_x_id.clone(),
+				token_y_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidityMinted
+		);
+
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			2000,
+			1000,
+			1500,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1001000, 1501500)));
+	});
+}
+
+#[test]
+fn it_fails_for_add_liquidity_with_overflowing_quote() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000, u128::MAX / 2);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1,
+			u128::MAX / 4,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				10,
+				10,
+				1,
+				1,
+				token_x_id,
+				token_y_id,
+				None,
+				None
+			),
+			crate::Error::<Test>::ArithmeticOverflow
+		);
+	});
+}
+
+#[test]
+fn it_works_for_exact_output_swaps() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		assert_noop!(
+			OmniSwap::swap_x2y_exact_out(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1004,
+				None,
+				None
+			),
+			crate::Error::<Test>::ExceedMaxInput
+		);
+		assert_ok!(OmniSwap::swap_x2y_exact_out(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1005,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1001005, 999000)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(998995));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id), Some(1001000));
+
+		assert_ok!(OmniSwap::swap_y2x_exact_out(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1003,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000005, 1000003)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(999995));
+		assert_eq!(OmniSwap::balance(&pk, &token_y_id), Some(999997));
+	});
+}
+
+#[test]
+fn it_fails_for_calls_after_deadline() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			Some(1)
+		));
+
+		System::set_block_number(6);
+		let deadline = Some(5);
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				deadline,
+				None,
+				None
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				deadline,
+				None,
+				None
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1000,
+				1,
+				1,
+				token_x_id,
+				token_y_id,
+				None,
+				deadline
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+		assert_noop!(
+			OmniSwap::remove_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				1,
+				deadline
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+		assert_noop!(
+			OmniSwap::remove_all_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1,
+				1,
+				deadline
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+		assert_noop!(
+			OmniSwap::swap_x2y_exact_out(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				2000,
+				deadline,
+				None
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+		assert_noop!(
+			OmniSwap::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(account),
+				pk,
+				1000,
+				1,
+				vec![trading_pair.clone()],
+				deadline
+			),
+			crate::Error::<Test>::DeadlinePassed
+		);
+
+		// The deadline is inclusive
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair,
+			pk,
+			1000,
+			1,
+			Some(6),
+			None,
+			None
+		));
+	});
+}
+
+#[test]
+fn it_fails_for_swap_with_empty_reserve() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::get_or_create_pair(&trading_pair, &token_x_id, &token_y_id));
+		crate::TradingPairs::<Test>::insert(&trading_pair, (0, 1000));
+
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				100,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidity
+		);
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				100,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidity
+		);
+		assert_noop!(
+			OmniSwap::swap_x2y_exact_out(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				100,
+				1000,
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidity
+		);
+		assert_eq!(crate::get_input_price(100, 0, 1000), None);
+	});
+}
+
+#[test]
+fn it_fails_for_swap_with_excessive_price_impact() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) = init_tokens(&secp, &(secret_key, public_key), 20000, 20000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			10000,
+			10000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+
+		// Selling half of the reserve moves the price by about 33%
+		assert_eq!(crate::price_impact_bps(5000, 3326, 10000, 10000), Some(3348));
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				5000,
+				1,
+				None,
+				Some(500),
+				None
+			),
+			crate::Error::<Test>::PriceImpactTooHigh
+		);
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				5000,
+				1,
+				None,
+				Some(500),
+				None
+			),
+			crate::Error::<Test>::PriceImpactTooHigh
+		);
+
+		// A small trade stays within the limit
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			100,
+			1,
+			None,
+			Some(500),
+			None
+		));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((10100, 9902)));
+	});
+}
+
+#[test]
+fn it_fails_for_exact_output_swap_after_reserve_manipulation() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) = init_tokens(&secp, &(secret_key, public_key), 20000, 20000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			10000,
+			10000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id,
+			None,
+			None
+		));
+
+		// Buying 1000 Y moves the price by about 10% at the current reserves
+		let (reserve_x, reserve_y) = OmniSwap::trading_pairs(&trading_pair).unwrap();
+		let tokens_sold = crate::get_output_price(1000, reserve_x, reserve_y).unwrap();
+		assert_eq!(crate::price_impact_bps(tokens_sold, 1000, reserve_x, reserve_y), Some(1031));
+
+		// Another trader drains the Y reserve ahead of the swap
+		let (_, trader_public_key) = secp.generate_keypair(&mut OsRng);
+		let trader_pk: [u8; 64] =
+			trader_public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let trader = get_account_id_from_pk(trader_public_key.serialize().as_slice());
+		assert_ok!(OmniSwap::transfer_balance(
+			RuntimeOrigin::signed(account),
+			pk,
+			trader_pk,
+			token_x_id,
+			5000
+		));
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(trader),
+			trading_pair.clone(),
+			trader_pk,
+			5000,
+			1,
+			None,
+			None,
+			None
+		));
+
+		// The same amount now moves the price beyond the limit
+		assert_noop!(
+			OmniSwap::swap_x2y_exact_out(
+				RuntimeOrigin::signed(account),
+				trading_pair,
+				pk,
+				1000,
+				5000,
+				None,
+				Some(1200)
+			),
+			crate::Error::<Test>::PriceImpactTooHigh
+		);
+	});
+}
+
+#[test]
+fn it_works_for_k_invariant() {
+	let (reserve_x, reserve_y) = (1000000, 1500000);
+	let tokens_bought = crate::get_input_price(3000, reserve_x, reserve_y).unwrap();
+	assert_eq!(tokens_bought, 4473);
+	assert!(crate::k_invariant_holds(3000, tokens_bought, reserve_x, reserve_y));
+	// The price without the fee decreases the product once the fee is left out
+	assert!(!crate::k_invariant_holds(
+		3000,
+		3000 * reserve_y / (reserve_x + 3000),
+		reserve_x,
+		reserve_y
+	));
+	assert!(!crate::k_invariant_holds(0, 1, reserve_x, reserve_y));
+	assert!(!crate::k_invariant_holds(3000, reserve_y + 1, reserve_x, reserve_y));
+	assert!(crate::k_invariant_holds(u128::MAX, 1, u128::MAX, u128::MAX));
+}
+
+#[test]
+fn it_fails_for_balance_not_backed_by_mpc() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+		let mpc = OmniSwap::mpc_members()[0];
+		assert_eq!(OmniverseToken::balance_of(&token_x_id, &mpc), 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1500000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+
+		// The pool and the balances together are owed what is deposited
+		assert_eq!(OmniSwap::total_deposited(&token_x_id), 2000000);
+
+		// The custody drifts below the total owed, though it still covers the balance of `pk`
+		pallet_assets::Tokens::<Test>::insert(&token_x_id, mpc, 1999999);
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::BalanceNotBacked
+		);
+		assert_noop!(
+			OmniSwap::swap_y2x(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::BalanceNotBacked
+		);
+		assert_noop!(
+			OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 1000),
+			crate::Error::<Test>::BalanceNotBacked
+		);
+		assert_noop!(
+			OmniSwap::transfer_balance(
+				RuntimeOrigin::signed(account),
+				pk,
+				[1u8; 64],
+				token_x_id.clone(),
+				1000
+			),
+			crate::Error::<Test>::BalanceNotBacked
+		);
+
+		// Once the custody covers the total again the balance can be traded
+		pallet_assets::Tokens::<Test>::insert(&token_x_id, mpc, 2000000);
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair,
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+	});
+}
+
+#[test]
+fn it_works_for_accumulated_withdrawals() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 50));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), Some(150));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(850));
+
+		// More than pending
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				encode_withdrawal(&token_x_id, &pk, 200, 0),
+				vec![]
+			),
+			crate::Error::<Test>::WithdrawAmountMismatch
+		);
+
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			encode_withdrawal(&token_x_id, &pk, 100, 0),
+			vec![]
+		));
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), Some(50));
+
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			encode_withdrawal(&token_x_id, &pk, 50, 1),
+			vec![]
+		));
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), None);
+		assert_eq!(Assets::tokens(&token_x_id, &pk), 150);
+	});
+}
+
+#[test]
+fn it_works_for_cancel_withdrawal() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(900));
+		assert_noop!(
+			OmniSwap::cancel_withdrawal(
+				RuntimeOrigin::signed(account),
+				pk,
+				token_x_id.clone(),
+				101
+			),
+			crate::Error::<Test>::WithdrawAmountMismatch
+		);
+
+		assert_ok!(OmniSwap::cancel_withdrawal(
+			RuntimeOrigin::signed(account),
+			pk,
+			token_x_id.clone(),
+			100
+		));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), None);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1000));
+	});
+}
+
+#[test]
+fn it_fails_for_withdraw_comfirm_after_cancel() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_ok!(OmniSwap::cancel_withdrawal(
+			RuntimeOrigin::signed(account),
+			pk,
+			token_x_id.clone(),
+			100
+		));
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				encode_withdrawal(&token_x_id, &pk, 100, 0),
+				vec![]
+			),
+			crate::Error::<Test>::WithdrawalNotExist
+		);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1000));
+		assert_eq!(Assets::tokens(&token_x_id, &pk), 0);
+	});
+}
+
+#[test]
+fn it_fails_for_cancel_withdrawal_after_comfirm() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_ok!(OmniSwap::withdraw_comfirm(
+			RuntimeOrigin::signed(1),
+			pk,
+			token_x_id.clone(),
+			encode_withdrawal(&token_x_id, &pk, 100, 0),
+			vec![]
+		));
+		assert_noop!(
+			OmniSwap::cancel_withdrawal(
+				RuntimeOrigin::signed(account),
+				pk,
+				token_x_id.clone(),
+				100
+			),
+			crate::Error::<Test>::WithdrawalNotExist
+		);
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(900));
+		assert_eq!(Assets::tokens(&token_x_id, &pk), 100);
+	});
+}
+
+#[test]
+fn it_fails_for_deposit_not_transfer_or_zero_amount() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		let mpc = OmniSwap::mpc_members()[0];
+
+		let payload = Fungible::new(MINT, mpc.to_vec(), 100).encode();
+		let mut mint_data =
+			OmniverseTransactionData::new(4, CHAIN_ID, token_x_id.clone(), pk, payload);
+		mint_data.set_signature([1; 65]);
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id.clone(), mint_data, false),
+			crate::Error::<Test>::InvalidValue
+		);
+
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 0, 4);
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id, transfer_data, false),
+			crate::Error::<Test>::InvalidValue
+		);
+	});
+}
+
+#[test]
+fn it_fails_for_deposit_with_too_many_pending_deposits() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+		let mpc = OmniSwap::mpc_members()[0];
+		crate::MpcPendingDeposits::<Test>::insert(mpc, u32::MAX);
+
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 100, 5);
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id, transfer_data, false),
+			crate::Error::<Test>::ArithmeticOverflow
+		);
+	});
+}
+
+#[test]
+fn it_fails_for_deposit_comfirm_before_execution() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+
+		let transfer_data = encode_transfer(
+			&secp,
+			&token_x_id,
+			&(secret_key, public_key),
+			&OmniSwap::mpc_members()[0],
+			100,
+			5,
+		);
+		assert_ok!(OmniSwap::deposit(
+			RuntimeOrigin::signed(1),
+			token_x_id.clone(),
+			transfer_data,
+			false
+		));
+		assert_noop!(
+			OmniSwap::deposit_comfirm(RuntimeOrigin::signed(1), pk, token_x_id.clone(), 5),
+			crate::Error::<Test>::DepositNotExecuted
+		);
+
+		assert_ok!(Assets::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_ok!(OmniSwap::deposit_comfirm(RuntimeOrigin::signed(1), pk, token_x_id.clone(), 5));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1100));
+	});
+}
+
+#[test]
+fn it_fails_for_to_account_with_invalid_key() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(
+			OmniSwap::to_account(&pk).ok(),
+			Some(get_account_id_from_pk(public_key.serialize().as_slice()))
+		);
+
+		// A key which is not on the curve is reported instead of panicking
+		assert_err!(
+			OmniSwap::to_account(&[0; 64]).map_err(DispatchError::from),
+			crate::Error::<Test>::SerializePublicKeyFailed
+		);
+	});
+}
+
+#[test]
+fn it_works_for_genesis_trading_pairs() {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let provider = [1u8; 64];
+	let trading_pair = b"genesis_pair".to_vec();
+	let config = crate::GenesisConfig {
+		trading_pairs: vec![(
+			trading_pair.clone(),
+			vec![2],
+			vec![1],
+			1_000,
+			4_000,
+			provider,
+			2_000,
+		)],
+		mpc_members: vec![[2; 64], [3; 64]],
+		mpc_threshold: 2,
+	};
+	GenesisBuild::<Test>::assimilate_storage(&config, &mut storage).unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| {
+		// The tokens are stored in canonical order along with their reserves
+		assert_eq!(OmniSwap::token_id(&trading_pair), Some((vec![1], vec![2])));
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((4_000, 1_000)));
+		assert_eq!(OmniSwap::total_liquidity(&trading_pair), Some(2_000));
+		// The minimum liquidity is locked out of the genesis liquidity
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), provider)), Some(1_000));
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), crate::BURN_PK)), Some(1_000));
+		assert_eq!(OmniSwap::mpc_members(), vec![[2; 64], [3; 64]]);
+		assert_eq!(OmniSwap::mpc_threshold(), 2);
+	});
+}
+
+#[test]
+fn it_works_for_swap_fee_in_events() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+		let pair_id = crate::canonical_pair_id(&token_x_id, &token_y_id);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+
+		// 0.3% of the sold tokens
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			100000,
+			1,
+			None,
+			None,
+			None
+		));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::SwapX2YTokens(
+			trading_pair.clone(),
+			pair_id.clone(),
+			pk,
+			100000,
+			90661,
+			300,
+		)));
+
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+		let tokens_bought = crate::get_input_price(1000, 909339, 1100000).unwrap();
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::SwapY2XTokens(
+			trading_pair,
+			pair_id,
+			pk,
+			1000,
+			tokens_bought,
+			3,
+		)));
+	});
+}
+
+#[test]
+fn it_fails_for_confirming_a_deposit_twice() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		// The deposit of token X with nonce 1 is confirmed
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		assert_eq!(OmniSwap::confirmed_deposit((pk, token_x_id.clone(), 1)), Some(()));
+
+		assert_noop!(
+			OmniSwap::deposit_comfirm(RuntimeOrigin::signed(1), pk, token_x_id.clone(), 1),
+			crate::Error::<Test>::NotDeposit
+		);
+
+		// Depositing the executed transfer again does not credit it twice
+		let mpc = OmniSwap::mpc_members()[0];
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 1000, 1);
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id.clone(), transfer_data, true),
+			crate::Error::<Test>::DepositExist
+		);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(1000));
+	});
+}
+
+#[test]
+fn it_fails_for_replaying_a_confirmed_deposit() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		// The deposit of token X with nonce 1 is confirmed and its record removed
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		assert_eq!(OmniSwap::deposit_record((pk, token_x_id.clone(), 1)), None);
+
+		// Replaying it can not leave a pending deposit to confirm again
+		let mpc = OmniSwap::mpc_members()[0];
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 1000, 1);
+		assert_noop!(
+			OmniSwap::deposit(
+				RuntimeOrigin::signed(1),
+				token_x_id.clone(),
+				transfer_data.clone(),
+				false
+			),
+			crate::Error::<Test>::DepositExist
+		);
+		assert_eq!(OmniSwap::mpc_pending_deposits(mpc), 0);
+
+		// The replay is rejected before the transfer is sent to the omniverse token
+		MockFactoryResult::set(Some(pallet_assets::FactoryResult::ProtocolMalicious));
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id, transfer_data, false),
+			crate::Error::<Test>::DepositExist
+		);
+		MockFactoryResult::set(None);
+	});
+}
+
+#[test]
+fn it_works_for_pending_deposits_and_withdrawals() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		assert_eq!(OmniSwap::pending_deposits(pk), Vec::new());
+		assert_eq!(OmniSwap::pending_withdrawals(pk), Vec::new());
+
+		mint(&secp, &token_x_id, &(secret_key, public_key), &pk, 100, 4);
+		deposit(&secp, &token_x_id, &(secret_key, public_key), 30, 5);
+		deposit(&secp, &token_x_id, &(secret_key, public_key), 20, 6);
+		let mut pending = OmniSwap::pending_deposits(pk);
+		pending.sort_by_key(|(_, nonce, _)| *nonce);
+		assert_eq!(
+			pending
+				.iter()
+				.map(|(token_id, nonce, _)| (token_id.clone(), *nonce))
+				.collect::<Vec<_>>(),
+			vec![(token_x_id.clone(), 5), (token_x_id.clone(), 6)]
+		);
+		assert_eq!(pending[0].2.nonce, 5);
+		assert_eq!(OmniSwap::pending_deposits([0; 64]), Vec::new());
+
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 10));
+		assert_eq!(OmniSwap::pending_withdrawals(pk), vec![(token_x_id.clone(), 10)]);
+
+		assert_ok!(OmniSwap::deposit_comfirm(RuntimeOrigin::signed(1), pk, token_x_id.clone(), 5));
+		assert_eq!(OmniSwap::pending_deposits(pk).len(), 1);
+	});
+}
+
+#[test]
+fn it_fails_for_add_liquidity_with_mismatched_token_ids() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_noop!(
+			OmniSwap::add_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1000,
+				1000,
+				1,
+				1,
+				token_x_id.clone(),
+				vec![1],
+				None,
+				None
+			),
+			crate::Error::<Test>::MismatchTokenId
+		);
+		assert_eq!(OmniSwap::trading_pairs(&trading_pair), Some((1000000, 1000000)));
+	});
+}
+
+#[test]
+fn it_fails_for_swap_with_zero_output() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			2000000,
+			1000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_eq!(crate::get_input_price(1, 2000000, 1000), Some(0));
+		assert_noop!(
+			OmniSwap::swap_x2y(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1,
+				1,
+				None,
+				None,
+				None
+			),
+			crate::Error::<Test>::InsufficientOutputAmount
+		);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(0));
+	});
+}
+
+#[test]
+fn it_works_for_approve_and_transfer_balance_from() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+
+		let other_secret_key = SecretKey::from_slice(&[2u8; 32]).unwrap();
+		let other_public_key = PublicKey::from_secret_key(&secp, &other_secret_key);
+		let other_pk: [u8; 64] =
+			other_public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let other_account = get_account_id_from_pk(other_public_key.serialize().as_slice());
+
+		assert_noop!(
+			OmniSwap::approve(
+				RuntimeOrigin::signed(other_account),
+				pk,
+				other_pk,
+				token_x_id.clone(),
+				300
+			),
+			crate::Error::<Test>::NoPermission
+		);
+		assert_ok!(OmniSwap::approve(
+			RuntimeOrigin::signed(account),
+			pk,
+			other_pk,
+			token_x_id.clone(),
+			300
+		));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::Approval(
+			pk,
+			other_pk,
+			token_x_id.clone(),
+			300,
+		)));
+		assert_eq!(OmniSwap::allowance((pk, other_pk, token_x_id.clone())), Some(300));
+
+		// Spend within the allowance
+		assert_ok!(OmniSwap::transfer_balance_from(
+			RuntimeOrigin::signed(other_account),
+			other_pk,
+			pk,
+			other_pk,
+			token_x_id.clone(),
+			200
+		));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::TransferredFrom(
+			pk,
+			other_pk,
+			other_pk,
+			token_x_id.clone(),
+			200,
+		)));
+		assert_eq!(OmniSwap::allowance((pk, other_pk, token_x_id.clone())), Some(100));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(800));
+		assert_eq!(OmniSwap::balance(&other_pk, &token_x_id), Some(200));
+
+		// Spend beyond the allowance
+		assert_noop!(
+			OmniSwap::transfer_balance_from(
+				RuntimeOrigin::signed(other_account),
+				other_pk,
+				pk,
+				other_pk,
+				token_x_id.clone(),
+				200
+			),
+			crate::Error::<Test>::InsufficientAllowance
+		);
+		assert_noop!(
+			OmniSwap::transfer_balance_from(
+				RuntimeOrigin::signed(account),
+				other_pk,
+				pk,
+				pk,
+				token_x_id.clone(),
+				100
+			),
+			crate::Error::<Test>::NoPermission
+		);
+		assert_ok!(OmniSwap::transfer_balance_from(
+			RuntimeOrigin::signed(other_account),
+			other_pk,
+			pk,
+			other_pk,
+			token_x_id.clone(),
+			100
+		));
+		assert_eq!(OmniSwap::allowance((pk, other_pk, token_x_id.clone())), None);
+		assert_eq!(OmniSwap::balance(&other_pk, &token_x_id), Some(300));
+	});
+}
+
+#[test]
+fn it_works_for_transfer_balance() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		let other_pk = [2u8; 64];
+
+		assert_noop!(
+			OmniSwap::transfer_balance(
+				RuntimeOrigin::signed(1),
+				pk,
+				other_pk,
+				token_x_id.clone(),
+				300
+			),
+			crate::Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			OmniSwap::transfer_balance(
+				RuntimeOrigin::signed(account),
+				pk,
+				other_pk,
+				token_x_id.clone(),
+				1001
+			),
+			crate::Error::<Test>::BalanceNotEnough
+		);
+		assert_ok!(OmniSwap::transfer_balance(
+			RuntimeOrigin::signed(account),
+			pk,
+			other_pk,
+			token_x_id.clone(),
+			300
+		));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::BalanceTransferred(
+			pk,
+			other_pk,
+			token_x_id.clone(),
+			300,
+		)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(700));
+		assert_eq!(OmniSwap::balance(&other_pk, &token_x_id), Some(300));
+	});
+}
+
+#[test]
+fn it_fails_for_remove_liquidity_returning_nothing() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			2000000,
+			1000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		let liquidity = OmniSwap::liquidity((trading_pair.clone(), pk)).unwrap();
+		assert!(1000 < OmniSwap::total_liquidity(&trading_pair).unwrap());
+		assert_noop!(
+			OmniSwap::remove_liquidity(
+				RuntimeOrigin::signed(account),
+				trading_pair.clone(),
+				pk,
+				1,
+				0,
+				0,
+				None
+			),
+			crate::Error::<Test>::InsufficientLiquidityBurned
+		);
+		assert_eq!(OmniSwap::liquidity((trading_pair.clone(), pk)), Some(liquidity));
+	});
+}
+
+#[test]
+fn it_fails_for_malicious_or_duplicated_omniverse_transfers() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		let mpc = OmniSwap::mpc_members()[0];
+
+		MockFactoryResult::set(Some(pallet_assets::FactoryResult::ProtocolMalicious));
+		let transfer_data =
+			encode_transfer(&secp, &token_x_id, &(secret_key, public_key), &mpc, 100, 4);
+		assert_noop!(
+			OmniSwap::deposit(RuntimeOrigin::signed(1), token_x_id.clone(), transfer_data, false),
+			crate::Error::<Test>::OmniverseTransferMalicious
+		);
+
+		MockFactoryResult::set(None);
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		let data = encode_withdrawal(&token_x_id, &pk, 100, 0);
+		MockFactoryResult::set(Some(pallet_assets::FactoryResult::ProtocolMalicious));
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				data.clone(),
+				vec![]
+			),
+			crate::Error::<Test>::OmniverseTransferMalicious
+		);
+		MockFactoryResult::set(Some(pallet_assets::FactoryResult::ProtocolDuplicated));
+		assert_noop!(
+			OmniSwap::withdraw_comfirm(
+				RuntimeOrigin::signed(1),
+				pk,
+				token_x_id.clone(),
+				data,
+				vec![]
+			),
+			crate::Error::<Test>::OmniverseTransferDuplicated
+		);
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id)), Some(100));
+	});
+}
+
+#[test]
+fn it_works_for_bridge_fees() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+
+		assert_noop!(
+			OmniSwap::set_bridge_fees(RuntimeOrigin::signed(1), 100, 200),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			OmniSwap::set_bridge_fees(RuntimeOrigin::root(), 10_001, 200),
+			crate::Error::<Test>::InvalidValue
+		);
+		let fee_to = [1u8; 64];
+		assert_ok!(OmniSwap::set_fee_to(RuntimeOrigin::root(), Some(fee_to)));
+		assert_ok!(OmniSwap::set_bridge_fees(RuntimeOrigin::root(), 100, 200));
+
+		// 1% of the deposits
+		let (token_x_id, _) = init_tokens(&secp, &(secret_key, public_key), 1000, 1000);
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(990));
+		assert_eq!(OmniSwap::balance(&fee_to, &token_x_id), Some(10));
+
+		// 2% of the withdrawals
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 500));
+		System::assert_last_event(RuntimeEvent::OmniSwap(crate::Event::Withdrawal(
+			pk,
+			token_x_id.clone(),
+			490,
+		)));
+		assert_eq!(OmniSwap::balance(&pk, &token_x_id), Some(490));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), Some(490));
+		assert_eq!(OmniSwap::balance(&fee_to, &token_x_id), Some(20));
+
+		// No fee is charged without a receiver
+		assert_ok!(OmniSwap::set_fee_to(RuntimeOrigin::root(), None));
+		assert_ok!(OmniSwap::withdraw(RuntimeOrigin::signed(account), pk, token_x_id.clone(), 100));
+		assert_eq!(OmniSwap::withdrawals((pk, token_x_id.clone())), Some(590));
+		assert_eq!(OmniSwap::balance(&fee_to, &token_x_id), Some(20));
+	});
+}
+
+#[test]
+fn it_works_for_bridge_fee_near_u128_max() {
+	assert_eq!(crate::bridge_fee(1000, 100), 10);
+	assert_eq!(crate::bridge_fee(u128::MAX, 200), u128::MAX / 50);
+	assert_eq!(crate::bridge_fee(u128::MAX, 10_000), u128::MAX);
+}
+
+#[test]
+fn it_works_for_liquidity_updated_at() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_eq!(OmniSwap::liquidity_updated_at((trading_pair.clone(), pk)), None);
+		System::set_block_number(3);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id.clone(),
+			token_y_id.clone(),
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::liquidity_updated_at((trading_pair.clone(), pk)), Some(3));
+
+		System::set_block_number(7);
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::liquidity_updated_at((trading_pair.clone(), pk)), Some(7));
+
+		System::set_block_number(12);
+		assert_ok!(OmniSwap::remove_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			1,
+			None
+		));
+		assert_eq!(OmniSwap::liquidity_updated_at((trading_pair.clone(), pk)), Some(12));
+		// Swaps do not change the positions
+		System::set_block_number(15);
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::liquidity_updated_at((trading_pair, pk)), Some(12));
+	});
+}
+
+#[test]
+fn it_works_for_pair_volume() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let (token_x_id, token_y_id) =
+			init_tokens(&secp, &(secret_key, public_key), 2000000, 2000000);
+
+		let trading_pair = vec![1];
+		assert_ok!(OmniSwap::add_liquidity(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000000,
+			1000000,
+			1,
+			1,
+			token_x_id,
+			token_y_id,
+			None,
+			None
+		));
+		// Adding liquidity is not traded volume
+		assert_eq!(OmniSwap::pair_volume(&trading_pair), (0, 0));
+
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::swap_y2x(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			500,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			2000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::pair_volume(&trading_pair), (3000, 500));
+
+		// The amount sold is counted for an exact output as well
+		let (reserve_x, reserve_y) = OmniSwap::trading_pairs(&trading_pair).unwrap();
+		let tokens_sold = crate::get_output_price(100, reserve_y, reserve_x).unwrap();
+		assert_ok!(OmniSwap::swap_y2x_exact_out(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			100,
+			tokens_sold,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::pair_volume(&trading_pair), (3000, 500 + tokens_sold));
+
+		// The volume saturates instead of overflowing
+		crate::PairVolume::<Test>::insert(&trading_pair, (u128::MAX - 1, 0));
+		assert_ok!(OmniSwap::swap_x2y(
+			RuntimeOrigin::signed(account),
+			trading_pair.clone(),
+			pk,
+			1000,
+			1,
+			None,
+			None,
+			None
+		));
+		assert_eq!(OmniSwap::pair_volume(&trading_pair), (u128::MAX, 0));
+	});
+}
+
+#[test]
+fn it_works_for_migrating_deposit_records_to_v1() {
+	new_test_ext().execute_with(|| {
+		let pk = [1u8; 64];
+		let token_id = vec![1];
+		// A deposit recorded before the signature scheme was added
+		let old = pallet_omniverse_protocol::migration::v0::OmniverseTransactionData {
+			nonce: 1,
+			chain_id: CHAIN_ID,
+			initiator_address: Vec::new(),
+			from: pk,
+			payload: vec![2],
+			signature: [3; 65],
+		};
+		let key = crate::DepositRecords::<Test>::hashed_key_for((pk, token_id.clone(), 1u128));
+		frame_support::storage::unhashed::put(&key, &old);
+		StorageVersion::new(0).put::<OmniSwap>();
+
+		let upgraded_at = Timestamp::now().as_secs();
+		crate::migration::migrate_to_v1::<Test>();
+		let (data, deposited_at) = OmniSwap::deposit_record((pk, token_id, 1u128)).unwrap();
+		assert_eq!(data, old.upgrade());
+		assert_eq!(data.signature_scheme, pallet_omniverse_protocol::SECP256K1);
+		assert!(deposited_at >= upgraded_at && deposited_at <= Timestamp::now().as_secs());
+		assert_eq!(OmniSwap::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn it_fails_for_reclaiming_a_migrated_deposit_before_expiry() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+		let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let token_id = vec![1];
+		let old = pallet_omniverse_protocol::migration::v0::OmniverseTransactionData {
+			nonce: 1,
+			chain_id: CHAIN_ID,
+			initiator_address: Vec::new(),
+			from: pk,
+			payload: vec![2],
+			signature: [3; 65],
+		};
+		let key = crate::DepositRecords::<Test>::hashed_key_for((pk, token_id.clone(), 1u128));
+		frame_support::storage::unhashed::put(&key, &old);
+		StorageVersion::new(0).put::<OmniSwap>();
+		crate::migration::migrate_to_v1::<Test>();
+
+		// The expiry of a migrated deposit runs from the upgrade
+		assert_noop!(
+			OmniSwap::reclaim_deposit(RuntimeOrigin::signed(account), pk, token_id.clone(), 1),
+			crate::Error::<Test>::DepositNotExpired
+		);
+		Timestamp::past(DepositExpiry::get());
+		assert_noop!(
+			OmniSwap::reclaim_deposit(RuntimeOrigin::signed(account), pk, token_id, 1),
+			crate::Error::<Test>::TxNotExisted
+		);
+	});
+}
+
+#[test]
+fn it_works_for_migrating_the_mpc_to_v1() {
+	new_test_ext().execute_with(|| {
+		// The single MPC key stored before the MPC members were added
+		let mpc = [7u8; 64];
+		frame_support::storage::migration::put_storage_value(b"OmniSwap", b"Mpc", &[], mpc);
+		StorageVersion::new(0).put::<OmniSwap>();
+
+		crate::migration::migrate_to_v1::<Test>();
+		assert_eq!(OmniSwap::mpc_members(), vec![mpc]);
+		assert_eq!(OmniSwap::mpc_threshold(), 1);
+		assert_eq!(
+			frame_support::storage::migration::get_storage_value::<[u8; 64]>(
+				b"OmniSwap",
+				b"Mpc",
+				&[]
+			),
+			None
+		);
+		assert_eq!(OmniSwap::on_chain_storage_version(), 1);
+	});
+}
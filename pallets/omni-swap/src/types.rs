@@ -0,0 +1,19 @@
+//! Various basic types for use in the omniverse swap pallet.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+/// The state of a pool, returned by `pool_info`
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct PoolInfo<BlockNumber> {
+	pub reserve_x: u128,
+	pub reserve_y: u128,
+	pub total_liquidity: u128,
+	pub token_x_id: Vec<u8>,
+	pub token_y_id: Vec<u8>,
+	/// Swap fee in basis points
+	pub fee_tier: u32,
+	/// The block at which the reserves were last updated
+	pub last_updated: BlockNumber,
+}
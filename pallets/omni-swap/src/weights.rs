@@ -0,0 +1,681 @@
+//! Weights for pallet_omniverse_swap
+//!
+//! The weights are estimated from the storage accessed by each call, they are to be replaced by
+//! the output of the benchmark CLI on reference hardware:
+//!
+//! ./target/release/node-template benchmark pallet --chain=dev --steps=50 --repeat=20
+//! --pallet=pallet_omniverse_swap --extrinsic=* --execution=wasm --wasm-execution=compiled
+//! --output=./pallets/omni-swap/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_omniverse_swap.
+pub trait WeightInfo {
+	fn swap_x2y(m: u32, ) -> Weight;
+	fn swap_y2x(m: u32, ) -> Weight;
+	fn add_liquidity(m: u32, ) -> Weight;
+	fn remove_liquidity(m: u32, ) -> Weight;
+	fn deposit(m: u32, ) -> Weight;
+	fn withdraw(m: u32, ) -> Weight;
+	fn deposit_comfirm(m: u32, ) -> Weight;
+	fn reclaim_deposit() -> Weight;
+	fn withdraw_comfirm(s: u32, ) -> Weight;
+	fn cancel_withdrawal(m: u32, ) -> Weight;
+	fn swap_x2y_exact_out(m: u32, ) -> Weight;
+	fn swap_y2x_exact_out(m: u32, ) -> Weight;
+	fn swap_exact_tokens_for_tokens(p: u32, m: u32, ) -> Weight;
+	fn remove_all_liquidity(m: u32, ) -> Weight;
+	fn set_mpc_members(m: u32, ) -> Weight;
+	fn propose_mpc() -> Weight;
+	fn accept_mpc() -> Weight;
+	fn set_fee_to() -> Weight;
+	fn set_paused() -> Weight;
+	fn set_bridge_fees() -> Weight;
+	fn transfer_balance(m: u32, ) -> Weight;
+	fn approve() -> Weight;
+	fn transfer_balance_from(m: u32, ) -> Weight;
+}
+
+/// Weights for pallet_omniverse_swap using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_x2y(m: u32, ) -> Weight {
+		Weight::from_ref_time(45_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_y2x(m: u32, ) -> Weight {
+		Weight::from_ref_time(45_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:1)
+	// Storage: OmniverseSwap PairOf (r:1 w:1)
+	// Storage: OmniverseSwap TradingPairs (r:2 w:1)
+	// Storage: OmniverseSwap TotalLiquidity (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:0 w:1)
+	// Storage: OmniverseSwap PairCreatedAt (r:0 w:1)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap KLast (r:1 w:1)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Liquidity (r:1 w:2)
+	// Storage: OmniverseSwap LiquidityUpdatedAt (r:0 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn add_liquidity(m: u32, ) -> Weight {
+		Weight::from_ref_time(70_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(13_u64))
+	}
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap KLast (r:1 w:1)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Liquidity (r:2 w:2)
+	// Storage: OmniverseSwap TotalLiquidity (r:2 w:2)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap LiquidityUpdatedAt (r:0 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn remove_liquidity(m: u32, ) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap DepositRecords (r:1 w:1)
+	// Storage: OmniverseSwap ConfirmedDeposits (r:1 w:1)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:1)
+	// Storage: Assets TokensInfo (r:1 w:0)
+	// Storage: Assets DelayedTransactions (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionCount (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:1)
+	// Storage: OmniverseProtocol EvilRecorder (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:1)
+	// Storage: OmniverseSwap DepositFeeBps (r:1 w:0)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn deposit(m: u32, ) -> Weight {
+		Weight::from_ref_time(120_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:1 w:1)
+	// Storage: OmniverseSwap Withdrawals (r:1 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn withdraw(m: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap DepositRecords (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:0)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:1)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:1)
+	// Storage: OmniverseSwap ConfirmedDeposits (r:1 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	// Storage: OmniverseSwap DepositFeeBps (r:1 w:0)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	fn deposit_comfirm(m: u32, ) -> Weight {
+		Weight::from_ref_time(40_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	// Storage: OmniverseSwap DepositRecords (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:0)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:1)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:1)
+	fn reclaim_deposit() -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: OmniverseSwap Withdrawals (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap MpcThreshold (r:1 w:0)
+	// Storage: Assets TokensInfo (r:1 w:0)
+	// Storage: Assets DelayedTransactions (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionCount (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:1)
+	// Storage: OmniverseProtocol EvilRecorder (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:1)
+	fn withdraw_comfirm(s: u32, ) -> Weight {
+		Weight::from_ref_time(120_000_000_u64)
+			.saturating_add(Weight::from_ref_time(40_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Withdrawals (r:1 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:1 w:1)
+	fn cancel_withdrawal(m: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_x2y_exact_out(m: u32, ) -> Weight {
+		Weight::from_ref_time(50_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_y2x_exact_out(m: u32, ) -> Weight {
+		Weight::from_ref_time(50_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_exact_tokens_for_tokens(p: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(15_000_000_u64)
+			.saturating_add(Weight::from_ref_time(40_000_000_u64).saturating_mul(p as u64))
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(p as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((7_u64).saturating_mul(p as u64)))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(p as u64)))
+	}
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap KLast (r:1 w:1)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Liquidity (r:2 w:2)
+	// Storage: OmniverseSwap TotalLiquidity (r:2 w:2)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap LiquidityUpdatedAt (r:0 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn remove_all_liquidity(m: u32, ) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	// Storage: OmniverseSwap MpcMembers (r:0 w:1)
+	// Storage: OmniverseSwap MpcThreshold (r:0 w:1)
+	fn set_mpc_members(m: u32, ) -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(Weight::from_ref_time(500_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap PendingMpc (r:0 w:1)
+	fn propose_mpc() -> Weight {
+		Weight::from_ref_time(15_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap PendingMpc (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:1)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:1)
+	fn accept_mpc() -> Weight {
+		Weight::from_ref_time(20_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: OmniverseSwap FeeTo (r:0 w:1)
+	fn set_fee_to() -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:0 w:1)
+	fn set_paused() -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap DepositFeeBps (r:0 w:1)
+	// Storage: OmniverseSwap WithdrawFeeBps (r:0 w:1)
+	fn set_bridge_fees() -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn transfer_balance(m: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap Allowances (r:0 w:1)
+	fn approve() -> Weight {
+		Weight::from_ref_time(12_000_000_u64)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap Allowances (r:1 w:1)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn transfer_balance_from(m: u32, ) -> Weight {
+		Weight::from_ref_time(30_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_x2y(m: u32, ) -> Weight {
+		Weight::from_ref_time(45_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_y2x(m: u32, ) -> Weight {
+		Weight::from_ref_time(45_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:1)
+	// Storage: OmniverseSwap PairOf (r:1 w:1)
+	// Storage: OmniverseSwap TradingPairs (r:2 w:1)
+	// Storage: OmniverseSwap TotalLiquidity (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:0 w:1)
+	// Storage: OmniverseSwap PairCreatedAt (r:0 w:1)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap KLast (r:1 w:1)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Liquidity (r:1 w:2)
+	// Storage: OmniverseSwap LiquidityUpdatedAt (r:0 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn add_liquidity(m: u32, ) -> Weight {
+		Weight::from_ref_time(70_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(13_u64))
+	}
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap KLast (r:1 w:1)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Liquidity (r:2 w:2)
+	// Storage: OmniverseSwap TotalLiquidity (r:2 w:2)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap LiquidityUpdatedAt (r:0 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn remove_liquidity(m: u32, ) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap DepositRecords (r:1 w:1)
+	// Storage: OmniverseSwap ConfirmedDeposits (r:1 w:1)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:1)
+	// Storage: Assets TokensInfo (r:1 w:0)
+	// Storage: Assets DelayedTransactions (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionCount (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:1)
+	// Storage: OmniverseProtocol EvilRecorder (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:1)
+	// Storage: OmniverseSwap DepositFeeBps (r:1 w:0)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn deposit(m: u32, ) -> Weight {
+		Weight::from_ref_time(120_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:1 w:1)
+	// Storage: OmniverseSwap Withdrawals (r:1 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn withdraw(m: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap DepositRecords (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:0)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:1)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:1)
+	// Storage: OmniverseSwap ConfirmedDeposits (r:1 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	// Storage: OmniverseSwap DepositFeeBps (r:1 w:0)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	fn deposit_comfirm(m: u32, ) -> Weight {
+		Weight::from_ref_time(40_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	// Storage: OmniverseSwap DepositRecords (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:0)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:1)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:1)
+	fn reclaim_deposit() -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: OmniverseSwap Withdrawals (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap MpcThreshold (r:1 w:0)
+	// Storage: Assets TokensInfo (r:1 w:0)
+	// Storage: Assets DelayedTransactions (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionCount (r:1 w:1)
+	// Storage: OmniverseProtocol TransactionRecorder (r:1 w:1)
+	// Storage: OmniverseProtocol EvilRecorder (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:1)
+	fn withdraw_comfirm(s: u32, ) -> Weight {
+		Weight::from_ref_time(120_000_000_u64)
+			.saturating_add(Weight::from_ref_time(40_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Withdrawals (r:1 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:1 w:1)
+	fn cancel_withdrawal(m: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_x2y_exact_out(m: u32, ) -> Weight {
+		Weight::from_ref_time(50_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_y2x_exact_out(m: u32, ) -> Weight {
+		Weight::from_ref_time(50_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap PairVolume (r:1 w:1)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn swap_exact_tokens_for_tokens(p: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(15_000_000_u64)
+			.saturating_add(Weight::from_ref_time(40_000_000_u64).saturating_mul(p as u64))
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(p as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((7_u64).saturating_mul(p as u64)))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(p as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(p as u64)))
+	}
+	// Storage: OmniverseSwap TradingPairs (r:1 w:1)
+	// Storage: OmniverseSwap PriceCumulative (r:1 w:1)
+	// Storage: OmniverseSwap KLast (r:1 w:1)
+	// Storage: OmniverseSwap FeeTo (r:1 w:0)
+	// Storage: OmniverseSwap Liquidity (r:2 w:2)
+	// Storage: OmniverseSwap TotalLiquidity (r:2 w:2)
+	// Storage: OmniverseSwap TokenId (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap LiquidityUpdatedAt (r:0 w:1)
+	// Storage: OmniverseSwap TotalDeposited (r:2 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:2 w:0)
+	fn remove_all_liquidity(m: u32, ) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			.saturating_add(Weight::from_ref_time(2_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	// Storage: OmniverseSwap MpcMembers (r:0 w:1)
+	// Storage: OmniverseSwap MpcThreshold (r:0 w:1)
+	fn set_mpc_members(m: u32, ) -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(Weight::from_ref_time(500_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap PendingMpc (r:0 w:1)
+	fn propose_mpc() -> Weight {
+		Weight::from_ref_time(15_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap PendingMpc (r:1 w:1)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:1)
+	// Storage: OmniverseSwap MpcPendingDeposits (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:1)
+	fn accept_mpc() -> Weight {
+		Weight::from_ref_time(20_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: OmniverseSwap FeeTo (r:0 w:1)
+	fn set_fee_to() -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:0 w:1)
+	fn set_paused() -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap DepositFeeBps (r:0 w:1)
+	// Storage: OmniverseSwap WithdrawFeeBps (r:0 w:1)
+	fn set_bridge_fees() -> Weight {
+		Weight::from_ref_time(10_000_000_u64)
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn transfer_balance(m: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: OmniverseSwap Allowances (r:0 w:1)
+	fn approve() -> Weight {
+		Weight::from_ref_time(12_000_000_u64)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: OmniverseSwap Paused (r:1 w:0)
+	// Storage: OmniverseSwap Allowances (r:1 w:1)
+	// Storage: OmniverseSwap Balance (r:2 w:2)
+	// Storage: OmniverseSwap TotalDeposited (r:1 w:0)
+	// Storage: OmniverseSwap MpcMembers (r:1 w:0)
+	// Storage: OmniverseSwap RetiredMpcMembers (r:1 w:0)
+	// Storage: Assets Tokens (r:1 w:0)
+	fn transfer_balance_from(m: u32, ) -> Weight {
+		Weight::from_ref_time(30_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_000_000_u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+}
@@ -21,14 +21,18 @@ use super::*;
 use frame_support::{
 	ensure,
 	traits::{ExistenceRequirement, Get},
+	BoundedVec,
 };
 use pallet_omniverse_protocol::{
 	traits::OmniverseAccounts,
-	types::{Assets, OmniverseTransactionData, VerifyError, VerifyResult, BURN, MINT, TRANSFER},
+	types::{
+		BatchMintOp, NonFungible, OmniverseTransactionData, TransferWithPriceOp, VerifyError,
+		VerifyResult, BATCH_MINT, BURN, MINT, SET_METADATA, TRANSFER,
+	},
 };
 use secp256k1::PublicKey;
 use sp_core::Hasher;
-use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::traits::{BlakeTwo256, SaturatedConversion};
 use sp_runtime::{DispatchError, DispatchResult};
 
 impl<T: Config<I>, I: 'static> Pallet<T, I> {
@@ -291,7 +295,56 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			.map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
 		let public_key_compressed = public_key.serialize();
 		let hash = BlakeTwo256::hash(&public_key_compressed);
-		Ok(T::AccountId::decode(&mut &hash[..]).unwrap())
+		T::AccountId::decode(&mut &hash[..]).map_err(|_| Error::<T, I>::AccountConversionFailed)
+	}
+
+	/// The chains and addresses the omniverse token `token_id` is deployed to
+	pub fn token_members(token_id: Vec<u8>) -> Option<Vec<(u32, Vec<u8>)>> {
+		TokensInfo::<T, I>::get(token_id).map(|token| token.members)
+	}
+
+	/// Whether `address` on `chain_id` is a member of the omniverse token `token_id`
+	pub fn is_token_member(token_id: Vec<u8>, chain_id: u32, address: Vec<u8>) -> bool {
+		TokensInfo::<T, I>::get(token_id)
+			.map_or(false, |token| token.is_member(&(chain_id, address)))
+	}
+
+	/// Decode the destination of a transfer, followed by the sale price if one is attached
+	pub(super) fn decode_transfer(
+		ex_data: Vec<u8>,
+	) -> Result<([u8; 64], Option<u128>), Error<T, I>> {
+		if ex_data.len() == 64 {
+			let dest_pk: [u8; 64] =
+				ex_data.try_into().map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
+			return Ok((dest_pk, None));
+		}
+		let op = TransferWithPriceOp::decode(&mut ex_data.as_slice())
+			.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
+		Ok((op.to, Some(op.price)))
+	}
+
+	/// Pay the royalty of the sale price from the seller to the beneficiary of the token
+	pub(super) fn pay_royalty(
+		token_id: &Vec<u8>,
+		seller: &T::AccountId,
+		item: T::ItemId,
+		price: u128,
+	) -> DispatchResult {
+		if let Some((bps, beneficiary)) = Royalties::<T, I>::get(token_id) {
+			let royalty = price.saturating_mul(bps as u128) / 10_000;
+			if royalty > 0 {
+				let dest = Self::to_account(&beneficiary)?;
+				let amount: DepositBalanceOf<T, I> = royalty.saturated_into();
+				T::Currency::transfer(seller, &dest, amount, ExistenceRequirement::KeepAlive)?;
+				Self::deposit_event(Event::RoyaltyPaid {
+					token_id: token_id.clone(),
+					item,
+					beneficiary,
+					amount,
+				});
+			}
+		}
+		Ok(())
 	}
 
 	pub(super) fn omniverse_transfer(
@@ -351,6 +404,35 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(())
 	}
 
+	/// Set the metadata of an item from an omniverse transaction, metadata set this way holds no
+	/// deposit
+	pub(super) fn omniverse_set_metadata(
+		collection: T::CollectionId,
+		item: T::ItemId,
+		data: BoundedVec<u8, T::StringLimit>,
+	) -> DispatchResult {
+		let mut collection_details =
+			Collection::<T, I>::get(collection).ok_or(Error::<T, I>::UnknownCollection)?;
+
+		ItemMetadataOf::<T, I>::try_mutate_exists(collection, item, |metadata| {
+			ensure!(!metadata.as_ref().map_or(false, |m| m.is_frozen), Error::<T, I>::Frozen);
+
+			if metadata.is_none() {
+				collection_details.item_metadatas.saturating_inc();
+			}
+			let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+			collection_details.total_deposit.saturating_reduce(old_deposit);
+			T::Currency::unreserve(&collection_details.owner, old_deposit);
+
+			*metadata =
+				Some(ItemMetadata { deposit: Zero::zero(), data: data.clone(), is_frozen: false });
+
+			Collection::<T, I>::insert(collection, &collection_details);
+			Self::deposit_event(Event::MetadataSet { collection, item, data, is_frozen: false });
+			Ok(())
+		})
+	}
+
 	pub fn send_transaction_external(
 		token_id: Vec<u8>,
 		data: &OmniverseTransactionData,
@@ -377,12 +459,17 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		// Check if the sender is honest
 		ensure!(!T::OmniverseProtocol::is_malicious(data.from), Error::<T, I>::UserIsMalicious);
 
+		// Transactions of the other member chains are relayed here on purpose
+		let relayed = data.chain_id != T::OmniverseProtocol::get_chain_id()
+			&& omniverse_token.is_member(&(data.chain_id, data.initiator_address.clone()));
+
 		// Verify the signature
 		let ret = T::OmniverseProtocol::verify_transaction(
 			PALLET_NAME.as_ref(),
 			&omniverse_token.token_id,
 			data,
 			false,
+			relayed,
 		);
 		let ret = match ret {
 			Err(_) => T::OmniverseProtocol::verify_transaction(
@@ -390,6 +477,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				&omniverse_token.token_id,
 				data,
 				true,
+				relayed,
 			),
 			_ => ret,
 		};
@@ -412,22 +500,20 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				return Err(Error::<T, I>::ProtocolSignerNotCaller.into())
 			},
 			Err(VerifyError::NonceError) => return Err(Error::<T, I>::ProtocolNonceError.into()),
+			Err(VerifyError::WrongChain) => return Err(Error::<T, I>::ProtocolWrongChain.into()),
 			Ok(VerifyResult::Success) => {
 				// Verify balance
-				{
+				let (op, quantity) = {
 					let id = TokenId2CollectionId::<T, I>::get(&omniverse_token.token_id)
 						.ok_or(Error::<T, I>::UnknownCollection)?;
-					let assets = Assets::decode(&mut data.payload.as_slice())
+					let nft = NonFungible::decode(&mut data.payload.as_slice())
 						.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
-					let item = T::ItemId::try_from(assets.quantity)
-						.unwrap_or(<T as Config<I>>::ItemId::default());
+					let item = T::ItemId::try_from(nft.token_id)
+						.map_err(|_| Error::<T, I>::InvalidItemId)?;
 					let collection_details =
 						Collection::<T, I>::get(id).ok_or(Error::<T, I>::UnknownCollection)?;
-					if assets.op == TRANSFER {
-						let dest_pk: [u8; 64] = assets
-							.ex_data
-							.try_into()
-							.map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
+					if nft.op == TRANSFER {
+						let (dest_pk, _) = Self::decode_transfer(nft.ex_data)?;
 						Self::to_account(&dest_pk)?;
 						ensure!(!collection_details.is_frozen, Error::<T, I>::Frozen);
 						ensure!(!T::Locker::is_locked(id, item), Error::<T, I>::Locked);
@@ -439,8 +525,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 							let approved = details.approved.take().map_or(false, |i| i == source);
 							ensure!(approved, Error::<T, I>::NoPermission);
 						}
-					} else if assets.op == MINT {
-						let dest_pk: [u8; 64] = assets
+					} else if nft.op == MINT {
+						let dest_pk: [u8; 64] = nft
 							.ex_data
 							.try_into()
 							.map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
@@ -458,19 +544,71 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 							);
 						}
 						collection_details.items.checked_add(1).ok_or(ArithmeticError::Overflow)?;
-					} else if assets.op == BURN {
+					} else if nft.op == BURN {
 						let details =
 							Item::<T, I>::get(id, item).ok_or(Error::<T, I>::UnknownCollection)?;
 						let is_permitted = details.owner == source;
 						ensure!(is_permitted, Error::<T, I>::NoPermission);
+					} else if nft.op == BATCH_MINT {
+						let batch = BatchMintOp::decode(&mut nft.ex_data.as_slice())
+							.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
+						ensure!(
+							!batch.item_ids.is_empty()
+								&& batch.item_ids.len() as u128 == nft.token_id,
+							Error::<T, I>::BatchMismatch
+						);
+						Self::to_account(&batch.to)?;
+						for (i, item_id) in batch.item_ids.iter().enumerate() {
+							let item = T::ItemId::try_from(*item_id)
+								.map_err(|_| Error::<T, I>::InvalidItemId)?;
+							ensure!(
+								!batch.item_ids[..i].contains(item_id)
+									&& !Item::<T, I>::contains_key(id, item),
+								Error::<T, I>::AlreadyExists
+							);
+						}
+						ensure!(collection_details.issuer == source, Error::<T, I>::NoPermission);
+
+						// The whole batch must fit in the max supply
+						let items = collection_details
+							.items
+							.checked_add(batch.item_ids.len() as u32)
+							.ok_or(ArithmeticError::Overflow)?;
+						if let Ok(max_supply) = CollectionMaxSupply::<T, I>::try_get(id) {
+							ensure!(items <= max_supply, Error::<T, I>::MaxSupplyReached);
+						}
+					} else if nft.op == SET_METADATA {
+						let _: BoundedVec<u8, T::StringLimit> =
+							nft.ex_data.try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+						let details =
+							Item::<T, I>::get(id, item).ok_or(Error::<T, I>::UnknownItem)?;
+						ensure!(
+							details.owner == source || collection_details.issuer == source,
+							Error::<T, I>::NoPermission
+						);
+						ensure!(
+							!ItemMetadataOf::<T, I>::get(id, item).map_or(false, |m| m.is_frozen),
+							Error::<T, I>::Frozen
+						);
 					} else {
 						return Err(Error::<T, I>::UnknownProtocolType.into());
 					}
-				}
+					(nft.op, nft.token_id)
+				};
 				let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
+				ensure!(
+					delayed_index - delayed_executing_index < T::MaxDelayedQueue::get(),
+					Error::<T, I>::DelayedQueueFull
+				);
 				DelayedTransactions::<T, I>::insert(
 					delayed_index,
-					DelayedTx::new(data.from, omniverse_token.token_id.clone(), data.nonce),
+					DelayedTx::new(
+						data.from,
+						omniverse_token.token_id.clone(),
+						data.nonce,
+						op,
+						quantity,
+					),
 				);
 				DelayedIndex::<T, I>::set((delayed_executing_index, delayed_index + 1));
 				Self::deposit_event(Event::TransactionSent {
@@ -484,47 +622,100 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(FactoryResult::Success)
 	}
 
+	/// Execute the delayed transaction at the head of the queue if its cooling down time has
+	/// elapsed
+	pub(super) fn execute_next_delayed_transaction() -> DispatchResult {
+		let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
+		ensure!(delayed_executing_index < delayed_index, Error::<T, I>::NoDelayedTx);
+		let delayed_tx = DelayedTransactions::<T, I>::get(delayed_executing_index)
+			.ok_or(Error::<T, I>::DelayedTxNotExisted)?;
+		let omni_tx = T::OmniverseProtocol::get_transaction_data(
+			delayed_tx.sender,
+			PALLET_NAME.to_vec(),
+			delayed_tx.token_id.clone(),
+			delayed_tx.nonce,
+		)
+		.ok_or(Error::<T, I>::TxNotExisted)?;
+
+		let token = TokensInfo::<T, I>::get(&delayed_tx.token_id)
+			.ok_or(Error::<T, I>::UnknownCollection)?;
+		let cur_st = T::Timestamp::now().as_secs();
+		ensure!(cur_st >= omni_tx.timestamp + token.cooldown_time, Error::<T, I>::NotExecutable);
+
+		DelayedIndex::<T, I>::set((delayed_executing_index + 1, delayed_index));
+
+		// A transaction failing to execute is skipped so that it does not block the queue, a
+		// record mismatching the queue is kept to be executed once it is fixed
+		let result = frame_support::storage::with_storage_layer(|| {
+			Self::execute_transaction(&delayed_tx, &omni_tx.tx_data)
+		});
+		match result {
+			Ok(()) => Self::deposit_event(Event::TransactionExecuted {
+				pk: delayed_tx.sender,
+				nonce: delayed_tx.nonce,
+				token_id: delayed_tx.token_id,
+			}),
+			Err(e) if e == DispatchError::from(Error::<T, I>::DelayedTxMismatch) => return Err(e),
+			Err(reason) => Self::deposit_event(Event::TransactionExecutionFailed {
+				pk: delayed_tx.sender,
+				nonce: delayed_tx.nonce,
+				token_id: delayed_tx.token_id,
+				reason,
+			}),
+		}
+
+		Ok(())
+	}
+
 	pub(super) fn execute_transaction(
-		token_id: &Vec<u8>,
+		delayed_tx: &DelayedTx,
 		data: &OmniverseTransactionData,
 	) -> Result<(), DispatchError> {
+		let token_id = &delayed_tx.token_id;
 		let omniverse_token =
 			TokensInfo::<T, I>::get(token_id).ok_or(Error::<T, I>::UnknownCollection)?;
 
 		// Execute
 		// let op_data = TokenOpcode::decode(&mut data.data.as_slice()).unwrap();
 		// let transfer_data = TransferTokenOp::decode(&mut data.op_data.as_slice()).unwrap();
-		let assets = Assets::decode(&mut data.payload.as_slice())
+		let nft = NonFungible::decode(&mut data.payload.as_slice())
 			.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
+		// The recorded transaction must be the one that was enqueued
+		ensure!(
+			nft.op == delayed_tx.op && nft.token_id == delayed_tx.quantity,
+			Error::<T, I>::DelayedTxMismatch
+		);
 		// Convert public key to account id
 		let origin = Self::to_account(&data.from)?;
 		let item_id =
-			T::ItemId::try_from(assets.quantity).unwrap_or(<T as Config<I>>::ItemId::default());
+			T::ItemId::try_from(delayed_tx.quantity).map_err(|_| Error::<T, I>::InvalidItemId)?;
 		let id =
 			TokenId2CollectionId::<T, I>::get(token_id).ok_or(Error::<T, I>::UnknownCollection)?;
 
-		if assets.op == TRANSFER {
-			let dest_pk: [u8; 64] =
-				assets.ex_data.try_into().map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
+		if delayed_tx.op == TRANSFER {
+			let (dest_pk, price) = Self::decode_transfer(nft.ex_data)?;
 			let dest = Self::to_account(&dest_pk)?;
+			if let Some(price) = price {
+				Self::pay_royalty(token_id, &origin, item_id, price)?;
+			}
 			Self::do_transfer(id, item_id, dest, |collection_details, details| {
 				if details.owner != origin && collection_details.admin != origin {
 					let approved = details.approved.take().map_or(false, |i| i == origin);
 					ensure!(approved, Error::<T, I>::NoPermission);
 				}
-				Self::omniverse_transfer(omniverse_token, data.from, dest_pk, assets.quantity)?;
+				Self::omniverse_transfer(omniverse_token, data.from, dest_pk, delayed_tx.quantity)?;
 				Ok(())
 			})?;
-		} else if assets.op == MINT {
+		} else if delayed_tx.op == MINT {
 			let dest_pk: [u8; 64] =
-				assets.ex_data.try_into().map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
+				nft.ex_data.try_into().map_err(|_| Error::<T, I>::SerializePublicKeyFailed)?;
 			let dest = Self::to_account(&dest_pk)?;
 			Self::do_mint(id, item_id, dest, |collection_details| {
 				ensure!(collection_details.issuer == origin, Error::<T, I>::NoPermission);
 				Ok(())
 			})?;
-			Self::omniverse_mint(omniverse_token, dest_pk, assets.quantity)?;
-		} else if assets.op == BURN {
+			Self::omniverse_mint(omniverse_token, dest_pk, delayed_tx.quantity)?;
+		} else if delayed_tx.op == BURN {
 			// let check_owner = Some(origin.clone());
 			Self::do_burn(id, item_id, |_, details| {
 				let is_permitted = details.owner == origin;
@@ -535,7 +726,31 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				// );
 				Ok(())
 			})?;
-			Self::omniverse_burn(omniverse_token, data.from, assets.quantity)?;
+			Self::omniverse_burn(omniverse_token, data.from, delayed_tx.quantity)?;
+		} else if delayed_tx.op == BATCH_MINT {
+			let batch = BatchMintOp::decode(&mut nft.ex_data.as_slice())
+				.map_err(|_| Error::<T, I>::DecodePayloadFailed)?;
+			let dest = Self::to_account(&batch.to)?;
+			for item_id in batch.item_ids {
+				let item =
+					T::ItemId::try_from(item_id).map_err(|_| Error::<T, I>::InvalidItemId)?;
+				Self::do_mint(id, item, dest.clone(), |collection_details| {
+					ensure!(collection_details.issuer == origin, Error::<T, I>::NoPermission);
+					Ok(())
+				})?;
+				Self::omniverse_mint(omniverse_token.clone(), batch.to, item_id)?;
+			}
+		} else if delayed_tx.op == SET_METADATA {
+			let metadata: BoundedVec<u8, T::StringLimit> =
+				nft.ex_data.try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+			let details = Item::<T, I>::get(id, item_id).ok_or(Error::<T, I>::UnknownItem)?;
+			let collection_details =
+				Collection::<T, I>::get(id).ok_or(Error::<T, I>::UnknownCollection)?;
+			ensure!(
+				details.owner == origin || collection_details.issuer == origin,
+				Error::<T, I>::NoPermission
+			);
+			Self::omniverse_set_metadata(id, item_id, metadata)?;
 		}
 		Ok(())
 	}
@@ -51,7 +51,10 @@ use frame_support::{
 	dispatch::DispatchResult,
 	ensure,
 	pallet_prelude::DispatchResultWithPostInfo,
-	traits::{tokens::Locker, Currency, EnsureOriginWithArg, ReservableCurrency, UnixTime},
+	traits::{
+		tokens::Locker, BalanceStatus::Reserved, Currency, EnsureOriginWithArg, ReservableCurrency,
+		UnixTime,
+	},
 	transactional,
 };
 use frame_system::Config as SystemConfig;
@@ -78,8 +81,12 @@ pub mod pallet {
 
 	use pallet_omniverse_protocol::{traits::OmniverseAccounts, OmniverseTransactionData};
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T, I = ()>(_);
 
@@ -173,6 +180,18 @@ pub mod pallet {
 		#[pallet::constant]
 		type ValueLimit: Get<u32>;
 
+		/// The maximum number of delayed transactions waiting to be executed.
+		#[pallet::constant]
+		type MaxDelayedQueue: Get<u32>;
+
+		/// The maximum length of an omniverse token ID.
+		#[pallet::constant]
+		type MaxTokenIdLength: Get<u32>;
+
+		/// The maximum number of members of an omniverse token.
+		#[pallet::constant]
+		type MaxMembers: Get<u32>;
+
 		#[cfg(feature = "runtime-benchmarks")]
 		/// A set of helper functions for benchmarking.
 		type Helper: BenchmarkHelper<Self::CollectionId, Self::ItemId>;
@@ -335,6 +354,13 @@ pub mod pallet {
 	pub type TokenId2CollectionId<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, Vec<u8>, T::CollectionId>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn royalty_of)]
+	// key: token_id
+	// value: (royalty_bps, beneficiary_pk)
+	pub type Royalties<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, (u16, [u8; 64])>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn token_id_of_member)]
 	// key: (chain_id, member_address)
@@ -514,6 +540,22 @@ pub mod pallet {
 			token_id: Vec<u8>,
 		},
 
+		// An omniverse transaction failed to execute, it is reverted and skipped.
+		TransactionExecutionFailed {
+			pk: [u8; 64],
+			nonce: u128,
+			token_id: Vec<u8>,
+			reason: DispatchError,
+		},
+
+		// A delayed transaction that can not be executed was skipped by the force origin.
+		DelayedTransactionSkipped {
+			index: u32,
+			pk: [u8; 64],
+			nonce: u128,
+			token_id: Vec<u8>,
+		},
+
 		TransactionDuplicated {
 			pk: [u8; 64],
 			nonce: u128,
@@ -526,10 +568,36 @@ pub mod pallet {
 			members: Vec<(u32, Vec<u8>)>,
 		},
 
+		// remove omniverse members
+		MembersRemoved {
+			token_id: Vec<u8>,
+			members: Vec<(u32, Vec<u8>)>,
+		},
+
 		CooldownTimeSet {
 			token_id: Vec<u8>,
 			cooldown_time: u64,
 		},
+
+		// transfer the ownership of an omniverse token with its collection
+		TokenOwnershipTransferred {
+			token_id: Vec<u8>,
+			owner: T::AccountId,
+			owner_pk: [u8; 64],
+		},
+
+		RoyaltySet {
+			token_id: Vec<u8>,
+			bps: u16,
+			beneficiary: [u8; 64],
+		},
+
+		RoyaltyPaid {
+			token_id: Vec<u8>,
+			item: T::ItemId,
+			beneficiary: [u8; 64],
+			amount: DepositBalanceOf<T, I>,
+		},
 	}
 
 	#[pallet::error]
@@ -580,17 +648,37 @@ pub mod pallet {
 		NotExist,
 		DecodePayloadFailed,
 		SerializePublicKeyFailed,
+		/// The runtime account id can not be decoded from a public key
+		AccountConversionFailed,
 		WrongDestination,
 		UserIsMalicious,
 		SignerNotOwner,
 		ProtocolSignerNotCaller,
 		ProtocolSignatureError,
 		ProtocolNonceError,
+		/// The transaction was signed for another chain
+		ProtocolWrongChain,
 		NoDelayedTx,
 		TxNotExisted,
 		NotExecutable,
 		DelayedTxNotExisted,
 		UnknownProtocolType,
+		/// The recorded transaction does not match the enqueued one
+		DelayedTxMismatch,
+		/// The items of a batch mint are empty or do not match its quantity
+		BatchMismatch,
+		/// The token id of the payload does not fit in an item ID
+		InvalidItemId,
+		/// The metadata of the payload exceeds the string limit
+		BadMetadata,
+		/// The royalty exceeds 10_000 basis points
+		InvalidRoyalty,
+		/// There are `MaxDelayedQueue` delayed transactions waiting to be executed
+		DelayedQueueFull,
+		/// The token ID is longer than `MaxTokenIdLength`
+		TokenIdTooLong,
+		/// There are more than `MaxMembers` members of the token
+		TooManyMembers,
 	}
 
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
@@ -605,6 +693,17 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = Weight::zero();
+			if Self::on_chain_storage_version() < 1 {
+				weight = crate::migration::migrate_to_v1::<T, I, Self>();
+			}
+			weight.saturating_add(crate::migration::migrate_to_v2::<T, I, Self>())
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		/// Issue a new collection of non-fungible items from a public origin.
@@ -625,22 +724,21 @@ pub mod pallet {
 		/// Weight: `O(1)`
 		#[pallet::weight(T::WeightInfo::create())]
 		pub fn create(
-			_origin: OriginFor<T>,
-			_collection: T::CollectionId,
-			_admin: AccountIdLookupOf<T>,
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			admin: AccountIdLookupOf<T>,
 		) -> DispatchResult {
-			// let owner = T::CreateOrigin::ensure_origin(origin, &collection)?;
-			// let admin = T::Lookup::lookup(admin)?;
+			let owner = T::CreateOrigin::ensure_origin(origin, &collection)?;
+			let admin = T::Lookup::lookup(admin)?;
 
-			// Self::do_create_collection(
-			// 	collection,
-			// 	owner.clone(),
-			// 	admin.clone(),
-			// 	T::CollectionDeposit::get(),
-			// 	false,
-			// 	Event::Created { collection, creator: owner, owner: admin },
-			// )
-			Err(Error::<T, I>::Unsupport.into())
+			Self::do_create_collection(
+				collection,
+				owner.clone(),
+				admin.clone(),
+				T::CollectionDeposit::get(),
+				false,
+				Event::Created { collection, creator: owner, owner: admin },
+			)
 		}
 
 		/// Issue a new collection of non-fungible items from a public origin.
@@ -669,6 +767,14 @@ pub mod pallet {
 		) -> DispatchResult {
 			ensure_signed(origin)?;
 			ensure!(!TokensInfo::<T, I>::contains_key(&token_id), Error::<T, I>::InUse);
+			ensure!(
+				token_id.len() <= T::MaxTokenIdLength::get() as usize,
+				Error::<T, I>::TokenIdTooLong
+			);
+			ensure!(
+				members.as_ref().map_or(0, |m| m.len()) <= T::MaxMembers::get() as usize,
+				Error::<T, I>::TooManyMembers
+			);
 
 			// Convert public key to account id
 			let owner = Self::to_account(&owner_pk)?;
@@ -680,7 +786,7 @@ pub mod pallet {
 					owner_pk,
 					token_id.clone(),
 					members.clone(),
-					cooldown_time,
+					Some(cooldown_time.unwrap_or_else(T::OmniverseProtocol::get_cooling_down_time)),
 				),
 			);
 
@@ -765,28 +871,29 @@ pub mod pallet {
 		/// - `m = witness.item_metadatas`
 		/// - `a = witness.attributes`
 		#[pallet::weight(T::WeightInfo::destroy(
-			_witness.items,
- 			_witness.item_metadatas,
-			_witness.attributes,
- 		))]
+			witness.items,
+			witness.item_metadatas,
+			witness.attributes,
+		))]
 		pub fn destroy(
-			_origin: OriginFor<T>,
-			_collection: T::CollectionId,
-			_witness: DestroyWitness,
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			witness: DestroyWitness,
 		) -> DispatchResultWithPostInfo {
-			// let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
-			// 	Ok(_) => None,
-			// 	Err(origin) => Some(ensure_signed(origin)?),
-			// };
-			// let details = Self::do_destroy_collection(collection, witness, maybe_check_owner)?;
-
-			// Ok(Some(T::WeightInfo::destroy(
-			// 	details.items,
-			// 	details.item_metadatas,
-			// 	details.attributes,
-			// ))
-			// .into())
-			Err(Error::<T, I>::Unsupport.into())
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+			// The collection of an omniverse token lives as long as the token
+			ensure!(!CollectionId2TokenId::<T, I>::contains_key(collection), Error::<T, I>::InUse);
+			let details = Self::do_destroy_collection(collection, witness, maybe_check_owner)?;
+
+			Ok(Some(T::WeightInfo::destroy(
+				details.items,
+				details.item_metadatas,
+				details.attributes,
+			))
+			.into())
 		}
 
 		/// Mint an item of a particular collection.
@@ -802,19 +909,23 @@ pub mod pallet {
 		/// Weight: `O(1)`
 		#[pallet::weight(T::WeightInfo::mint())]
 		pub fn mint(
-			_origin: OriginFor<T>,
-			_collection: T::CollectionId,
-			_item: T::ItemId,
-			_owner: AccountIdLookupOf<T>,
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			item: T::ItemId,
+			owner: AccountIdLookupOf<T>,
 		) -> DispatchResult {
-			// let origin = ensure_signed(origin)?;
-			// let owner = T::Lookup::lookup(owner)?;
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			// Items of omniverse tokens only move through omniverse transactions
+			ensure!(
+				!CollectionId2TokenId::<T, I>::contains_key(collection),
+				Error::<T, I>::Unsupport
+			);
 
-			// Self::do_mint(collection, item, owner, |collection_details| {
-			// 	ensure!(collection_details.issuer == origin, Error::<T, I>::NoPermission);
-			// 	Ok(())
-			// })
-			Err(Error::<T, I>::Unsupport.into())
+			Self::do_mint(collection, item, owner, |collection_details| {
+				ensure!(collection_details.issuer == origin, Error::<T, I>::NoPermission);
+				Ok(())
+			})
 		}
 
 		/// Destroy a single item.
@@ -832,24 +943,28 @@ pub mod pallet {
 		/// Modes: `check_owner.is_some()`.
 		#[pallet::weight(T::WeightInfo::burn())]
 		pub fn burn(
-			_origin: OriginFor<T>,
-			_collection: T::CollectionId,
-			_item: T::ItemId,
-			_check_owner: Option<AccountIdLookupOf<T>>,
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			item: T::ItemId,
+			check_owner: Option<AccountIdLookupOf<T>>,
 		) -> DispatchResult {
-			// let origin = ensure_signed(origin)?;
-			// let check_owner = check_owner.map(T::Lookup::lookup).transpose()?;
-
-			// Self::do_burn(collection, item, |collection_details, details| {
-			// 	let is_permitted = collection_details.admin == origin || details.owner == origin;
-			// 	ensure!(is_permitted, Error::<T, I>::NoPermission);
-			// 	ensure!(
-			// 		check_owner.map_or(true, |o| o == details.owner),
-			// 		Error::<T, I>::WrongOwner
-			// 	);
-			// 	Ok(())
-			// })
-			Err(Error::<T, I>::Unsupport.into())
+			let origin = ensure_signed(origin)?;
+			let check_owner = check_owner.map(T::Lookup::lookup).transpose()?;
+			// Items of omniverse tokens only move through omniverse transactions
+			ensure!(
+				!CollectionId2TokenId::<T, I>::contains_key(collection),
+				Error::<T, I>::Unsupport
+			);
+
+			Self::do_burn(collection, item, |collection_details, details| {
+				let is_permitted = collection_details.admin == origin || details.owner == origin;
+				ensure!(is_permitted, Error::<T, I>::NoPermission);
+				ensure!(
+					check_owner.map_or(true, |o| o == details.owner),
+					Error::<T, I>::WrongOwner
+				);
+				Ok(())
+			})
 		}
 
 		/// Move an item from the sender account to another.
@@ -871,22 +986,26 @@ pub mod pallet {
 		/// Weight: `O(1)`
 		#[pallet::weight(T::WeightInfo::transfer())]
 		pub fn transfer(
-			_origin: OriginFor<T>,
-			_collection: T::CollectionId,
-			_item: T::ItemId,
-			_dest: AccountIdLookupOf<T>,
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			item: T::ItemId,
+			dest: AccountIdLookupOf<T>,
 		) -> DispatchResult {
-			// let origin = ensure_signed(origin)?;
-			// let dest = T::Lookup::lookup(dest)?;
+			let origin = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+			// Items of omniverse tokens only move through omniverse transactions
+			ensure!(
+				!CollectionId2TokenId::<T, I>::contains_key(collection),
+				Error::<T, I>::Unsupport
+			);
 
-			// Self::do_transfer(collection, item, dest, |collection_details, details| {
-			// 	if details.owner != origin && collection_details.admin != origin {
-			// 		let approved = details.approved.take().map_or(false, |i| i == origin);
-			// 		ensure!(approved, Error::<T, I>::NoPermission);
-			// 	}
-			// 	Ok(())
-			// })
-			Err(Error::<T, I>::Unsupport.into())
+			Self::do_transfer(collection, item, dest, |collection_details, details| {
+				if details.owner != origin && collection_details.admin != origin {
+					let approved = details.approved.take().map_or(false, |i| i == origin);
+					ensure!(approved, Error::<T, I>::NoPermission);
+				}
+				Ok(())
+			})
 		}
 
 		/// Reevaluate the deposits on some items.
@@ -1708,31 +1827,38 @@ pub mod pallet {
 		/// Emits `CollectionMaxSupplySet` event when successful.
 		#[pallet::weight(T::WeightInfo::set_collection_max_supply())]
 		pub fn set_collection_max_supply(
-			_origin: OriginFor<T>,
-			_collection: T::CollectionId,
-			_max_supply: u32,
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			max_supply: u32,
 		) -> DispatchResult {
-			// let maybe_check_owner = T::ForceOrigin::try_origin(origin)
-			// 	.map(|_| None)
-			// 	.or_else(|origin| ensure_signed(origin).map(Some))?;
+			let maybe_check_owner = T::ForceOrigin::try_origin(origin)
+				.map(|_| None)
+				.or_else(|origin| ensure_signed(origin).map(Some))?;
 
-			// ensure!(
-			// 	!CollectionMaxSupply::<T, I>::contains_key(&collection),
-			// 	Error::<T, I>::MaxSupplyAlreadySet
-			// );
+			ensure!(
+				!CollectionMaxSupply::<T, I>::contains_key(&collection),
+				Error::<T, I>::MaxSupplyAlreadySet
+			);
 
-			// let details =
-			// 	Collection::<T, I>::get(&collection).ok_or(Error::<T, I>::UnknownCollection)?;
-			// if let Some(check_owner) = &maybe_check_owner {
-			// 	ensure!(check_owner == &details.owner, Error::<T, I>::NoPermission);
-			// }
+			let details =
+				Collection::<T, I>::get(&collection).ok_or(Error::<T, I>::UnknownCollection)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				// The owner of an omniverse collection is the owner of its token
+				let owner = match CollectionId2TokenId::<T, I>::get(&collection) {
+					Some(token_id) =>
+						TokensInfo::<T, I>::get(&token_id)
+							.ok_or(Error::<T, I>::UnknownCollection)?
+							.owner,
+					None => details.owner,
+				};
+				ensure!(check_owner == &owner, Error::<T, I>::NoPermission);
+			}
 
-			// ensure!(details.items <= max_supply, Error::<T, I>::MaxSupplyTooSmall);
+			ensure!(details.items <= max_supply, Error::<T, I>::MaxSupplyTooSmall);
 
-			// CollectionMaxSupply::<T, I>::insert(&collection, max_supply);
-			// Self::deposit_event(Event::CollectionMaxSupplySet { collection, max_supply });
-			// Ok(())
-			Err(Error::<T, I>::Unsupport.into())
+			CollectionMaxSupply::<T, I>::insert(&collection, max_supply);
+			Self::deposit_event(Event::CollectionMaxSupplySet { collection, max_supply });
+			Ok(())
 		}
 
 		/// Set (or reset) the price for an item.
@@ -1800,32 +1926,50 @@ pub mod pallet {
 		pub fn trigger_execution(origin: OriginFor<T>) -> DispatchResult {
 			ensure_signed(origin)?;
 
+			Self::execute_next_delayed_transaction()
+		}
+
+		/// Execute up to `max_count` delayed transactions in order, stopping at the first one
+		/// that is not executable yet. Fails only if the first one can not be executed.
+		#[pallet::weight(0)]
+		pub fn trigger_execution_batch(origin: OriginFor<T>, max_count: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			for i in 0..max_count.max(1) {
+				let result = frame_support::storage::with_storage_layer(|| {
+					Self::execute_next_delayed_transaction()
+				});
+				if let Err(e) = result {
+					if i == 0 {
+						return Err(e);
+					}
+					break;
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Skip the delayed transaction at the head of the queue without executing it, for a
+		/// transaction that keeps failing and blocks the ones after it.
+		///
+		/// Origin must be ForceOrigin.
+		#[pallet::weight(0)]
+		pub fn skip_delayed(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
 			let (delayed_executing_index, delayed_index) = DelayedIndex::<T, I>::get();
 			ensure!(delayed_executing_index < delayed_index, Error::<T, I>::NoDelayedTx);
 			let delayed_tx = DelayedTransactions::<T, I>::get(delayed_executing_index)
 				.ok_or(Error::<T, I>::DelayedTxNotExisted)?;
-			let omni_tx = T::OmniverseProtocol::get_transaction_data(
-				delayed_tx.sender,
-				PALLET_NAME.to_vec(),
-				delayed_tx.token_id.clone(),
-				delayed_tx.nonce,
-			)
-			.ok_or(Error::<T, I>::TxNotExisted)?;
-
-			let token = TokensInfo::<T, I>::get(&delayed_tx.token_id)
-				.ok_or(Error::<T, I>::UnknownCollection)?;
-			let cur_st = T::Timestamp::now().as_secs();
-			ensure!(cur_st >= omni_tx.timestamp + token.cooldown_time, Error::<T, I>::NotExecutable);
-
 			DelayedIndex::<T, I>::set((delayed_executing_index + 1, delayed_index));
 
-			Self::execute_transaction(&delayed_tx.token_id, &omni_tx.tx_data)?;
-			Self::deposit_event(Event::TransactionExecuted {
+			Self::deposit_event(Event::DelayedTransactionSkipped {
+				index: delayed_executing_index,
 				pk: delayed_tx.sender,
 				nonce: delayed_tx.nonce,
 				token_id: delayed_tx.token_id,
 			});
-
 			Ok(())
 		}
 
@@ -1842,6 +1986,7 @@ pub mod pallet {
 				TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::UnknownCollection)?;
 
 			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+			ensure!(members.len() <= T::MaxMembers::get() as usize, Error::<T, I>::TooManyMembers);
 
 			token.add_members(members.clone());
 
@@ -1856,6 +2001,36 @@ pub mod pallet {
 			Ok(())
 		}
 
+		#[pallet::weight(0)]
+		pub fn remove_members(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			members: Vec<(u32, Vec<u8>)>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let mut token =
+				TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::UnknownCollection)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			token.remove_members(&members);
+
+			for member in members.iter() {
+				// The member may have been set for another token since
+				if TokenIdofMember::<T, I>::get(member).as_ref() == Some(&token_id) {
+					TokenIdofMember::<T, I>::remove(member);
+				}
+			}
+			// Update storage
+			TokensInfo::<T, I>::insert(&token_id, token);
+
+			Self::deposit_event(Event::MembersRemoved { token_id, members });
+
+			Ok(())
+		}
+
 		#[pallet::weight(0)]
 		pub fn set_cooldown_time(
 			origin: OriginFor<T>,
@@ -1879,5 +2054,85 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Transfer the ownership of the token to `new_owner_pk`, the collection of the token and
+		/// its deposit go along with it
+		#[pallet::weight(0)]
+		pub fn transfer_token_ownership(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			new_owner_pk: [u8; 64],
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let mut token =
+				TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::UnknownCollection)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+
+			let new_owner = Self::to_account(&new_owner_pk)?;
+			let collection = TokenId2CollectionId::<T, I>::get(&token_id)
+				.ok_or(Error::<T, I>::UnknownCollection)?;
+			Collection::<T, I>::try_mutate(collection, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T, I>::UnknownCollection)?;
+				T::Currency::repatriate_reserved(
+					&details.owner,
+					&new_owner,
+					details.total_deposit,
+					Reserved,
+				)?;
+				CollectionAccount::<T, I>::remove(&details.owner, &collection);
+				CollectionAccount::<T, I>::insert(&new_owner, &collection, ());
+				details.owner = new_owner.clone();
+				details.issuer = new_owner.clone();
+				details.admin = new_owner.clone();
+				details.freezer = new_owner.clone();
+				Ok(())
+			})?;
+
+			token.owner = new_owner.clone();
+			token.owner_pk = new_owner_pk;
+			// Update storage
+			TokensInfo::<T, I>::insert(&token_id, token);
+
+			Self::deposit_event(Event::TokenOwnershipTransferred {
+				token_id,
+				owner: new_owner,
+				owner_pk: new_owner_pk,
+			});
+
+			Ok(())
+		}
+
+		/// Set the royalty paid to `beneficiary` on transfers carrying a sale price, `bps` of 0
+		/// removes it
+		#[pallet::weight(0)]
+		pub fn set_royalty(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			bps: u16,
+			beneficiary: [u8; 64],
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// Check if the token exists.
+			let token =
+				TokensInfo::<T, I>::get(&token_id).ok_or(Error::<T, I>::UnknownCollection)?;
+
+			ensure!(token.owner == sender, Error::<T, I>::NoPermission);
+			ensure!(bps <= 10_000, Error::<T, I>::InvalidRoyalty);
+			Self::to_account(&beneficiary)?;
+
+			if bps == 0 {
+				Royalties::<T, I>::remove(&token_id);
+			} else {
+				Royalties::<T, I>::insert(&token_id, (bps, beneficiary));
+			}
+
+			Self::deposit_event(Event::RoyaltySet { token_id, bps, beneficiary });
+
+			Ok(())
+		}
 	}
 }
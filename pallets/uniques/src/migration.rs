@@ -17,7 +17,22 @@
 
 //! Various pieces of common functionality.
 use super::*;
+use codec::Decode;
 use frame_support::traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion};
+use pallet_omniverse_protocol::{traits::OmniverseAccounts, NonFungible};
+
+/// The types as they were encoded before the operation was captured in the queue.
+pub mod v1 {
+	use codec::{Decode, Encode};
+	use sp_std::vec::Vec;
+
+	#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+	pub struct DelayedTx {
+		pub sender: [u8; 64],
+		pub token_id: Vec<u8>,
+		pub nonce: u128,
+	}
+}
 
 /// Migrate the pallet storage to v1.
 pub fn migrate_to_v1<T: Config<I>, I: 'static, P: GetStorageVersion + PalletInfoAccess>(
@@ -52,3 +67,48 @@ pub fn migrate_to_v1<T: Config<I>, I: 'static, P: GetStorageVersion + PalletInfo
 		T::DbWeight::get().reads(1)
 	}
 }
+
+/// Migrate the pallet storage to v2, the queued transactions are translated to carry the
+/// operation and the quantity of the recorded transaction.
+pub fn migrate_to_v2<T: Config<I>, I: 'static, P: GetStorageVersion + PalletInfoAccess>(
+) -> frame_support::weights::Weight {
+	let on_chain_storage_version = <P as GetStorageVersion>::on_chain_storage_version();
+	log::info!(
+		target: "runtime::uniques",
+		"Running migration storage v2 for uniques with storage version {:?}",
+		on_chain_storage_version,
+	);
+
+	if on_chain_storage_version < 2 {
+		let mut count = 0;
+		DelayedTransactions::<T, I>::translate::<v1::DelayedTx, _>(|_, tx| {
+			count += 1;
+			// A transaction whose record is missing or undecodable fails before its operation is
+			// compared, so it is skipped by the queue as it would have been before
+			let (op, quantity) = T::OmniverseProtocol::get_transaction_data(
+				tx.sender,
+				PALLET_NAME.to_vec(),
+				tx.token_id.clone(),
+				tx.nonce,
+			)
+			.and_then(|omni_tx| NonFungible::decode(&mut omni_tx.tx_data.payload.as_slice()).ok())
+			.map_or((0, 0), |nft| (nft.op, nft.token_id));
+			Some(DelayedTx::new(tx.sender, tx.token_id, tx.nonce, op, quantity))
+		});
+		StorageVersion::new(2).put::<P>();
+		log::info!(
+			target: "runtime::uniques",
+			"Running migration storage v2 for uniques with storage version {:?} was complete",
+			on_chain_storage_version,
+		);
+		// calculate and return migration weights
+		T::DbWeight::get().reads_writes(2 * count as u64 + 1, count as u64 + 1)
+	} else {
+		log::warn!(
+			target: "runtime::uniques",
+			"Attempted to apply migration to v2 but failed because storage version is {:?}",
+			on_chain_storage_version,
+		);
+		T::DbWeight::get().reads(1)
+	}
+}
@@ -23,7 +23,7 @@ use std::ops::AddAssign;
 use std::time::{Duration, SystemTime};
 
 use frame_support::{
-	construct_runtime,
+	construct_runtime, parameter_types,
 	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64},
 };
 use sp_core::H256;
@@ -109,11 +109,18 @@ impl Config for Test {
 	type StringLimit = ConstU32<50>;
 	type KeyLimit = ConstU32<50>;
 	type ValueLimit = ConstU32<50>;
+	type MaxDelayedQueue = MaxDelayedQueue;
+	type MaxTokenIdLength = ConstU32<32>;
+	type MaxMembers = ConstU32<4>;
 	type WeightInfo = ();
 	#[cfg(feature = "runtime-benchmarks")]
 	type Helper = ();
 }
 
+parameter_types! {
+	pub static MaxDelayedQueue: u32 = 100;
+}
+
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 
@@ -164,6 +171,7 @@ impl OmniverseAccounts for OmniverseProtocol {
 		_token_id: &[u8],
 		data: &OmniverseTransactionData,
 		_with_ethereum: bool,
+		_relayed: bool,
 	) -> Result<VerifyResult, VerifyError> {
 		if data.signature == [0; 65] {
 			return Err(VerifyError::SignatureError);
@@ -184,6 +192,10 @@ impl OmniverseAccounts for OmniverseProtocol {
 		1
 	}
 
+	fn get_cooling_down_time() -> u64 {
+		0
+	}
+
 	fn get_transaction_data(
 		_pk: [u8; 64],
 		_pallet_name: Vec<u8>,
@@ -18,10 +18,14 @@
 //! Tests for Uniques pallet.
 
 use crate::{mock::*, *};
-use frame_support::{assert_err, assert_ok, traits::Currency};
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	traits::{Currency, GetStorageVersion, StorageVersion},
+};
 use pallet_omniverse_protocol::OmniverseTx;
 use pallet_omniverse_protocol::{
-	traits::OmniverseAccounts, Fungible, OmniverseTransactionData, MINT, TRANSFER,
+	traits::OmniverseAccounts, BatchMintOp, NonFungible, OmniverseTransactionData,
+	TransferWithPriceOp, BATCH_MINT, MINT, SET_METADATA, TRANSFER,
 };
 use secp256k1::rand::rngs::OsRng;
 use secp256k1::{ecdsa::RecoverableSignature, Message, PublicKey, Secp256k1, SecretKey};
@@ -61,6 +65,105 @@ fn basic_setup_works() {
 	});
 }
 
+#[test]
+fn lifecycle_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Uniques::create(RuntimeOrigin::signed(1), 0, 1));
+		assert_eq!(Balances::reserved_balance(&1), 2);
+		assert_noop!(Uniques::create(RuntimeOrigin::signed(1), 0, 1), Error::<Test>::InUse);
+
+		assert_ok!(Uniques::mint(RuntimeOrigin::signed(1), 0, 42, 10));
+		assert_ok!(Uniques::mint(RuntimeOrigin::signed(1), 0, 69, 20));
+		assert_eq!(Balances::reserved_balance(&1), 4);
+		assert_eq!(items(), vec![(10, 0, 42), (20, 0, 69)]);
+
+		let w = Collection::<Test>::get(0).unwrap().destroy_witness();
+		assert_noop!(Uniques::destroy(RuntimeOrigin::signed(2), 0, w), Error::<Test>::NoPermission);
+		assert_ok!(Uniques::destroy(RuntimeOrigin::signed(1), 0, w));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+
+		assert!(!Collection::<Test>::contains_key(0));
+		assert!(!Item::<Test>::contains_key(0, 42));
+		assert!(!Item::<Test>::contains_key(0, 69));
+		assert_eq!(items(), vec![]);
+	});
+}
+
+#[test]
+fn local_mint_transfer_burn_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Uniques::create(RuntimeOrigin::signed(1), 0, 1));
+
+		// Only the issuer mints
+		assert_noop!(
+			Uniques::mint(RuntimeOrigin::signed(2), 0, 42, 2),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Uniques::mint(RuntimeOrigin::signed(1), 0, 42, 2));
+		assert_noop!(
+			Uniques::mint(RuntimeOrigin::signed(1), 0, 42, 2),
+			Error::<Test>::AlreadyExists
+		);
+		assert_eq!(items(), vec![(2, 0, 42)]);
+
+		// Only the owner, the admin or the delegate transfers
+		assert_noop!(
+			Uniques::transfer(RuntimeOrigin::signed(3), 0, 42, 3),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Uniques::transfer(RuntimeOrigin::signed(2), 0, 42, 3));
+		assert_eq!(items(), vec![(3, 0, 42)]);
+		assert_ok!(Uniques::transfer(RuntimeOrigin::signed(1), 0, 42, 4));
+		assert_eq!(items(), vec![(4, 0, 42)]);
+
+		// Only the owner or the admin burns
+		assert_noop!(
+			Uniques::burn(RuntimeOrigin::signed(3), 0, 42, None),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Uniques::burn(RuntimeOrigin::signed(4), 0, 42, Some(3)),
+			Error::<Test>::WrongOwner
+		);
+		assert_ok!(Uniques::burn(RuntimeOrigin::signed(4), 0, 42, Some(4)));
+		assert_eq!(items(), vec![]);
+		assert_eq!(Balances::reserved_balance(&1), 2);
+	});
+}
+
+#[test]
+fn destroy_omniverse_collection_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(RuntimeOrigin::signed(1), pk, TOKEN_ID, None, None));
+
+		// Local collections coexist with the ones of omniverse tokens
+		let collection = TokenId2CollectionId::<Test>::get(TOKEN_ID).unwrap();
+		Balances::make_free_balance_be(&1, 100);
+		assert_noop!(
+			Uniques::create(RuntimeOrigin::signed(1), collection, 1),
+			Error::<Test>::InUse
+		);
+		assert_ok!(Uniques::create(RuntimeOrigin::signed(1), collection + 1, 1));
+
+		let w = Collection::<Test>::get(collection).unwrap().destroy_witness();
+		assert_noop!(
+			Uniques::destroy(RuntimeOrigin::signed(account), collection, w),
+			Error::<Test>::InUse
+		);
+		assert_noop!(
+			Uniques::mint(RuntimeOrigin::signed(account), collection, 1, account),
+			Error::<Test>::Unsupport
+		);
+	});
+}
+
 // tests of omniverse tokens
 const CHAIN_ID: u32 = 1;
 const TOKEN_ID: Vec<u8> = Vec::<u8>::new();
@@ -94,10 +197,33 @@ fn encode_transfer(
 ) -> OmniverseTransactionData {
 	let pk_from: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
 	let pk_to: [u8; 64] = to.serialize_uncompressed()[1..].try_into().expect("");
-	let payload = Fungible::new(TRANSFER, pk_to.into(), amount).encode();
+	let payload = NonFungible::new(TRANSFER, pk_to.into(), amount).encode();
+	let mut tx_data =
+		OmniverseTransactionData::new(nonce, CHAIN_ID, INITIATOR_ADDRESS, pk_from, payload);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
+	let message = Message::from_slice(h.as_slice())
+		.expect("messages must be 32 bytes and are expected to be hashes");
+	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
+	let sig_recovery = get_sig_slice(&sig);
+	tx_data.set_signature(sig_recovery);
+	tx_data
+}
+
+fn encode_transfer_with_price(
+	secp: &Secp256k1<secp256k1::All>,
+	from: (SecretKey, PublicKey),
+	to: PublicKey,
+	item_id: u128,
+	price: u128,
+	nonce: u128,
+) -> OmniverseTransactionData {
+	let pk_from: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
+	let pk_to: [u8; 64] = to.serialize_uncompressed()[1..].try_into().expect("");
+	let ex_data = TransferWithPriceOp::new(pk_to, price).encode();
+	let payload = NonFungible::new(TRANSFER, ex_data, item_id).encode();
 	let mut tx_data =
 		OmniverseTransactionData::new(nonce, CHAIN_ID, INITIATOR_ADDRESS, pk_from, payload);
-	let h = tx_data.get_raw_hash(false);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -115,9 +241,50 @@ fn encode_mint(
 ) -> OmniverseTransactionData {
 	let pk_from: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
 	let pk_to: [u8; 64] = to.serialize_uncompressed()[1..].try_into().expect("");
-	let payload = Fungible::new(MINT, pk_to.into(), amount).encode();
+	let payload = NonFungible::new(MINT, pk_to.into(), amount).encode();
+	let mut tx_data = OmniverseTransactionData::new(nonce, CHAIN_ID, TOKEN_ID, pk_from, payload);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
+	let message = Message::from_slice(h.as_slice())
+		.expect("messages must be 32 bytes and are expected to be hashes");
+	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
+	let sig_recovery = get_sig_slice(&sig);
+	tx_data.set_signature(sig_recovery);
+	tx_data
+}
+
+fn encode_batch_mint(
+	secp: &Secp256k1<secp256k1::All>,
+	from: (SecretKey, PublicKey),
+	to: PublicKey,
+	item_ids: Vec<u128>,
+	nonce: u128,
+) -> OmniverseTransactionData {
+	let pk_from: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
+	let pk_to: [u8; 64] = to.serialize_uncompressed()[1..].try_into().expect("");
+	let quantity = item_ids.len() as u128;
+	let ex_data = BatchMintOp::new(pk_to, item_ids).encode();
+	let payload = NonFungible::new(BATCH_MINT, ex_data, quantity).encode();
+	let mut tx_data = OmniverseTransactionData::new(nonce, CHAIN_ID, TOKEN_ID, pk_from, payload);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
+	let message = Message::from_slice(h.as_slice())
+		.expect("messages must be 32 bytes and are expected to be hashes");
+	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
+	let sig_recovery = get_sig_slice(&sig);
+	tx_data.set_signature(sig_recovery);
+	tx_data
+}
+
+fn encode_set_metadata(
+	secp: &Secp256k1<secp256k1::All>,
+	from: (SecretKey, PublicKey),
+	item_id: u128,
+	metadata: Vec<u8>,
+	nonce: u128,
+) -> OmniverseTransactionData {
+	let pk_from: [u8; 64] = from.1.serialize_uncompressed()[1..].try_into().expect("");
+	let payload = NonFungible::new(SET_METADATA, metadata, item_id).encode();
 	let mut tx_data = OmniverseTransactionData::new(nonce, CHAIN_ID, TOKEN_ID, pk_from, payload);
-	let h = tx_data.get_raw_hash(false);
+	let h = tx_data.get_raw_hash(&TOKEN_ID, false);
 	let message = Message::from_slice(h.as_slice())
 		.expect("messages must be 32 bytes and are expected to be hashes");
 	let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &from.0);
@@ -165,6 +332,40 @@ fn create_token_with_token_already_exist_not_work() {
 	});
 }
 
+#[test]
+fn create_token_beyond_the_bounds_not_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		let members: Vec<(u32, Vec<u8>)> = (0..5).map(|i| (i, vec![i as u8])).collect();
+		assert_noop!(
+			Uniques::create_token(RuntimeOrigin::signed(1), pk, vec![1; 33], None, None),
+			Error::<Test>::TokenIdTooLong
+		);
+		assert_noop!(
+			Uniques::create_token(
+				RuntimeOrigin::signed(1),
+				pk,
+				vec![1],
+				Some(members.clone()),
+				None
+			),
+			Error::<Test>::TooManyMembers
+		);
+
+		assert_ok!(Uniques::create_token(RuntimeOrigin::signed(1), pk, vec![1; 32], None, None));
+		assert_noop!(
+			Uniques::set_members(RuntimeOrigin::signed(account), vec![1; 32], members),
+			Error::<Test>::TooManyMembers
+		);
+	});
+}
+
 #[test]
 fn set_members_with_token_not_exist_not_work() {
 	new_test_ext().execute_with(|| {
@@ -175,6 +376,112 @@ fn set_members_with_token_not_exist_not_work() {
 	});
 }
 
+#[test]
+fn remove_members_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(RuntimeOrigin::signed(1), pk, vec![1], None, None));
+
+		let members = vec![(1, vec![1]), (2, vec![2]), (3, vec![3])];
+		assert_ok!(Uniques::set_members(RuntimeOrigin::signed(account), vec![1], members.clone()));
+		assert_noop!(
+			Uniques::remove_members(RuntimeOrigin::signed(1), vec![1], vec![(2, vec![2])]),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Uniques::remove_members(
+			RuntimeOrigin::signed(account),
+			vec![1],
+			vec![(2, vec![2])]
+		));
+
+		let token = Uniques::tokens_info(vec![1]).unwrap();
+		assert_eq!(token.members, vec![(1, vec![1]), (3, vec![3])]);
+		assert_eq!(Uniques::token_id_of_member((1, vec![1])), Some(vec![1]));
+		assert_eq!(Uniques::token_id_of_member((2, vec![2])), None);
+		assert_eq!(Uniques::token_id_of_member((3, vec![3])), Some(vec![1]));
+	});
+}
+
+#[test]
+fn transfer_token_ownership_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pairs
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let (new_secret_key, new_public_key) = secp.generate_keypair(&mut OsRng);
+		let new_pk: [u8; 64] = new_public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		let new_account = get_account_id_from_pk(new_public_key.serialize().as_slice());
+		fund_account(account);
+		fund_account(new_account);
+		assert_ok!(Uniques::create_token(RuntimeOrigin::signed(1), pk, TOKEN_ID, None, None));
+
+		assert_noop!(
+			Uniques::transfer_token_ownership(RuntimeOrigin::signed(new_account), TOKEN_ID, new_pk),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Uniques::transfer_token_ownership(
+			RuntimeOrigin::signed(account),
+			TOKEN_ID,
+			new_pk
+		));
+		System::assert_last_event(RuntimeEvent::Uniques(Event::TokenOwnershipTransferred {
+			token_id: TOKEN_ID,
+			owner: new_account,
+			owner_pk: new_pk,
+		}));
+		let token = Uniques::tokens_info(TOKEN_ID).unwrap();
+		assert_eq!((token.owner, token.owner_pk), (new_account, new_pk));
+		assert_eq!(Balances::reserved_balance(new_account), 2);
+
+		// Only the new owner manages the token and mints it
+		assert_noop!(
+			Uniques::set_members(RuntimeOrigin::signed(account), TOKEN_ID, vec![(1, vec![1])]),
+			Error::<Test>::NoPermission
+		);
+		let nonce =
+			OmniverseProtocol::get_transaction_count(new_pk, PALLET_NAME.to_vec(), Vec::new());
+		let data = encode_mint(&secp, (new_secret_key, new_public_key), new_public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+	});
+}
+
+#[test]
+fn token_members_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (_, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_eq!(Uniques::token_members(vec![1]), None);
+		assert!(!Uniques::is_token_member(vec![1], 1, vec![1]));
+
+		assert_ok!(Uniques::create_token(RuntimeOrigin::signed(1), pk, vec![1], None, None));
+		assert_eq!(Uniques::token_members(vec![1]), Some(vec![]));
+
+		let members = vec![(1, vec![1]), (2, vec![2])];
+		assert_ok!(Uniques::set_members(RuntimeOrigin::signed(account), vec![1], members.clone()));
+		assert_eq!(Uniques::token_members(vec![1]), Some(members));
+		assert!(Uniques::is_token_member(vec![1], 1, vec![1]));
+		assert!(Uniques::is_token_member(vec![1], 2, vec![2]));
+		// Both the chain and the address must match
+		assert!(!Uniques::is_token_member(vec![1], 1, vec![2]));
+		assert!(!Uniques::is_token_member(vec![1], 3, vec![3]));
+		assert!(!Uniques::is_token_member(vec![2], 1, vec![1]));
+	});
+}
+
 #[test]
 fn transfer_item_not_exist_not_work() {
 	new_test_ext().execute_with(|| {
@@ -320,7 +627,7 @@ fn mint_item_should_work() {
 }
 
 #[test]
-fn not_item_owner_transfer_should_not_work() {
+fn mint_item_with_delayed_queue_full_not_work() {
 	new_test_ext().execute_with(|| {
 		let secp = Secp256k1::new();
 		// Generate key pair
@@ -333,7 +640,6 @@ fn not_item_owner_transfer_should_not_work() {
 		// Create token
 		let account = get_account_id_from_pk(public_key.serialize().as_slice());
 		fund_account(account);
-
 		assert_ok!(Uniques::create_token(
 			RuntimeOrigin::signed(1),
 			pk,
@@ -342,33 +648,33 @@ fn not_item_owner_transfer_should_not_work() {
 			None
 		));
 
-		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
-		let to = get_account_id_from_pk(public_key_to.serialize().as_slice());
-		fund_account(to);
-
-		// Mint token
-		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
-		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
-
+		// Fill the queue up to the cap
+		MaxDelayedQueue::set(2);
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
 		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-			mint_data,
+			data,
 			Timestamp::now().as_secs(),
 		)));
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 2, nonce + 1);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
 
-		// Delay
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 3, nonce + 2);
+		assert_noop!(
+			Uniques::send_transaction(RuntimeOrigin::signed(1), TOKEN_ID, data.clone()),
+			Error::<Test>::DelayedQueueFull
+		);
+
+		// Executing a transaction frees a slot
 		Timestamp::past(COOL_DOWN);
 		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
-
-		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 10, nonce);
-		assert_err!(
-			Uniques::send_transaction_external(TOKEN_ID, &data),
-			Error::<Test>::UnknownCollection
-		);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+		assert_eq!(Uniques::delayed_index(), (1, 3));
 	});
 }
 
 #[test]
-fn transfer_item_should_work() {
+fn skip_delayed_should_work() {
 	new_test_ext().execute_with(|| {
 		let secp = Secp256k1::new();
 		// Generate key pair
@@ -389,23 +695,82 @@ fn transfer_item_should_work() {
 			None
 		));
 
-		// Mint token
-		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
-		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 2, nonce + 1);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
 
+		// The head does not match the record, it blocks the queue
 		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
-			mint_data,
+			data,
 			Timestamp::now().as_secs(),
 		)));
-
-		// Delay
 		Timestamp::past(COOL_DOWN);
+		assert_noop!(
+			Uniques::trigger_execution(RuntimeOrigin::signed(1)),
+			Error::<Test>::DelayedTxMismatch
+		);
+
+		assert_noop!(
+			Uniques::skip_delayed(RuntimeOrigin::signed(1)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Uniques::skip_delayed(RuntimeOrigin::root()));
+		System::assert_last_event(RuntimeEvent::Uniques(Event::DelayedTransactionSkipped {
+			index: 0,
+			pk,
+			nonce,
+			token_id: TOKEN_ID,
+		}));
+
+		// The queue is unblocked
 		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(Uniques::delayed_index(), (2, 2));
+		assert_eq!(Uniques::tokens(TOKEN_ID, pk), Some(vec![2]));
+		assert_noop!(Uniques::skip_delayed(RuntimeOrigin::root()), Error::<Test>::NoDelayedTx);
+	});
+}
+
+#[test]
+fn mint_item_zero_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
 
 		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
 		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
 		fund_account(account_to);
-		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 1, nonce);
+
+		// Item id out of the range of `ItemId`
+		let data = encode_mint(
+			&secp,
+			(secret_key, public_key),
+			public_key_to,
+			u32::MAX as u128 + 1,
+			nonce,
+		);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::InvalidItemId
+		);
+
+		let data = encode_mint(&secp, (secret_key, public_key), public_key_to, 0, nonce);
 		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
 
 		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
@@ -417,8 +782,522 @@ fn transfer_item_should_work() {
 		Timestamp::past(COOL_DOWN);
 		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
 
-		assert_eq!(Uniques::tokens(TOKEN_ID, &pk), Some(vec![]));
 		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
-		assert_eq!(Uniques::tokens(TOKEN_ID, &pk_to), Some(vec![1]));
+		let token = Uniques::tokens(TOKEN_ID, pk_to);
+		assert_eq!(token, Some(vec![0]));
+
+		// Item 0 now exists
+		let data = encode_mint(&secp, (secret_key, public_key), public_key_to, 0, nonce + 1);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::AlreadyExists
+		);
+	});
+}
+
+#[test]
+fn set_metadata_through_omniverse_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		let (secret_key_to, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_mint(&secp, (secret_key, public_key), public_key_to, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// Neither the owner nor the issuer
+		let (secret_key_other, public_key_other) = secp.generate_keypair(&mut OsRng);
+		let account_other = get_account_id_from_pk(public_key_other.serialize().as_slice());
+		fund_account(account_other);
+		let data = encode_set_metadata(
+			&secp,
+			(secret_key_other, public_key_other),
+			1,
+			b"metadata".to_vec(),
+			0,
+		);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::NoPermission
+		);
+
+		// Data exceeding the string limit
+		let data = encode_set_metadata(&secp, (secret_key_to, public_key_to), 1, vec![0; 51], 0);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::BadMetadata
+		);
+
+		// Set by the owner of the item
+		let data =
+			encode_set_metadata(&secp, (secret_key_to, public_key_to), 1, b"metadata".to_vec(), 0);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let collection = TokenId2CollectionId::<Test>::get(TOKEN_ID).unwrap();
+		let metadata = ItemMetadataOf::<Test>::get(collection, 1).unwrap();
+		assert_eq!(metadata.data.to_vec(), b"metadata".to_vec());
+	});
+}
+
+#[test]
+fn batch_mint_items_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+		let collection = Uniques::token_id_2_asset_id(TOKEN_ID).unwrap();
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+
+		// The whole batch must fit in the max supply
+		CollectionMaxSupply::<Test>::insert(collection, 4);
+		let data = encode_batch_mint(
+			&secp,
+			(secret_key, public_key),
+			public_key_to,
+			vec![1, 2, 3, 4, 5],
+			nonce,
+		);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::MaxSupplyReached
+		);
+		let data =
+			encode_batch_mint(&secp, (secret_key, public_key), public_key_to, vec![1, 2, 2], nonce);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::AlreadyExists
+		);
+
+		CollectionMaxSupply::<Test>::insert(collection, 5);
+		let data = encode_batch_mint(
+			&secp,
+			(secret_key, public_key),
+			public_key_to,
+			vec![1, 2, 3, 4, 5],
+			nonce,
+		);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Uniques::tokens(TOKEN_ID, pk_to), Some(vec![1, 2, 3, 4, 5]));
+		for item in 1..=5 {
+			assert_eq!(Item::<Test>::get(collection, item).unwrap().owner, account_to);
+		}
+		assert_eq!(Collection::<Test>::get(collection).unwrap().items, 5);
+	});
+}
+
+#[test]
+fn not_item_owner_transfer_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(to);
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 10, nonce);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::UnknownCollection
+		);
+	});
+}
+
+#[test]
+fn transfer_item_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		assert_eq!(Uniques::tokens(TOKEN_ID, &pk), Some(vec![]));
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Uniques::tokens(TOKEN_ID, &pk_to), Some(vec![1]));
+	});
+}
+
+#[test]
+fn transfer_item_not_owned_fails_on_execution() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// Both transfers of the item are sent while it is still owned
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let (_, public_key_other) = secp.generate_keypair(&mut OsRng);
+		let data = encode_transfer(&secp, (secret_key, public_key), public_key_to, 1, nonce + 1);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+		let data_other =
+			encode_transfer(&secp, (secret_key, public_key), public_key_other, 1, nonce + 2);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data_other));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// The second transfer fails and is skipped
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data_other,
+			Timestamp::now().as_secs(),
+		)));
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+		System::assert_last_event(RuntimeEvent::Uniques(Event::TransactionExecutionFailed {
+			pk,
+			nonce: nonce + 2,
+			token_id: TOKEN_ID,
+			reason: Error::<Test>::NoPermission.into(),
+		}));
+		assert_eq!(Uniques::delayed_index(), (3, 3));
+
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		let pk_other: [u8; 64] =
+			public_key_other.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Uniques::tokens(TOKEN_ID, &pk_to), Some(vec![1]));
+		assert_eq!(Uniques::tokens(TOKEN_ID, &pk_other), None);
+	});
+}
+
+#[test]
+fn transfer_item_with_royalty_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+
+		// Set royalty
+		let (_, public_key_beneficiary) = secp.generate_keypair(&mut OsRng);
+		let pk_beneficiary: [u8; 64] =
+			public_key_beneficiary.serialize_uncompressed()[1..].try_into().expect("");
+		let account_beneficiary =
+			get_account_id_from_pk(public_key_beneficiary.serialize().as_slice());
+		fund_account(account_beneficiary);
+		assert_noop!(
+			Uniques::set_royalty(RuntimeOrigin::signed(1), TOKEN_ID, 500, pk_beneficiary),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Uniques::set_royalty(RuntimeOrigin::signed(account), TOKEN_ID, 10_001, pk_beneficiary),
+			Error::<Test>::InvalidRoyalty
+		);
+		assert_ok!(Uniques::set_royalty(
+			RuntimeOrigin::signed(account),
+			TOKEN_ID,
+			500,
+			pk_beneficiary
+		));
+		assert_eq!(Uniques::royalty_of(TOKEN_ID), Some((500, pk_beneficiary)));
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 1, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// Transfer with a sale price of 400
+		let (_, public_key_to) = secp.generate_keypair(&mut OsRng);
+		let account_to = get_account_id_from_pk(public_key_to.serialize().as_slice());
+		fund_account(account_to);
+		let data = encode_transfer_with_price(
+			&secp,
+			(secret_key, public_key),
+			public_key_to,
+			1,
+			400,
+			nonce,
+		);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &data));
+
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		let balance = Balances::free_balance(account);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+
+		// 5% of the sale price goes to the beneficiary
+		assert_eq!(Balances::free_balance(account), balance - 20);
+		assert_eq!(Balances::free_balance(account_beneficiary), 1020);
+		let pk_to: [u8; 64] = public_key_to.serialize_uncompressed()[1..].try_into().expect("");
+		assert_eq!(Uniques::tokens(TOKEN_ID, &pk_to), Some(vec![1]));
+	});
+}
+
+#[test]
+fn set_collection_max_supply_should_work() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+		// Get nonce
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+		let nonce = OmniverseProtocol::get_transaction_count(pk, PALLET_NAME.to_vec(), Vec::new());
+
+		// Create token
+		let account = get_account_id_from_pk(public_key.serialize().as_slice());
+		fund_account(account);
+		assert_ok!(Uniques::create_token(
+			RuntimeOrigin::signed(1),
+			pk,
+			TOKEN_ID,
+			Some(Vec::<(u32, Vec<u8>)>::new()),
+			None
+		));
+		let collection = Uniques::token_id_2_asset_id(TOKEN_ID).unwrap();
+
+		// Only the owner of the token can cap the collection
+		assert_noop!(
+			Uniques::set_collection_max_supply(RuntimeOrigin::signed(1), collection, 1),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Uniques::set_collection_max_supply(
+			RuntimeOrigin::signed(account),
+			collection,
+			1
+		));
+		assert_eq!(CollectionMaxSupply::<Test>::get(collection), Some(1));
+		assert_noop!(
+			Uniques::set_collection_max_supply(RuntimeOrigin::signed(account), collection, 2),
+			Error::<Test>::MaxSupplyAlreadySet
+		);
+
+		// Mint token
+		let mint_data = encode_mint(&secp, (secret_key, public_key), public_key, 100, nonce);
+		assert_ok!(Uniques::send_transaction_external(TOKEN_ID, &mint_data));
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(
+			mint_data,
+			Timestamp::now().as_secs(),
+		)));
+
+		// Delay
+		Timestamp::past(COOL_DOWN);
+		assert_ok!(Uniques::trigger_execution(RuntimeOrigin::signed(1)));
+		assert_eq!(Uniques::owner(collection, 100), Some(account));
+
+		// Mint past the max supply
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 101, nonce + 1);
+		assert_err!(
+			Uniques::send_transaction_external(TOKEN_ID, &data),
+			Error::<Test>::MaxSupplyReached
+		);
+	});
+}
+
+#[test]
+fn it_works_for_migrating_delayed_transactions_to_v2() {
+	new_test_ext().execute_with(|| {
+		let secp = Secp256k1::new();
+		// Generate key pair
+		let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+		let pk: [u8; 64] = public_key.serialize_uncompressed()[1..].try_into().expect("");
+
+		// A transaction queued before its operation was captured
+		let data = encode_mint(&secp, (secret_key, public_key), public_key, 100, 0);
+		OmniverseProtocol::set_transaction_data(Some(OmniverseTx::new(data, 100)));
+		let old = crate::migration::v1::DelayedTx { sender: pk, token_id: TOKEN_ID, nonce: 0 };
+		frame_support::storage::unhashed::put(
+			&DelayedTransactions::<Test>::hashed_key_for(0),
+			&old,
+		);
+		StorageVersion::new(1).put::<Uniques>();
+
+		crate::migration::migrate_to_v2::<Test, (), Uniques>();
+		assert_eq!(
+			Uniques::delayed_transctions(0),
+			Some(DelayedTx::new(pk, TOKEN_ID, 0, MINT, 100))
+		);
+		assert_eq!(Uniques::on_chain_storage_version(), 2);
 	});
 }
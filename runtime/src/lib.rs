@@ -51,6 +51,7 @@ pub use pallet_template;
 
 /// Import the omniverse protocol pallet.
 pub use pallet_omniverse_protocol;
+use pallet_omniverse_protocol::traits::OmniverseAccounts;
 
 pub use pallet_omniverse_swap;
 
@@ -276,6 +277,10 @@ parameter_types! {
 	pub const AssetAccountDeposit: Balance = UNITS;
 	pub const ApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
 	pub const StringLimit: u32 = 256;
+	pub const MaxPayloadSize: u32 = 256;
+	pub const MaxDelayedQueue: u32 = 1024;
+	pub const MaxTokenIdLength: u32 = 64;
+	pub const MaxMembers: u32 = 32;
 	/// Key = 32 bytes, Value = 36 bytes (32+1+1+1+1)
 	// https://github.com/paritytech/substrate/blob/069917b/frame/assets/src/lib.rs#L257L271
 	pub const MetadataDepositBase: Balance = UNITS;
@@ -306,6 +311,10 @@ impl pallet_assets::Config for Runtime {
 	type MetadataDepositPerByte = MetadataDepositPerByte;
 	type ApprovalDeposit = ApprovalDeposit;
 	type StringLimit = StringLimit;
+	type MaxPayloadSize = MaxPayloadSize;
+	type MaxDelayedQueue = MaxDelayedQueue;
+	type MaxTokenIdLength = MaxTokenIdLength;
+	type MaxMembers = MaxMembers;
 	type Freezer = ();
 	type Extra = ();
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
@@ -330,6 +339,9 @@ impl pallet_uniques::Config for Runtime {
 	type StringLimit = StringLimit;
 	type KeyLimit = KeyLimit;
 	type ValueLimit = ValueLimit;
+	type MaxDelayedQueue = MaxDelayedQueue;
+	type MaxTokenIdLength = MaxTokenIdLength;
+	type MaxMembers = MaxMembers;
 
 	#[cfg(feature = "runtime-benchmarks")]
 	type Helper = ();
@@ -361,13 +373,26 @@ impl pallet_template::Config for Runtime {
 
 parameter_types! {
 	pub ChainId: u8 = 1;
+	pub CoolingDownTime: u64 = 10;
+	pub TransactionRetention: u64 = 30 * 24 * 60 * 60;
+	pub MaliciousPenalty: u128 = 0;
 }
 
 /// Configure the pallet-omniverse-protocol in pallets/omni-protocol.
 impl pallet_omniverse_protocol::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type ChainId = ChainId;
+	type CoolingDownTime = CoolingDownTime;
 	type Timestamp = Timestamp;
+	type TransactionRetention = TransactionRetention;
+	type SlashHandler = ();
+	type MaliciousPenalty = MaliciousPenalty;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxEvilRecords = ConstU32<64>;
+}
+
+parameter_types! {
+	pub DepositExpiry: u64 = 7 * 24 * 60 * 60;
 }
 
 /// Configure the pallet-omniverse-swap in pallets/omni-swap.
@@ -375,6 +400,121 @@ impl pallet_omniverse_swap::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type OmniverseToken = Assets;
 	type OmniverseProtocol = OmniverseProtocol;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type Timestamp = Timestamp;
+	type DepositExpiry = DepositExpiry;
+	type MaxMpcMembers = ConstU32<32>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = OmniverseSwapBenchmarkHelper;
+	type WeightInfo = pallet_omniverse_swap::weights::SubstrateWeight<Runtime>;
+}
+
+/// Creates the tokens of the omni-swap benchmarks and signs their transactions with keys of the
+/// keystore of the benchmark CLI.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct OmniverseSwapBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl OmniverseSwapBenchmarkHelper {
+	const KEY_TYPE: KeyTypeId = KeyTypeId(*b"omni");
+
+	/// The omniverse public key of `public`, which is the uncompressed one
+	fn omniverse_pk(public: &sp_core::ecdsa::Public) -> [u8; 64] {
+		let signature = sp_io::crypto::ecdsa_sign_prehashed(Self::KEY_TYPE, public, &[0; 32])
+			.expect("the key is in the keystore");
+		sp_io::crypto::secp256k1_ecdsa_recover(&signature.0, &[0; 32])
+			.expect("the signature is valid")
+	}
+
+	/// The key owning the tokens created for the benchmarks
+	fn issuer() -> (sp_core::ecdsa::Public, [u8; 64]) {
+		let public = sp_io::crypto::ecdsa_generate(Self::KEY_TYPE, Some(b"//Issuer".to_vec()));
+		(public, Self::omniverse_pk(&public))
+	}
+
+	/// A transaction of `token_id` by the issuer, signed with the nonce it must be sent with next
+	fn transaction(
+		token_id: Vec<u8>,
+		op: u8,
+		to: [u8; 64],
+		amount: u128,
+	) -> pallet_omniverse_protocol::OmniverseTransactionData {
+		use codec::Encode;
+		use pallet_omniverse_protocol::Fungible;
+
+		let (public, pk) = Self::issuer();
+		let nonce = OmniverseProtocol::get_transaction_count(
+			pk,
+			pallet_assets::PALLET_NAME.to_vec(),
+			token_id.clone(),
+		);
+		let payload = Fungible::new(op, to.to_vec(), amount).encode();
+		let mut data = pallet_omniverse_protocol::OmniverseTransactionData::new(
+			nonce,
+			ChainId::get().into(),
+			token_id.clone(),
+			pk,
+			payload,
+		);
+		let hash = data.get_raw_hash(&token_id, false);
+		let signature = sp_io::crypto::ecdsa_sign_prehashed(Self::KEY_TYPE, &public, &hash)
+			.expect("the key is in the keystore");
+		data.set_signature(signature.0);
+		data
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_omniverse_swap::BenchmarkHelper for OmniverseSwapBenchmarkHelper {
+	fn mint(token_id: Vec<u8>, to: [u8; 64], amount: u128) {
+		use frame_support::traits::Currency;
+
+		let (_, pk) = Self::issuer();
+		let owner = OmniverseSwap::to_account(&pk).expect("the key is valid");
+		if Assets::tokens_info(&token_id).is_none() {
+			Balances::make_free_balance_be(&owner, EXISTENTIAL_DEPOSIT * 1_000);
+			// Without cooling down the transactions are executed right away
+			Assets::create_token(
+				RuntimeOrigin::signed(owner.clone()),
+				pk,
+				token_id.clone(),
+				Some(Vec::new()),
+				Some(0),
+			)
+			.expect("the token does not exist");
+		}
+		let data = Self::transaction(token_id.clone(), pallet_omniverse_protocol::MINT, to, amount);
+		Assets::send_transaction(RuntimeOrigin::signed(owner.clone()), token_id, data)
+			.expect("the mint is valid");
+		Assets::trigger_execution(RuntimeOrigin::signed(owner)).expect("the mint is executable");
+	}
+
+	fn deposit_transaction(
+		to: [u8; 64],
+		amount: u128,
+	) -> (Vec<u8>, pallet_omniverse_protocol::OmniverseTransactionData) {
+		let (_, pk) = Self::issuer();
+		let token_id = b"benchmark".to_vec();
+		Self::mint(token_id.clone(), pk, amount);
+		let data =
+			Self::transaction(token_id.clone(), pallet_omniverse_protocol::TRANSFER, to, amount);
+		(token_id, data)
+	}
+
+	fn mpc_signatures(hash: [u8; 32], count: u32) -> Vec<([u8; 64], [u8; 65])> {
+		(0..count)
+			.map(|_| {
+				let public = sp_io::crypto::ecdsa_generate(Self::KEY_TYPE, None);
+				let signature = sp_io::crypto::ecdsa_sign_prehashed(Self::KEY_TYPE, &public, &hash)
+					.expect("the key is in the keystore");
+				(Self::omniverse_pk(&public), signature.0)
+			})
+			.collect()
+	}
+
+	fn advance_time(secs: u64) {
+		pallet_timestamp::Now::<Runtime>::mutate(|now| *now += secs * 1_000);
+	}
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -446,7 +586,6 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
-		[pallet_omniverse_protocol, OmniverseProtocol]
 		[pallet_omniverse_swap, OmniverseSwap]
 	);
 }
@@ -599,6 +738,92 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_omniverse_swap_runtime_api::OmniSwapApi<Block> for Runtime {
+		fn quote_exact_in(
+			trading_pair: Vec<u8>,
+			token_in_is_x: bool,
+			amount_in: u128,
+		) -> Option<u128> {
+			OmniverseSwap::quote_exact_in(trading_pair, token_in_is_x, amount_in)
+		}
+
+		fn quote_exact_out(
+			trading_pair: Vec<u8>,
+			token_out_is_y: bool,
+			amount_out: u128,
+		) -> Option<u128> {
+			OmniverseSwap::quote_exact_out(trading_pair, token_out_is_y, amount_out)
+		}
+
+		fn quote_with_slippage(
+			trading_pair: Vec<u8>,
+			token_in_is_x: bool,
+			amount_in: u128,
+			slippage_bps: u16,
+		) -> Option<u128> {
+			OmniverseSwap::quote_with_slippage(trading_pair, token_in_is_x, amount_in, slippage_bps)
+		}
+
+		fn preview_remove_liquidity(
+			trading_pair: Vec<u8>,
+			liquidity: u128,
+		) -> Option<(u128, u128)> {
+			OmniverseSwap::preview_remove_liquidity(trading_pair, liquidity)
+		}
+
+		fn get_reserves(trading_pair: Vec<u8>) -> Option<(u128, u128)> {
+			OmniverseSwap::get_reserves(trading_pair)
+		}
+
+		fn swap_balance(pk: [u8; 64], token_id: Vec<u8>) -> u128 {
+			OmniverseSwap::swap_balance(pk, token_id)
+		}
+
+		fn pending_deposits(
+			pk: [u8; 64],
+		) -> Vec<(Vec<u8>, u128, pallet_omniverse_protocol::OmniverseTransactionData)> {
+			OmniverseSwap::pending_deposits(pk)
+		}
+
+		fn pending_withdrawals(pk: [u8; 64]) -> Vec<(Vec<u8>, u128)> {
+			OmniverseSwap::pending_withdrawals(pk)
+		}
+	}
+
+	impl pallet_assets_runtime_api::OmniverseAssetsApi<Block> for Runtime {
+		fn token_balance(token_id: Vec<u8>, pk: [u8; 64]) -> u128 {
+			Assets::token_balance(token_id, pk)
+		}
+
+		fn token_metadata(token_id: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>, u8)> {
+			Assets::token_metadata(token_id)
+		}
+
+		fn pending_delayed() -> Vec<(u32, pallet_assets::DelayedTx, u64)> {
+			Assets::pending_delayed()
+		}
+	}
+
+	impl pallet_omniverse_protocol_runtime_api::OmniverseProtocolApi<Block> for Runtime {
+		fn next_nonce(pk: [u8; 64], pallet_name: Vec<u8>, token_id: Vec<u8>) -> u128 {
+			<OmniverseProtocol as OmniverseAccounts>::next_nonce(pk, pallet_name, token_id)
+		}
+
+		fn missing_nonces(
+			pk: [u8; 64],
+			pallet_name: Vec<u8>,
+			token_id: Vec<u8>,
+			nonce: u128,
+		) -> Option<(u128, u128)> {
+			<OmniverseProtocol as OmniverseAccounts>::missing_nonces(
+				pk,
+				pallet_name,
+				token_id,
+				nonce,
+			)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (